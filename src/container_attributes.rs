@@ -1,13 +1,18 @@
-use serde::de::{DeserializeOwned, Error};
-use serde::{Deserialize, Deserializer};
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+
+use serde::de::{DeserializeOwned, DeserializeSeed, Error, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Deserializes a struct without checking for the fields case sensititivity.
 ///
 /// # **Notes**
 ///
 /// - The following deserializer is incompatible with serde's one. If you wish
-/// to use `serde(rename)`, there is a high risk it won't work. Please see
-/// https://github.com/vityafx/serde-aux/issues/8 for further information.
+///   to use `serde(rename)`, there is a high risk it won't work. Please see
+///   <https://github.com/vityafx/serde-aux/issues/8> for further information.
 ///
 /// # Example:
 ///
@@ -37,7 +42,7 @@ where
 {
     use serde_json::Value;
 
-    use std::collections::BTreeMap as Map;
+    use alloc::collections::BTreeMap as Map;
 
     let map = Map::<String, Value>::deserialize(deserializer)?;
     let lower = map
@@ -47,6 +52,608 @@ where
     T::deserialize(Value::Object(lower)).map_err(Error::custom)
 }
 
+/// Deserializes a struct from a flat map with the dotted keys, rebuilding the nested structure
+/// first: `{ "db.host": "x", "db.port": 5432 }` is read as `{ "db": { "host": "x", "port": 5432 } }`.
+/// Only the keys of the top level map are split. A key which is also a prefix of another one
+/// (like `db` together with `db.host`) is an error.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct Db {
+///     host: String,
+///     port: u16,
+/// }
+/// #[derive(serde::Deserialize, Debug)]
+/// struct Config {
+///     name: String,
+///     db: Db,
+/// }
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_nested_from_dotted_keys")]
+///     config: Config,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "config": { "db.host": "x", "db.port": 5432, "name": "y" } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.config.name, "y");
+///     assert_eq!(a.config.db.host, "x");
+///     assert_eq!(a.config.db.port, 5432);
+///
+///     let s = r#" { "config": { "db": {}, "db.host": "x", "db.port": 5432, "name": "y" } } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"conflicting keys "db" and "db.host""#));
+/// }
+/// ```
+pub fn deserialize_nested_from_dotted_keys<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: DeserializeOwned,
+    D: Deserializer<'de>,
+{
+    use serde_json::{Map as JsonMap, Value};
+
+    use alloc::collections::BTreeMap as Map;
+
+    let map = Map::<String, Value>::deserialize(deserializer)?;
+    for key in map.keys() {
+        for (i, _) in key.match_indices('.') {
+            if map.contains_key(&key[..i]) {
+                return Err(Error::custom(format!(
+                    "conflicting keys {:?} and {:?}",
+                    &key[..i],
+                    key
+                )));
+            }
+        }
+    }
+
+    let mut nested = JsonMap::new();
+    for (key, value) in map {
+        let mut segments = key.split('.').peekable();
+        let mut object = &mut nested;
+        while let Some(segment) = segments.next() {
+            if segments.peek().is_none() {
+                object.insert(segment.to_owned(), value);
+                break;
+            }
+            object = match object
+                .entry(segment.to_owned())
+                .or_insert_with(|| Value::Object(JsonMap::new()))
+            {
+                Value::Object(object) => object,
+                _ => unreachable!("the prefixes of the keys are checked to be absent"),
+            };
+        }
+    }
+    T::deserialize(Value::Object(nested)).map_err(Error::custom)
+}
+
+/// Deserializes a struct either from a map, as usual, or from a sequence holding the values of
+/// the fields in the order of their declaration: `["alice", 30, true]` is read as
+/// `{ "name": "alice", "age": 30, "active": true }`. The sequence must have exactly as many
+/// elements as the struct has fields, otherwise the error tells the expected and the found
+/// numbers.
+///
+/// # **Notes**
+///
+/// - The whole value is buffered (as a `serde_json::Value`) before `T` is deserialized.
+/// - The fields are found with
+///   [`serde_introspect`](crate::serde_introspection::serde_introspect), thus `T` must not have
+///   the aliases (`#[serde(alias)]`), which would be taken as the separate positions, nor the
+///   `#[serde(flatten)]` fields.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug, PartialEq)]
+/// struct User {
+///     name: String,
+///     age: u8,
+///     active: bool,
+/// }
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_struct_from_map_or_seq")]
+///     user: User,
+/// }
+///
+/// fn main() {
+///     let expected = User { name: "alice".to_owned(), age: 30, active: true };
+///     for s in &[
+///         r#" { "user": ["alice", 30, true] } "#,
+///         r#" { "user": { "name": "alice", "age": 30, "active": true } } "#,
+///     ] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.user, expected);
+///     }
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "user": ["alice", 30] } "#).unwrap_err();
+///     assert!(e.to_string().starts_with("expected 3 elements, found 2"));
+/// }
+/// ```
+pub fn deserialize_struct_from_map_or_seq<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: DeserializeOwned,
+    D: Deserializer<'de>,
+{
+    use serde_json::{Map as JsonMap, Value};
+
+    match Value::deserialize(deserializer)? {
+        Value::Array(values) => {
+            let fields = crate::serde_introspection::serde_introspect::<T>();
+            if values.len() != fields.len() {
+                return Err(Error::custom(format!(
+                    "expected {} elements, found {}",
+                    fields.len(),
+                    values.len()
+                )));
+            }
+            let object = fields
+                .iter()
+                .map(|field| (*field).to_owned())
+                .zip(values)
+                .collect::<JsonMap<String, Value>>();
+            T::deserialize(Value::Object(object)).map_err(Error::custom)
+        }
+        value => T::deserialize(value).map_err(Error::custom),
+    }
+}
+
+/// Builds a deserializer of a struct whose keys are renamed by a table first, for the structs
+/// which can't get the `#[serde(rename)]` attributes (coming from another crate, for example) and
+/// the data sources naming the keys differently. Every `(from, to)` pair of the table renames the
+/// key `from` to `to`. The keys which are not in the table are passed untouched, and a renamed
+/// key colliding with another key is a duplicate field error.
+///
+/// # **Notes**
+///
+/// - The whole map is buffered (as a `serde_json::Value`) before `T` is deserialized.
+/// - Only the keys of the top level map are renamed.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// // Imagine this one is defined in a shared crate.
+/// #[derive(serde::Deserialize, Debug)]
+/// struct User {
+///     id: u64,
+///     name: String,
+/// }
+///
+/// fn user_from_legacy<'de, D>(deserializer: D) -> Result<User, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     RenameKeys::new(&[("user_id", "id"), ("user_name", "name")]).into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "user_from_legacy")]
+///     user: User,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "user": { "user_id": 1, "user_name": "Ann" } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.user.id, 1);
+///     assert_eq!(a.user.name, "Ann");
+///
+///     let s = r#" { "user": { "user_id": 1, "name": "Ann" } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.user.name, "Ann");
+///
+///     let s = r#" { "user": { "user_id": 1, "id": 2, "name": "Ann" } } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with("duplicate field `id`"));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RenameKeys {
+    table: &'static [(&'static str, &'static str)],
+}
+
+impl RenameKeys {
+    /// Creates a builder renaming the keys by the `(from, to)` pairs of the table.
+    pub fn new(table: &'static [(&'static str, &'static str)]) -> Self {
+        RenameKeys { table }
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, T, D>(self) -> impl FnMut(D) -> Result<T, D::Error>
+    where
+        T: DeserializeOwned,
+        D: Deserializer<'de>,
+    {
+        use serde_json::{Map as JsonMap, Value};
+
+        use alloc::collections::BTreeMap as Map;
+
+        move |deserializer| {
+            let map = Map::<String, Value>::deserialize(deserializer)?;
+            let mut renamed = JsonMap::new();
+            for (key, value) in map {
+                let key = match self.table.iter().find(|(from, _)| *from == key) {
+                    Some((_, to)) => (*to).to_owned(),
+                    None => key,
+                };
+                if renamed.contains_key(&key) {
+                    // At least one of the colliding keys is renamed, so it is in the table.
+                    let field = self
+                        .table
+                        .iter()
+                        .map(|(_, to)| *to)
+                        .find(|to| *to == key)
+                        .unwrap_or("");
+                    return Err(Error::duplicate_field(field));
+                }
+                renamed.insert(key, value);
+            }
+            T::deserialize(Value::Object(renamed)).map_err(Error::custom)
+        }
+    }
+}
+
+/// Deserializes `T` allowing every number in it, at any depth, to be given as a numeric string:
+/// `{ "id": "42", "price": "1.5" }` is read the same as `{ "id": 42, "price": 1.5 }`. This is
+/// the same as putting [`deserialize_number_from_string`](crate::field_attributes::deserialize_number_from_string)
+/// on every numeric field, but done once for the whole struct.
+///
+/// # **Notes**
+///
+/// - The whole value is buffered (as a `serde_json::Value`) before `T` is deserialized.
+/// - A string is converted only when a number is asked for, so the `String` fields keep their
+///   numeric-looking values as they are. The string has to be a valid JSON number, whitespace
+///   around it is ignored.
+/// - The fields with `deserialize_with` or a custom `Deserialize` implementation calling
+///   `deserialize_any` see the strings as strings.
+/// - The enums are deserialized as usual, without the conversion inside them.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct Item {
+///     id: u64,
+///     price: f64,
+///     code: String,
+///     discount: Option<u8>,
+/// }
+/// #[derive(serde::Deserialize, Debug)]
+/// struct Order {
+///     items: Vec<Item>,
+///     total: f32,
+/// }
+///
+/// fn main() {
+///     let s = r#" {
+///         "items": [
+///             { "id": "42", "price": "1.5", "code": "007", "discount": null },
+///             { "id": 43, "price": 2, "code": "8", "discount": " 10 " }
+///         ],
+///         "total": "3.5"
+///     } "#;
+///     let PermissiveNumbers(order) = serde_json::from_str::<PermissiveNumbers<Order>>(s).unwrap();
+///     assert_eq!(order.total, 3.5);
+///     assert_eq!(order.items[0].id, 42);
+///     assert_eq!(order.items[0].price, 1.5);
+///     assert_eq!(order.items[0].code, "007");
+///     assert_eq!(order.items[1].discount, Some(10));
+///
+///     let s = r#" { "items": [], "total": "a lot" } "#;
+///     assert!(serde_json::from_str::<PermissiveNumbers<Order>>(s).is_err());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PermissiveNumbers<T>(pub T);
+
+impl<'de, T> Deserialize<'de> for PermissiveNumbers<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_permissive_numbers(deserializer).map(PermissiveNumbers)
+    }
+}
+
+/// The same as [`PermissiveNumbers`], for a field.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct Limits {
+///     min: i32,
+///     max: i32,
+/// }
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_permissive_numbers")]
+///     limits: Limits,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "limits": { "min": "-5", "max": 5 } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.limits.min, -5);
+///     assert_eq!(a.limits.max, 5);
+/// }
+/// ```
+pub fn deserialize_permissive_numbers<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: DeserializeOwned,
+    D: Deserializer<'de>,
+{
+    use serde_json::Value;
+
+    let value = Value::deserialize(deserializer)?;
+    T::deserialize(PermissiveValue(value)).map_err(Error::custom)
+}
+
+/// A struct together with the fields it doesn't know, for the version-tolerant consumers: the
+/// fields of `T` go to `value`, everything else is kept in `unknown` instead of being dropped.
+/// When serialized, the unknown fields are put back next to the fields of `T`, so a value can be
+/// passed through without losing what a newer producer has added.
+///
+/// Unlike `#[serde(flatten)]` this works with `#[serde(deny_unknown_fields)]` on `T` and doesn't
+/// change the way `T` itself is deserialized.
+///
+/// # **Notes**
+///
+/// - The whole map is buffered (as a `serde_json::Value`) before `T` is deserialized.
+/// - The known fields (and their aliases) are found with
+///   [`serde_introspect`](crate::serde_introspection::serde_introspect), so `T` has to be a plain
+///   struct, without the `#[serde(flatten)]` fields.
+/// - `T` has to serialize as a map for the serialization to work.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// #[serde(deny_unknown_fields)]
+/// struct User {
+///     id: u64,
+///     name: String,
+/// }
+///
+/// fn main() {
+///     let s = r#"{"id":1,"name":"Ann","nickname":"annie","tags":["a"]}"#;
+///     let a: WithUnknown<User> = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.value.id, 1);
+///     assert_eq!(a.value.name, "Ann");
+///     assert_eq!(a.unknown.len(), 2);
+///     assert_eq!(a.unknown["nickname"], "annie");
+///
+///     assert_eq!(serde_json::to_string(&a).unwrap(), s);
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WithUnknown<T> {
+    /// The struct deserialized from its own fields.
+    pub value: T,
+    /// The fields which are not the fields of `T`.
+    pub unknown: BTreeMap<String, serde_json::Value>,
+}
+
+impl<'de, T> Deserialize<'de> for WithUnknown<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde_json::{Map as JsonMap, Value};
+
+        let known_fields = crate::serde_introspection::serde_introspect::<T>();
+        let map = BTreeMap::<String, Value>::deserialize(deserializer)?;
+        let mut known = JsonMap::new();
+        let mut unknown = BTreeMap::new();
+        for (key, value) in map {
+            if known_fields.contains(&key.as_str()) {
+                known.insert(key, value);
+            } else {
+                unknown.insert(key, value);
+            }
+        }
+        let value = T::deserialize(Value::Object(known)).map_err(Error::custom)?;
+        Ok(WithUnknown { value, unknown })
+    }
+}
+
+impl<T> Serialize for WithUnknown<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use serde::ser::Error;
+        use serde_json::Value;
+
+        let mut fields = match serde_json::to_value(&self.value).map_err(S::Error::custom)? {
+            Value::Object(fields) => fields,
+            _ => {
+                return Err(S::Error::custom(
+                    "WithUnknown can only wrap a struct or a map",
+                ))
+            }
+        };
+        for (key, value) in &self.unknown {
+            if !fields.contains_key(key) {
+                fields.insert(key.clone(), value.clone());
+            }
+        }
+        fields.serialize(serializer)
+    }
+}
+
+/// Gives out a buffered value, parsing the strings when the numbers are asked for.
+struct PermissiveValue(serde_json::Value);
+
+macro_rules! permissive_numbers {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                use serde_json::{Number, Value};
+
+                match self.0 {
+                    Value::String(s) => match s.trim().parse::<Number>() {
+                        Ok(number) => Value::Number(number).$method(visitor),
+                        Err(_) => Value::String(s).$method(visitor),
+                    },
+                    value => PermissiveValue(value).deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for PermissiveValue {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        use serde_json::Value;
+
+        match self.0 {
+            Value::Array(values) => {
+                let mut seq = PermissiveSeq(values.into_iter());
+                let result = visitor.visit_seq(&mut seq)?;
+                match seq.0.len() {
+                    0 => Ok(result),
+                    left => Err(Error::invalid_length(left, &"fewer elements in the array")),
+                }
+            }
+            Value::Object(map) => visitor.visit_map(PermissiveMap {
+                entries: map.into_iter(),
+                value: None,
+            }),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            serde_json::Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(PermissiveValue(value)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    permissive_numbers! {
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct PermissiveSeq(alloc::vec::IntoIter<serde_json::Value>);
+
+impl<'de> SeqAccess<'de> for PermissiveSeq {
+    type Error = serde_json::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(PermissiveValue(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct PermissiveMap {
+    entries: serde_json::map::IntoIter,
+    value: Option<serde_json::Value>,
+}
+
+impl<'de> MapAccess<'de> for PermissiveMap {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(serde_json::Value::String(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(value) => seed.deserialize(PermissiveValue(value)),
+            None => Err(Error::custom("value is missing")),
+        }
+    }
+}
+
 /// This contains both serialization and ser/deserialization of a enum into and from numbers.
 /// The [reference implementation](https://serde.rs/enum-number.html) does not work if your
 /// enum has negative values. This `enum_number` handles this also.
@@ -89,7 +696,7 @@ macro_rules! enum_number_declare {
             fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
                 where D: serde::Deserializer<'de>
             {
-                use std::fmt;
+                use ::core::fmt;
                 struct Visitor;
 
                 impl<'de> serde::de::Visitor<'de> for Visitor {
@@ -107,7 +714,7 @@ macro_rules! enum_number_declare {
                         match value {
                             $( $value => Ok($name::$variant), )*
                             _ => Err(E::custom(
-                                format!("unknown {} value: {}",
+                                format_args!("unknown {} value: {}",
                                 stringify!($name), value))),
                         }
                     }
@@ -125,3 +732,70 @@ macro_rules! enum_number_declare {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use std::{
+        borrow::ToOwned,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    #[serde(deny_unknown_fields)]
+    struct Config {
+        name: String,
+        #[serde(alias = "max_connections")]
+        connections: u32,
+        #[serde(default)]
+        debug: bool,
+    }
+
+    #[test]
+    fn test_with_unknown_json() {
+        let s = r#"{"connections":5,"name":"db","retries":{"count":3},"timeout":1.5}"#;
+        let a: WithUnknown<Config> = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            a.value,
+            Config {
+                name: "db".to_owned(),
+                connections: 5,
+                debug: false,
+            }
+        );
+        assert_eq!(
+            a.unknown.keys().collect::<Vec<_>>(),
+            vec!["retries", "timeout"]
+        );
+        assert_eq!(
+            serde_json::to_string(&a).unwrap(),
+            r#"{"connections":5,"debug":false,"name":"db","retries":{"count":3},"timeout":1.5}"#
+        );
+
+        let a: WithUnknown<Config> =
+            serde_json::from_str(r#"{"name":"db","max_connections":2}"#).unwrap();
+        assert_eq!(a.value.connections, 2);
+        assert!(a.unknown.is_empty());
+
+        let e = serde_json::from_str::<WithUnknown<Config>>(r#"{"name":"db","x":1}"#).unwrap_err();
+        assert!(e.to_string().starts_with("missing field `connections`"));
+    }
+
+    #[test]
+    fn test_with_unknown_toml() {
+        let s = "connections = 5\nname = \"db\"\nregion = \"eu\"\n";
+        let a: WithUnknown<Config> = toml::from_str(s).unwrap();
+        assert_eq!(a.value.name, "db");
+        assert_eq!(a.value.connections, 5);
+        assert_eq!(a.unknown["region"], "eu");
+
+        let s = toml::to_string(&a).unwrap();
+        assert_eq!(
+            s,
+            "connections = 5\ndebug = false\nname = \"db\"\nregion = \"eu\"\n"
+        );
+        assert_eq!(toml::from_str::<WithUnknown<Config>>(&s).unwrap(), a);
+    }
+}
@@ -1,7 +1,57 @@
-use std::fmt::Display;
-use std::str::FromStr;
+use alloc::borrow::ToOwned;
+use alloc::collections::btree_map::{BTreeMap, Entry};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
+use core::hash::Hash;
+use core::iter::FromIterator;
+use core::marker::PhantomData;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use std::ffi::OsString;
+#[cfg(feature = "std")]
+use std::path::PathBuf;
 
-use serde::{Deserialize, Deserializer};
+use serde::de::Visitor;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Deserializes a `chrono::DateTime<Utc>` from a seconds time stamp, either a number or a string
+/// with a number inside like **"1519927261"**. See
+/// [`deserialize_datetime_utc_from_milliseconds`].
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_datetime_utc_from_seconds")]
+///     time: DateTime<Utc>,
+/// }
+/// fn main() {
+///     for s in &[r#" { "time": "1519927261" } "#, r#" { "time": 1519927261 } "#] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.time, Utc.timestamp_opt(1519927261, 0).unwrap());
+///     }
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_datetime_utc_from_seconds<'de, D>(
+    deserializer: D,
+) -> Result<chrono::DateTime<chrono::Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let number = deserialize_number_from_string::<i64, D>(deserializer)?;
+    datetime_utc_from_timestamp(number, 1)
+}
 
 /// Deserializes a `chrono::DateTime<Utc>` from a milliseconds time stamp. Useful when the data is coming from a number
 /// which is not a seconds time stamp but milliseconds one. It also handles the string to number conversion if the
@@ -32,698 +82,12947 @@ pub fn deserialize_datetime_utc_from_milliseconds<'de, D>(
 where
     D: Deserializer<'de>,
 {
-    use chrono::prelude::*;
-
     let number = deserialize_number_from_string::<i64, D>(deserializer)?;
-    let seconds = number / 1000;
-    let millis = (number % 1000) as u32;
-    let nanos = millis * 1_000_000;
-
-    Ok(DateTime::<Utc>::from_utc(
-        NaiveDateTime::from_timestamp(seconds, nanos),
-        Utc,
-    ))
+    datetime_utc_from_timestamp(number, 1_000)
 }
 
-/// Deserializes a number from string or a number.
+/// Deserializes a `chrono::DateTime<Utc>` from a microseconds time stamp, either a number or a
+/// string with a number inside like **"1519927261900123"**. See
+/// [`deserialize_datetime_utc_from_milliseconds`].
 ///
 /// # Example:
 ///
 /// ```rust
+/// use chrono::prelude::*;
 /// use serde_aux::prelude::*;
 ///
-/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// #[derive(serde::Deserialize, Debug)]
 /// struct MyStruct {
-///     #[serde(deserialize_with = "deserialize_number_from_string")]
-///     number_from_string: u64,
+///     #[serde(deserialize_with = "deserialize_datetime_utc_from_microseconds")]
+///     time: DateTime<Utc>,
 /// }
 /// fn main() {
-///     let s = r#" { "number_from_string": "123" } "#;
+///     let s = r#" { "time": "1519927261900123" } "#;
 ///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.number_from_string, 123);
+///     assert_eq!(a.time.timestamp(), 1519927261);
+///     assert_eq!(a.time.timestamp_subsec_micros(), 900_123);
 ///
-///     let s = r#" { "number_from_string": 444 } "#;
+///     let s = r#" { "time": -1 } "#;
 ///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.number_from_string, 444);
+///     assert_eq!(a.time.timestamp(), -1);
+///     assert_eq!(a.time.timestamp_subsec_micros(), 999_999);
 /// }
 /// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_datetime_utc_from_microseconds<'de, D>(
+    deserializer: D,
+) -> Result<chrono::DateTime<chrono::Utc>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let number = deserialize_number_from_string::<i64, D>(deserializer)?;
+    datetime_utc_from_timestamp(number, 1_000_000)
+}
+
+/// Deserializes a `chrono::DateTime<Utc>` from a nanoseconds time stamp, either a number or a
+/// string with a number inside like **"1519927261900123456"**. Any `i64` is a valid time stamp
+/// here, which covers the years 1677 to 2262. See [`deserialize_datetime_utc_from_milliseconds`].
 ///
-/// For making it work with strong types you must implement `FromStr` trait. It is quite simple.
-///
-/// # Example
+/// # Example:
 ///
 /// ```rust
-/// use std::str::FromStr;
-/// use std::num::{ParseIntError, ParseFloatError};
-///
+/// use chrono::prelude::*;
 /// use serde_aux::prelude::*;
 ///
-/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
-/// struct IntId(u64);
-///
-/// impl FromStr for IntId {
-///     type Err = ParseIntError;
-///
-///     fn from_str(s: &str) -> Result<IntId, Self::Err> {
-///         Ok(IntId(u64::from_str(s)?))
-///     }
-/// }
-///
-/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// #[derive(serde::Deserialize, Debug)]
 /// struct MyStruct {
-///     #[serde(deserialize_with = "deserialize_number_from_string")]
-///     int_id: IntId,
+///     #[serde(deserialize_with = "deserialize_datetime_utc_from_nanoseconds")]
+///     time: DateTime<Utc>,
 /// }
 /// fn main() {
-///     let s = r#"{ "int_id": "123" }"#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.int_id.0, 123);
-///
-///     let s = r#"{ "int_id": 444 }"#;
+///     let s = r#" { "time": 1519927261900123456 } "#;
 ///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.int_id.0, 444);
+///     assert_eq!(a.time.timestamp(), 1519927261);
+///     assert_eq!(a.time.timestamp_subsec_nanos(), 900_123_456);
 ///
+///     let s = r#" { "time": "9223372036854775808" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
 /// }
 /// ```
-pub fn deserialize_number_from_string<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+#[cfg(feature = "chrono")]
+pub fn deserialize_datetime_utc_from_nanoseconds<'de, D>(
+    deserializer: D,
+) -> Result<chrono::DateTime<chrono::Utc>, D::Error>
 where
     D: Deserializer<'de>,
-    T: FromStr + serde::Deserialize<'de>,
-    <T as FromStr>::Err: Display,
 {
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum StringOrInt<T> {
-        String(String),
-        Number(T),
-    }
+    let number = deserialize_number_from_string::<i64, D>(deserializer)?;
+    datetime_utc_from_timestamp(number, 1_000_000_000)
+}
 
-    match StringOrInt::<T>::deserialize(deserializer)? {
-        StringOrInt::String(s) => s.parse::<T>().map_err(serde::de::Error::custom),
-        StringOrInt::Number(i) => Ok(i),
-    }
+/// Serializes a `chrono::DateTime<Utc>` as a milliseconds time stamp, the counterpart of
+/// [`deserialize_datetime_utc_from_milliseconds`]. The sub-millisecond part is dropped (rounding
+/// towards the past, as `DateTime::timestamp_millis` does).
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_datetime_utc_as_milliseconds")]
+///     time: DateTime<Utc>,
+/// }
+/// fn main() {
+///     let time = Utc.timestamp_opt(1519927261, 900_999_999).unwrap();
+///     let a = MyStruct { time };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":1519927261900}"#);
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn serialize_datetime_utc_as_milliseconds<S>(
+    time: &chrono::DateTime<chrono::Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_i64(time.timestamp_millis())
 }
 
-/// Deserializes an option number from string or a number.
+/// Serializes a `chrono::DateTime<Utc>` as a string with a milliseconds time stamp inside like
+/// **"1519927261900"**, for the consumers which can't take the integers past 53 bits. Otherwise
+/// the same as [`serialize_datetime_utc_as_milliseconds`].
 ///
 /// # Example:
 ///
 /// ```rust
+/// use chrono::prelude::*;
 /// use serde_aux::prelude::*;
 ///
-/// #[derive(Debug, serde::Deserialize)]
+/// #[derive(serde::Serialize, Debug)]
 /// struct MyStruct {
-///     #[serde(deserialize_with = "deserialize_option_number_from_string")]
-///     option_num: Option<f32>,
-///     #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
-///     missing: Option<i32>
+///     #[serde(serialize_with = "serialize_datetime_utc_as_milliseconds_string")]
+///     time: DateTime<Utc>,
 /// }
-/// fn serde_qs_eq(s: &str, result: Option<f32>) {
-///     let a: MyStruct = serde_qs::from_str(s).unwrap();
-///     assert_eq!(a.option_num, result);
-///     assert_eq!(a.missing, None);
+/// fn main() {
+///     let time = Utc.timestamp_opt(1519927261, 900_000_000).unwrap();
+///     let a = MyStruct { time };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":"1519927261900"}"#);
 /// }
-/// fn serde_qs_err(s: &str) {
-///     assert!(serde_qs::from_str::<MyStruct>(s).is_err());
+/// ```
+#[cfg(feature = "chrono")]
+pub fn serialize_datetime_utc_as_milliseconds_string<S>(
+    time: &chrono::DateTime<chrono::Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(&time.timestamp_millis())
+}
+
+/// Serializes a `chrono::DateTime<Utc>` as a seconds time stamp, the counterpart of
+/// [`deserialize_datetime_utc_from_seconds`]. The sub-second part is truncated towards zero, so
+/// half a second before the epoch is written as `0` (unlike `DateTime::timestamp`, which gives
+/// `-1` there).
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_datetime_utc_as_seconds")]
+///     time: DateTime<Utc>,
 /// }
-/// fn serde_json_eq(s: &str, result: Option<f32>) {
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.option_num, result);
-///     assert_eq!(a.missing, None);
+/// fn main() {
+///     let a = MyStruct { time: Utc.timestamp_opt(1519927261, 900_000_000).unwrap() };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":1519927261}"#);
+///
+///     let a = MyStruct { time: Utc.timestamp_opt(-2, 500_000_000).unwrap() };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":-1}"#);
 /// }
-/// fn serde_json_err(s: &str) {
-///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// ```
+#[cfg(feature = "chrono")]
+pub fn serialize_datetime_utc_as_seconds<S>(
+    time: &chrono::DateTime<chrono::Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_i64(timestamp_seconds_towards_zero(time))
+}
+
+/// Serializes a `chrono::DateTime<Utc>` as a string with a seconds time stamp inside like
+/// **"1519927261"**. Otherwise the same as [`serialize_datetime_utc_as_seconds`].
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_datetime_utc_as_seconds_string")]
+///     time: DateTime<Utc>,
 /// }
 /// fn main() {
-///     serde_qs_eq("option_num=1", Some(1.0));
-///     serde_qs_eq("option_num=-1", Some(-1.0));
-///     serde_qs_eq("option_num=0.1", Some(0.1));
-///     serde_qs_eq("option_num=-0.1", Some(-0.1));
-///     serde_qs_eq("option_num=", None);
-///     serde_qs_eq("option_num", None);
+///     let a = MyStruct { time: Utc.timestamp_opt(1580887135, 999_000_000).unwrap() };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":"1580887135"}"#);
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn serialize_datetime_utc_as_seconds_string<S>(
+    time: &chrono::DateTime<chrono::Utc>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_str(&timestamp_seconds_towards_zero(time))
+}
+
+/// Returns the seconds time stamp of the date time with the sub-second part truncated towards
+/// zero.
+#[cfg(feature = "chrono")]
+fn timestamp_seconds_towards_zero(time: &chrono::DateTime<chrono::Utc>) -> i64 {
+    let seconds = time.timestamp();
+    if seconds < 0 && time.timestamp_subsec_nanos() > 0 {
+        seconds + 1
+    } else {
+        seconds
+    }
+}
+
+/// Converts a time stamp counting `units_per_second` units since the epoch into a date time.
+#[cfg(feature = "chrono")]
+fn datetime_utc_from_timestamp<E>(
+    timestamp: i64,
+    units_per_second: i64,
+) -> Result<chrono::DateTime<chrono::Utc>, E>
+where
+    E: serde::de::Error,
+{
+    use chrono::prelude::*;
+
+    let seconds = timestamp.div_euclid(units_per_second);
+    let nanos = timestamp.rem_euclid(units_per_second) * (1_000_000_000 / units_per_second);
+
+    Utc.timestamp_opt(seconds, nanos as u32)
+        .single()
+        .ok_or_else(|| E::custom(format!("Invalid timestamp: {}", timestamp)))
+}
+
+/// Converts a time stamp of unknown precision into a date time, guessing the precision by the
+/// magnitude of the value: the values below `10^11` are taken as seconds (which covers the dates
+/// up to the year 5138), below `10^14` as milliseconds, below `10^17` as microseconds and the rest
+/// as nanoseconds.
+#[cfg(feature = "chrono")]
+fn datetime_utc_from_timestamp_guess<E>(timestamp: i64) -> Result<chrono::DateTime<chrono::Utc>, E>
+where
+    E: serde::de::Error,
+{
+    let magnitude = (timestamp as i128).abs();
+    let units_per_second = if magnitude < 100_000_000_000 {
+        1
+    } else if magnitude < 100_000_000_000_000 {
+        1_000
+    } else if magnitude < 100_000_000_000_000_000 {
+        1_000_000
+    } else {
+        1_000_000_000
+    };
+    datetime_utc_from_timestamp(timestamp, units_per_second)
+}
+
+/// Deserializes a `chrono::DateTime<Utc>` from the several common representations, trying them in
+/// the following order:
 ///
-///     serde_qs_err("option_num=true");
-///     serde_qs_err("option_num=a");
-///     serde_qs_err("option_num[a]=");
-///     serde_qs_err("option_num[]=");
+/// 1. An RFC 3339 string like **"2023-01-02T03:04:05Z"**.
+/// 2. An RFC 2822 string like **"Mon, 02 Jan 2023 03:04:05 GMT"**.
+/// 3. An epoch time stamp, either a number or a string with a number inside. The precision of the
+///    time stamp is guessed by its magnitude: the values below `10^11` are seconds, below `10^14`
+///    milliseconds, below `10^17` microseconds and the rest are nanoseconds.
 ///
-///     serde_json_eq(r#" { "option_num": "1" } "#, Some(1.0));
-///     serde_json_eq(r#" { "option_num": "-1" } "#, Some(-1.0));
-///     serde_json_eq(r#" { "option_num": "0.1" } "#, Some(0.1));
-///     serde_json_eq(r#" { "option_num": "-0.1" } "#, Some(-0.1));
-///     serde_json_eq(r#" { "option_num": 1 } "#, Some(1.0));
-///     serde_json_eq(r#" { "option_num": -1 } "#, Some(-1.0));
-///     serde_json_eq(r#" { "option_num": 0.1 } "#, Some(0.1));
-///     serde_json_eq(r#" { "option_num": -0.1 } "#, Some(-0.1));
-///     serde_json_eq(r#" { "option_num": "" } "#, None);
-///     serde_json_eq(r#" { "option_num": null } "#, None);
+/// # Example:
 ///
-///     serde_json_err(r#" { "option_num": true } "#);
-///     serde_json_err(r#" { "option_num": "a" } "#);
-///     serde_json_err(r#" { "option_num": {} } "#);
-///     serde_json_err(r#" { "option_num": [] } "#);
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_datetime_utc_flexible")]
+///     published_at: DateTime<Utc>,
+/// }
+/// fn main() {
+///     let expected = Utc.with_ymd_and_hms(2023, 1, 2, 3, 4, 5).unwrap();
+///     for s in &[
+///         r#" { "published_at": "2023-01-02T03:04:05Z" } "#,
+///         r#" { "published_at": "2023-01-02T05:04:05+02:00" } "#,
+///         r#" { "published_at": "Mon, 02 Jan 2023 03:04:05 GMT" } "#,
+///         r#" { "published_at": 1672628645 } "#,
+///         r#" { "published_at": "1672628645" } "#,
+///         r#" { "published_at": 1672628645000 } "#,
+///     ] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.published_at, expected);
+///     }
+///
+///     let s = r#" { "published_at": "yesterday" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
 /// }
 /// ```
-pub fn deserialize_option_number_from_string<'de, T, D>(
+#[cfg(feature = "chrono")]
+pub fn deserialize_datetime_utc_flexible<'de, D>(
     deserializer: D,
-) -> Result<Option<T>, D::Error>
+) -> Result<chrono::DateTime<chrono::Utc>, D::Error>
 where
     D: Deserializer<'de>,
-    T: FromStr + serde::Deserialize<'de>,
-    <T as FromStr>::Err: Display,
 {
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum NumericOrNull<'a, T> {
-        Str(&'a str),
-        FromStr(T),
-        Null,
-    }
+    use chrono::prelude::*;
 
-    match NumericOrNull::<T>::deserialize(deserializer)? {
-        NumericOrNull::Str(s) => match s {
-            "" => Ok(None),
-            _ => T::from_str(&s).map(Some).map_err(serde::de::Error::custom),
-        },
-        NumericOrNull::FromStr(i) => Ok(Some(i)),
-        NumericOrNull::Null => Ok(None),
-    }
-}
+    struct DateTimeUtcFlexibleVisitor;
 
-macro_rules! wrap_option_number_from_string_fn {
-    (
-        $(#[doc = $doc:tt])*
-        $func:ident,
-        $res:ty
-    ) => {
-        $(#[doc = $doc])*
-        pub fn $func<'de, T, D>(deserializer: D) -> Result<$res, D::Error>
+    impl<'de> Visitor<'de> for DateTimeUtcFlexibleVisitor {
+        type Value = DateTime<Utc>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an RFC 3339 string, an RFC 2822 string or an epoch time stamp")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<DateTime<Utc>, E>
         where
-            D: Deserializer<'de>,
-            T: FromStr + serde::Deserialize<'de>,
-            <T as FromStr>::Err: Display,
+            E: serde::de::Error,
         {
-            #[derive(Deserialize)]
-            #[serde(untagged)]
-            enum NumericOrNull<'a, T> {
-                Str(&'a str),
-                FromStr(T),
-                Null,
+            if let Ok(dt) = DateTime::parse_from_rfc3339(v) {
+                Ok(dt.with_timezone(&Utc))
+            } else if let Ok(dt) = DateTime::parse_from_rfc2822(v) {
+                Ok(dt.with_timezone(&Utc))
+            } else if let Ok(timestamp) = v.parse::<i64>() {
+                datetime_utc_from_timestamp_guess(timestamp)
+            } else {
+                Err(E::custom(format!(
+                    "Could not parse a date time from {:?}, tried RFC 3339, RFC 2822 and an epoch time stamp",
+                    v
+                )))
             }
+        }
 
-            match NumericOrNull::<T>::deserialize(deserializer)? {
-                NumericOrNull::Str(s) => match s {
-                    "" => Ok(None.into()),
-                    _ => T::from_str(&s)
-                        .map(|i| Some(i).into())
-                        .map_err(serde::de::Error::custom),
-                },
-                NumericOrNull::FromStr(i) => Ok(Some(i).into()),
-                NumericOrNull::Null => Ok(None.into()),
+        fn visit_i64<E>(self, v: i64) -> Result<DateTime<Utc>, E>
+        where
+            E: serde::de::Error,
+        {
+            datetime_utc_from_timestamp_guess(v)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<DateTime<Utc>, E>
+        where
+            E: serde::de::Error,
+        {
+            if v > i64::MAX as u64 {
+                return Err(E::custom(format!("Invalid timestamp: {}", v)));
             }
+            datetime_utc_from_timestamp_guess(v as i64)
         }
-    };
+    }
+
+    deserializer.deserialize_any(DateTimeUtcFlexibleVisitor)
 }
-wrap_option_number_from_string_fn!(
-    /// Deserializes a `Cell` option number from string or a number. Same logic as [`"deserialize_option_number_from_string"`](https://docs.rs/serde-aux/latest/serde_aux/field_attributes/fn.deserialize_option_number_from_string.html).
-    ///
-    /// # Example:
-    ///
-    /// ```rust
-    /// use serde_aux::prelude::*;
-    /// use std::cell::Cell;
-    ///
-    /// #[derive(Debug, serde::Deserialize)]
-    /// struct MyStruct {
-    ///     #[serde(deserialize_with = "deserialize_cell_option_number_from_string")]
-    ///     v: Cell<Option<f32>>
-    /// }
-    /// fn main() {
-    ///     let a = serde_qs::from_str::<MyStruct>("v=-0.1").unwrap();
-    ///     assert_eq!(a.v, Cell::new(Some(-0.1)));
-    /// }
-    /// ```
-    deserialize_cell_option_number_from_string,
-    std::cell::Cell<Option<T>>
-);
-wrap_option_number_from_string_fn!(
-    /// Deserializes a `RefCell` option number from string or a number. Same logic as [`"deserialize_option_number_from_string"`](https://docs.rs/serde-aux/latest/serde_aux/field_attributes/fn.deserialize_option_number_from_string.html).
-    ///
-    /// # Example:
-    ///
-    /// ```rust
-    /// use serde_aux::prelude::*;
-    /// use std::cell::RefCell;
-    ///
-    /// #[derive(Debug, serde::Deserialize)]
-    /// struct MyStruct {
-    ///     #[serde(default, deserialize_with = "deserialize_ref_cell_option_number_from_string")]
-    ///     v: RefCell<Option<f32>>
-    /// }
-    /// fn main() {
-    ///     let a = serde_qs::from_str::<MyStruct>("v=-0.1").unwrap();
-    ///     assert_eq!(a.v, RefCell::new(Some(-0.1)));
-    /// }
-    /// ```
-    deserialize_ref_cell_option_number_from_string,
-    std::cell::RefCell<Option<T>>
-);
-wrap_option_number_from_string_fn!(
-    /// Deserializes a `Mutex` option number from string or a number. Same logic as [`"deserialize_option_number_from_string"`](https://docs.rs/serde-aux/latest/serde_aux/field_attributes/fn.deserialize_option_number_from_string.html).
-    ///
-    /// # Example:
-    ///
-    /// ```rust
-    /// use serde_aux::prelude::*;
-    /// use std::sync::Mutex;
-    ///
-    /// #[derive(Debug, serde::Deserialize)]
-    /// struct MyStruct {
-    ///     #[serde(default, deserialize_with = "deserialize_mutex_option_number_from_string")]
-    ///     v: Mutex<Option<f32>>
-    /// }
-    /// fn main() {
-    ///     let a = serde_qs::from_str::<MyStruct>("v=-0.1").unwrap();
-    ///     assert_eq!(*a.v.lock().unwrap(), Some(-0.1));
-    /// }
-    /// ```
-    deserialize_mutex_option_number_from_string,
-    std::sync::Mutex<Option<T>>
-);
-wrap_option_number_from_string_fn!(
-    /// Deserializes a `RwLock` option number from string or a number. Same logic as [`"deserialize_option_number_from_string"`](https://docs.rs/serde-aux/latest/serde_aux/field_attributes/fn.deserialize_option_number_from_string.html).
-    ///
-    /// # Example:
-    ///
-    /// ```rust
-    /// use serde_aux::prelude::*;
-    /// use std::sync::RwLock;
-    ///
-    /// #[derive(Debug, serde::Deserialize)]
-    /// struct MyStruct {
-    ///     #[serde(default, deserialize_with = "deserialize_rw_lock_option_number_from_string")]
-    ///     v: RwLock<Option<f32>>
-    /// }
-    /// fn main() {
-    ///     let a = serde_qs::from_str::<MyStruct>("v=-0.1").unwrap();
-    ///     assert_eq!(*a.v.read().unwrap(), Some(-0.1));
-    /// }
-    /// ```
-    deserialize_rw_lock_option_number_from_string,
-    std::sync::RwLock<Option<T>>
-);
 
-#[cfg(test)]
-mod tests {
-    use crate::prelude::*;
-    use std::{
-        cell::{Cell, RefCell},
-        sync::{Mutex, RwLock},
-    };
-    #[derive(Debug, serde::Deserialize)]
-    struct MyStruct {
-        #[serde(
-            default,
-            deserialize_with = "deserialize_cell_option_number_from_string"
-        )]
-        cell: Cell<Option<f32>>,
-        #[serde(
-            default,
-            deserialize_with = "deserialize_ref_cell_option_number_from_string"
-        )]
-        ref_cell: RefCell<Option<f32>>,
-        #[serde(
-            default,
-            deserialize_with = "deserialize_mutex_option_number_from_string"
-        )]
-        mutex: Mutex<Option<f32>>,
-        #[serde(
-            default,
-            deserialize_with = "deserialize_rw_lock_option_number_from_string"
-        )]
-        rw_lock: RwLock<Option<f32>>,
+/// Deserializes an `Option<chrono::DateTime<Utc>>` from an epoch time stamp treating `0` as "not
+/// set": `0`, `null` and an empty string give `None`. The other time stamps, numbers or strings
+/// with a number inside, are converted as in [`deserialize_datetime_utc_flexible`], guessing the
+/// precision by the magnitude. See [`deserialize_option_datetime_none_if_zero_or_minus_one`] for
+/// the systems using `-1` as well.
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(default, deserialize_with = "deserialize_option_datetime_none_if_zero")]
+///     deleted_at: Option<DateTime<Utc>>,
+/// }
+/// fn main() {
+///     for s in &[r#" { "deleted_at": 0 } "#, r#" { "deleted_at": "0" } "#, r#" { "deleted_at": null } "#, "{}"] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.deleted_at, None);
+///     }
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "deleted_at": 1519927261 } "#).unwrap();
+///     assert_eq!(a.deleted_at, Some(Utc.timestamp_opt(1519927261, 0).unwrap()));
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "deleted_at": -1 } "#).unwrap();
+///     assert_eq!(a.deleted_at, Some(Utc.timestamp_opt(-1, 0).unwrap()));
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_option_datetime_none_if_zero<'de, D>(
+    deserializer: D,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    option_datetime_none_if(deserializer, &[0], None)
+}
+
+/// The same as [`deserialize_option_datetime_none_if_zero`] but treats `-1` as "not set" too.
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_option_datetime_none_if_zero_or_minus_one")]
+///     deleted_at: Option<DateTime<Utc>>,
+/// }
+/// fn main() {
+///     for s in &[r#" { "deleted_at": 0 } "#, r#" { "deleted_at": -1 } "#, r#" { "deleted_at": "-1" } "#] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.deleted_at, None);
+///     }
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "deleted_at": 1519927261900 } "#).unwrap();
+///     assert_eq!(a.deleted_at, Some(Utc.timestamp_opt(1519927261, 900_000_000).unwrap()));
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_option_datetime_none_if_zero_or_minus_one<'de, D>(
+    deserializer: D,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    option_datetime_none_if(deserializer, &[0, -1], None)
+}
+
+/// Deserializes an `Option<chrono::DateTime<Utc>>` from a milliseconds time stamp, either a
+/// number or a string with a number inside. `null` and an empty string give `None`, every number
+/// (`0` included) is a time. The counterpart of
+/// [`serialize_option_datetime_as_milliseconds_null`], see
+/// [`crate::with::option_datetime_milliseconds_null`].
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(default, deserialize_with = "deserialize_option_datetime_utc_from_milliseconds")]
+///     time: Option<DateTime<Utc>>,
+/// }
+/// fn main() {
+///     for s in &[r#" { "time": null } "#, r#" { "time": "" } "#, "{}"] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.time, None);
+///     }
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "time": 0 } "#).unwrap();
+///     assert_eq!(a.time, Some(Utc.timestamp_opt(0, 0).unwrap()));
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "time": "1500" } "#).unwrap();
+///     assert_eq!(a.time, Some(Utc.timestamp_opt(1, 500_000_000).unwrap()));
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_option_datetime_utc_from_milliseconds<'de, D>(
+    deserializer: D,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    option_datetime_none_if(deserializer, &[], Some(1_000))
+}
+
+/// The same as [`deserialize_option_datetime_utc_from_milliseconds`] but treats `0` as "not set"
+/// too. Unlike [`deserialize_option_datetime_none_if_zero`] the precision is not guessed, so the
+/// times close to the epoch are read correctly. The counterpart of
+/// [`serialize_option_datetime_as_milliseconds_zero`], see
+/// [`crate::with::option_datetime_milliseconds_zero`].
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_option_datetime_utc_from_milliseconds_none_if_zero")]
+///     time: Option<DateTime<Utc>>,
+/// }
+/// fn main() {
+///     for s in &[r#" { "time": 0 } "#, r#" { "time": "0" } "#, r#" { "time": null } "#] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.time, None);
+///     }
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "time": 1500 } "#).unwrap();
+///     assert_eq!(a.time, Some(Utc.timestamp_opt(1, 500_000_000).unwrap()));
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_option_datetime_utc_from_milliseconds_none_if_zero<'de, D>(
+    deserializer: D,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    option_datetime_none_if(deserializer, &[0], Some(1_000))
+}
+
+/// Reads an optional time stamp giving `None` for the `sentinels`. The precision is guessed when
+/// `units_per_second` is not given.
+#[cfg(feature = "chrono")]
+fn option_datetime_none_if<'de, D>(
+    deserializer: D,
+    sentinels: &[i64],
+    units_per_second: Option<i64>,
+) -> Result<Option<chrono::DateTime<chrono::Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match deserialize_option_number_from_string::<i64, D>(deserializer)? {
+        Some(timestamp) if !sentinels.contains(&timestamp) => match units_per_second {
+            Some(units_per_second) => datetime_utc_from_timestamp(timestamp, units_per_second),
+            None => datetime_utc_from_timestamp_guess(timestamp),
+        }
+        .map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Serializes an `Option<chrono::DateTime<Utc>>` as a milliseconds time stamp writing `None` as
+/// `null`. See [`serialize_option_datetime_as_milliseconds_zero`] for the consumers which want
+/// `0` instead, and [`crate::with::option_datetime_milliseconds_null`] for the round trip.
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_option_datetime_as_milliseconds_null")]
+///     time: Option<DateTime<Utc>>,
+/// }
+/// fn main() {
+///     let a = MyStruct { time: Some(Utc.timestamp_opt(1519927261, 900_000_000).unwrap()) };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":1519927261900}"#);
+///
+///     let a = MyStruct { time: None };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":null}"#);
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn serialize_option_datetime_as_milliseconds_null<S>(
+    time: &Option<chrono::DateTime<chrono::Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match time {
+        Some(time) => serializer.serialize_some(&time.timestamp_millis()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serializes an `Option<chrono::DateTime<Utc>>` as a milliseconds time stamp writing `None` as
+/// `0`. Note that the epoch itself is written as `0` too and so reads back as `None`. See
+/// [`crate::with::option_datetime_milliseconds_zero`] for the round trip.
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_option_datetime_as_milliseconds_zero")]
+///     time: Option<DateTime<Utc>>,
+/// }
+/// fn main() {
+///     let a = MyStruct { time: Some(Utc.timestamp_opt(1519927261, 900_000_000).unwrap()) };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":1519927261900}"#);
+///
+///     let a = MyStruct { time: None };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":0}"#);
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn serialize_option_datetime_as_milliseconds_zero<S>(
+    time: &Option<chrono::DateTime<chrono::Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_i64(time.map_or(0, |time| time.timestamp_millis()))
+}
+
+/// Deserializes a `chrono::NaiveTime` from a `"HH:MM:SS"` or `"HH:MM"` string, or from a number
+/// of seconds since midnight (`0` to `86399`). The parse error of chrono is reported for the
+/// invalid strings like `"25:61"`.
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_naive_time_flexible")]
+///     opens_at: NaiveTime,
+/// }
+/// fn main() {
+///     for &(s, expected) in &[
+///         (r#""09:00:30""#, (9, 0, 30)),
+///         (r#""09:00""#, (9, 0, 0)),
+///         ("32430", (9, 0, 30)),
+///     ] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "opens_at": {} }}"#, s)).unwrap();
+///         assert_eq!(a.opens_at, NaiveTime::from_hms_opt(expected.0, expected.1, expected.2).unwrap());
+///     }
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "opens_at": "25:61" } "#).unwrap_err();
+///     assert!(e.to_string().contains("input is out of range"));
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "opens_at": 86400 } "#).is_err());
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_naive_time_flexible<'de, D>(
+    deserializer: D,
+) -> Result<chrono::NaiveTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use chrono::NaiveTime;
+
+    struct NaiveTimeFlexibleVisitor;
+
+    impl<'de> Visitor<'de> for NaiveTimeFlexibleVisitor {
+        type Value = NaiveTime;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a \"HH:MM:SS\" or \"HH:MM\" string or the seconds since midnight")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<NaiveTime, E>
+        where
+            E: serde::de::Error,
+        {
+            let format = if v.matches(':').count() == 2 {
+                "%H:%M:%S"
+            } else {
+                "%H:%M"
+            };
+            NaiveTime::parse_from_str(v, format)
+                .map_err(|e| E::custom(format!("Could not parse a time from {:?}: {}", v, e)))
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<NaiveTime, E>
+        where
+            E: serde::de::Error,
+        {
+            if v < 86_400 {
+                if let Some(time) = NaiveTime::from_num_seconds_from_midnight_opt(v as u32, 0) {
+                    return Ok(time);
+                }
+            }
+            Err(E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<NaiveTime, E>
+        where
+            E: serde::de::Error,
+        {
+            if v < 0 {
+                return Err(E::invalid_value(serde::de::Unexpected::Signed(v), &self));
+            }
+            self.visit_u64(v as u64)
+        }
+    }
+
+    deserializer.deserialize_any(NaiveTimeFlexibleVisitor)
+}
+
+/// Deserializes a `chrono::DateTime<FixedOffset>` keeping the offset of the source: an RFC 3339
+/// string like **"2023-06-01T12:00:00+05:30"** (or an RFC 2822 one) keeps its offset, while an
+/// epoch time stamp, a number or a string with a number inside, is taken as UTC. The precision
+/// of the time stamp is guessed as in [`deserialize_datetime_utc_flexible`].
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_datetime_fixed_offset_flexible")]
+///     starts_at: DateTime<FixedOffset>,
+/// }
+/// fn main() {
+///     let s = r#" { "starts_at": "2023-06-01T12:00:00+05:30" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(*a.starts_at.offset(), FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap());
+///     assert_eq!(a.starts_at.hour(), 12);
+///     assert_eq!(a.starts_at.timestamp(), 1685601000);
+///
+///     let s = r#" { "starts_at": 1685601000 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(*a.starts_at.offset(), FixedOffset::east_opt(0).unwrap());
+///     assert_eq!(a.starts_at.hour(), 6);
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "starts_at": "noon" } "#).is_err());
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_datetime_fixed_offset_flexible<'de, D>(
+    deserializer: D,
+) -> Result<chrono::DateTime<chrono::FixedOffset>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use chrono::prelude::*;
+
+    struct DateTimeFixedOffsetFlexibleVisitor;
+
+    impl DateTimeFixedOffsetFlexibleVisitor {
+        fn from_timestamp<E>(timestamp: i64) -> Result<DateTime<FixedOffset>, E>
+        where
+            E: serde::de::Error,
+        {
+            datetime_utc_from_timestamp_guess(timestamp).map(|dt| dt.into())
+        }
+    }
+
+    impl<'de> Visitor<'de> for DateTimeFixedOffsetFlexibleVisitor {
+        type Value = DateTime<FixedOffset>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an RFC 3339 string, an RFC 2822 string or an epoch time stamp")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<DateTime<FixedOffset>, E>
+        where
+            E: serde::de::Error,
+        {
+            if let Ok(dt) = DateTime::parse_from_rfc3339(v) {
+                Ok(dt)
+            } else if let Ok(dt) = DateTime::parse_from_rfc2822(v) {
+                Ok(dt)
+            } else if let Ok(timestamp) = v.parse::<i64>() {
+                Self::from_timestamp(timestamp)
+            } else {
+                Err(E::custom(format!(
+                    "Could not parse a date time from {:?}, tried RFC 3339, RFC 2822 and an epoch time stamp",
+                    v
+                )))
+            }
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<DateTime<FixedOffset>, E>
+        where
+            E: serde::de::Error,
+        {
+            Self::from_timestamp(v)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<DateTime<FixedOffset>, E>
+        where
+            E: serde::de::Error,
+        {
+            if v > i64::MAX as u64 {
+                return Err(E::custom(format!("Invalid timestamp: {}", v)));
+            }
+            Self::from_timestamp(v as i64)
+        }
+    }
+
+    deserializer.deserialize_any(DateTimeFixedOffsetFlexibleVisitor)
+}
+
+/// Deserializes a `chrono::Weekday` from a full name (`"monday"`), a three-letter abbreviation
+/// (`"Mon"`), both case-insensitive, or an ISO 8601 day number (also as a string): `1` is Monday
+/// and `7` is Sunday, so `0` and `8` are errors.
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_weekday_flexible")]
+///     day: Weekday,
+/// }
+/// fn main() {
+///     for s in &[r#""monday""#, r#""Mon""#, r#""MONDAY""#, "1", r#""1""#] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "day": {} }}"#, s)).unwrap();
+///         assert_eq!(a.day, Weekday::Mon);
+///     }
+///     let a: MyStruct = serde_json::from_str(r#" { "day": 7 } "#).unwrap();
+///     assert_eq!(a.day, Weekday::Sun);
+///
+///     for s in &["0", "8", r#""mo""#] {
+///         let json = format!(r#"{{ "day": {} }}"#, s);
+///         assert!(serde_json::from_str::<MyStruct>(&json).is_err());
+///     }
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_weekday_flexible<'de, D>(deserializer: D) -> Result<chrono::Weekday, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use chrono::Weekday;
+
+    const WEEKDAYS: [(Weekday, &str); 7] = [
+        (Weekday::Mon, "monday"),
+        (Weekday::Tue, "tuesday"),
+        (Weekday::Wed, "wednesday"),
+        (Weekday::Thu, "thursday"),
+        (Weekday::Fri, "friday"),
+        (Weekday::Sat, "saturday"),
+        (Weekday::Sun, "sunday"),
+    ];
+
+    deserializer.deserialize_any(CalendarNameVisitor {
+        values: &WEEKDAYS,
+        kind: "weekday",
+        numbering: "from 1 (Monday) to 7 (Sunday)",
+    })
+}
+
+/// Deserializes a `chrono::Month` from a full name (`"january"`), a three-letter abbreviation
+/// (`"Jan"`), both case-insensitive, or a number from `1` (January) to `12` (December), also as a
+/// string.
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::Month;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_month_flexible")]
+///     month: Month,
+/// }
+/// fn main() {
+///     for s in &[r#""january""#, r#""Jan""#, "1", r#""01""#] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "month": {} }}"#, s)).unwrap();
+///         assert_eq!(a.month, Month::January);
+///     }
+///     let a: MyStruct = serde_json::from_str(r#" { "month": "Sep" } "#).unwrap();
+///     assert_eq!(a.month, Month::September);
+///
+///     for s in &["0", "13", r#""Janu""#] {
+///         let json = format!(r#"{{ "month": {} }}"#, s);
+///         assert!(serde_json::from_str::<MyStruct>(&json).is_err());
+///     }
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_month_flexible<'de, D>(deserializer: D) -> Result<chrono::Month, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use chrono::Month;
+
+    const MONTHS: [(Month, &str); 12] = [
+        (Month::January, "january"),
+        (Month::February, "february"),
+        (Month::March, "march"),
+        (Month::April, "april"),
+        (Month::May, "may"),
+        (Month::June, "june"),
+        (Month::July, "july"),
+        (Month::August, "august"),
+        (Month::September, "september"),
+        (Month::October, "october"),
+        (Month::November, "november"),
+        (Month::December, "december"),
+    ];
+
+    deserializer.deserialize_any(CalendarNameVisitor {
+        values: &MONTHS,
+        kind: "month",
+        numbering: "from 1 (January) to 12 (December)",
+    })
+}
+
+/// Deserializes a `chrono::NaiveDate` from any of the ISO 8601 date forms: a calendar date
+/// **"2023-02-01"**, a week date **"2023-W05-3"** (Wednesday of the fifth ISO week) or an
+/// ordinal date **"2023-032"** (the 32nd day of the year), each also in the basic form without
+/// the hyphens (`"20230201"`, `"2023W053"`, `"2023032"`).
+///
+/// A week which the year does not have (`W00`, `W54`, or `W53` in a year of 52 weeks), a day of
+/// the year after its last one (`367`, or `366` in a common year) and the like are errors
+/// telling the reason.
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::NaiveDate;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_naive_date_iso_extended")]
+///     shipped_on: NaiveDate,
+/// }
+/// fn main() {
+///     let expected = NaiveDate::from_ymd_opt(2023, 1, 30).unwrap();
+///     for s in &["2023-01-30", "2023-W05-1", "2023-030", "20230130", "2023W051", "2023030"] {
+///         let json = format!(r#"{{ "shipped_on": "{}" }}"#, s);
+///         let a: MyStruct = serde_json::from_str(&json).unwrap();
+///         assert_eq!(a.shipped_on, expected);
+///     }
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "shipped_on": "2023-W54-1" } "#).unwrap_err();
+///     assert!(e.to_string().contains("2023 has 52 weeks"));
+///     let e = serde_json::from_str::<MyStruct>(r#" { "shipped_on": "2023-367" } "#).unwrap_err();
+///     assert!(e.to_string().contains("2023 has 365 days"));
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "shipped_on": "2023-1-30" } "#).is_err());
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub fn deserialize_naive_date_iso_extended<'de, D>(
+    deserializer: D,
+) -> Result<chrono::NaiveDate, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use chrono::{Datelike, NaiveDate, Weekday};
+
+    #[derive(Clone, Copy)]
+    enum Form {
+        Calendar,
+        Week,
+        Ordinal,
+    }
+
+    /// The forms, `#` standing for a digit.
+    const FORMS: [(&str, Form); 6] = [
+        ("####-##-##", Form::Calendar),
+        ("########", Form::Calendar),
+        ("####-W##-#", Form::Week),
+        ("####W###", Form::Week),
+        ("####-###", Form::Ordinal),
+        ("#######", Form::Ordinal),
+    ];
+
+    fn matches(v: &str, pattern: &str) -> bool {
+        v.len() == pattern.len()
+            && v.bytes().zip(pattern.bytes()).all(|(c, p)| match p {
+                b'#' => c.is_ascii_digit(),
+                _ => c == p,
+            })
+    }
+
+    fn parse(v: &str) -> Result<NaiveDate, Option<String>> {
+        let form = match FORMS.iter().find(|(pattern, _)| matches(v, pattern)) {
+            Some(&(_, form)) => form,
+            None => return Err(None),
+        };
+        let digits: Vec<u32> = v
+            .bytes()
+            .filter(u8::is_ascii_digit)
+            .map(|c| u32::from(c - b'0'))
+            .collect();
+        let number =
+            |range: core::ops::Range<usize>| digits[range].iter().fold(0, |n, d| n * 10 + d);
+        let year = number(0..4) as i32;
+        let invalid = |reason: String| Some(format!("invalid date {:?}: {}", v, reason));
+        match form {
+            Form::Calendar => {
+                let (month, day) = (number(4..6), number(6..8));
+                if !(1..=12).contains(&month) {
+                    return Err(invalid(format!("no month {}", month)));
+                }
+                NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| {
+                    let days = (28..=31)
+                        .rev()
+                        .find(|&day| NaiveDate::from_ymd_opt(year, month, day).is_some())
+                        .unwrap_or(0);
+                    invalid(format!(
+                        "no day {}, {}-{:02} has {} days",
+                        day, year, month, days
+                    ))
+                })
+            }
+            Form::Week => {
+                let (week, weekday) = (number(4..6), number(6..7));
+                let weeks =
+                    NaiveDate::from_ymd_opt(year, 12, 28).map_or(52, |d| d.iso_week().week());
+                if !(1..=weeks).contains(&week) {
+                    return Err(invalid(format!(
+                        "no week {}, {} has {} weeks",
+                        week, year, weeks
+                    )));
+                }
+                let weekday = match weekday {
+                    1 => Weekday::Mon,
+                    2 => Weekday::Tue,
+                    3 => Weekday::Wed,
+                    4 => Weekday::Thu,
+                    5 => Weekday::Fri,
+                    6 => Weekday::Sat,
+                    7 => Weekday::Sun,
+                    _ => {
+                        return Err(invalid(format!(
+                            "no day {} of the week, expected 1 (Monday) to 7 (Sunday)",
+                            weekday
+                        )))
+                    }
+                };
+                NaiveDate::from_isoywd_opt(year, week, weekday)
+                    .ok_or_else(|| invalid("out of range".to_owned()))
+            }
+            Form::Ordinal => {
+                let ordinal = number(4..7);
+                let days = if NaiveDate::from_ymd_opt(year, 2, 29).is_some() {
+                    366
+                } else {
+                    365
+                };
+                if !(1..=days).contains(&ordinal) {
+                    return Err(invalid(format!(
+                        "no day {}, {} has {} days",
+                        ordinal, year, days
+                    )));
+                }
+                NaiveDate::from_yo_opt(year, ordinal)
+                    .ok_or_else(|| invalid("out of range".to_owned()))
+            }
+        }
+    }
+
+    struct NaiveDateIsoExtendedVisitor;
+
+    impl<'de> Visitor<'de> for NaiveDateIsoExtendedVisitor {
+        type Value = NaiveDate;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an ISO 8601 calendar, week or ordinal date like \"2023-02-01\", \"2023-W05-3\" or \"2023-032\"")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<NaiveDate, E>
+        where
+            E: serde::de::Error,
+        {
+            parse(v).map_err(|reason| match reason {
+                Some(reason) => E::custom(reason),
+                None => E::invalid_value(serde::de::Unexpected::Str(v), &self),
+            })
+        }
+    }
+
+    deserializer.deserialize_str(NaiveDateIsoExtendedVisitor)
+}
+
+/// Picks a value from the 1-based `values` by its number, its lowercase full name or the first
+/// three letters of the name.
+#[cfg(feature = "chrono")]
+struct CalendarNameVisitor<T: 'static> {
+    values: &'static [(T, &'static str)],
+    kind: &'static str,
+    numbering: &'static str,
+}
+
+#[cfg(feature = "chrono")]
+impl<'de, T: Copy + 'static> Visitor<'de> for CalendarNameVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a {} name, its three-letter abbreviation or a number {}",
+            self.kind, self.numbering
+        )
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        if let Ok(number) = v.parse::<u64>() {
+            return self.visit_u64(number);
+        }
+        let name = v.to_lowercase();
+        self.values
+            .iter()
+            .find(|(_, full)| name == *full || name == full[..3])
+            .map(|&(value, _)| value)
+            .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        match v.checked_sub(1).and_then(|i| self.values.get(i as usize)) {
+            Some(&(value, _)) => Ok(value),
+            None => Err(E::invalid_value(serde::de::Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        if v < 0 {
+            return Err(E::invalid_value(serde::de::Unexpected::Signed(v), &self));
+        }
+        self.visit_u64(v as u64)
+    }
+}
+
+/// Builds a deserializer of a `chrono::DateTime<Utc>` from the naive date time strings like
+/// `"2023-06-01 12:00:00"`, which are implicitly in the local time of the given time zone: a
+/// `chrono::FixedOffset`, `chrono::Local` or any other `chrono::TimeZone`, like the ones of the
+/// `chrono-tz` crate.
+///
+/// - The strings are parsed with the formats given to [`formats`](Self::formats), by default
+///   `"%Y-%m-%d %H:%M:%S%.f"` and `"%Y-%m-%dT%H:%M:%S%.f"`.
+/// - A local time which happens twice (when the clocks are turned back) is taken with the
+///   earliest offset, that is the first of the two moments.
+/// - A local time which does not exist (when the clocks are turned forward) is an error.
+/// - The epoch time stamps, the numbers and the strings with a number inside, don't depend on
+///   the time zone and are converted as in [`deserialize_datetime_utc_flexible`].
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+/// use serde_aux::prelude::*;
+///
+/// fn berlin_time<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     NaiveInTimezone::new(chrono_tz::Europe::Berlin).into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "berlin_time")]
+///     time: DateTime<Utc>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "time": "2023-06-01 12:00:00" } "#).unwrap();
+///     assert_eq!(a.time, Utc.with_ymd_and_hms(2023, 6, 1, 10, 0, 0).unwrap());
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "time": 1685613600 } "#).unwrap();
+///     assert_eq!(a.time, Utc.with_ymd_and_hms(2023, 6, 1, 10, 0, 0).unwrap());
+///
+///     // 02:30 happens twice on the 29th of October in Berlin, the first one is in summer time.
+///     let a: MyStruct = serde_json::from_str(r#" { "time": "2023-10-29 02:30:00" } "#).unwrap();
+///     assert_eq!(a.time, Utc.with_ymd_and_hms(2023, 10, 29, 0, 30, 0).unwrap());
+///
+///     // 02:30 is skipped on the 26th of March.
+///     let s = r#" { "time": "2023-03-26 02:30:00" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone)]
+pub struct NaiveInTimezone<'a, Tz> {
+    timezone: Tz,
+    formats: &'a [&'a str],
+}
+
+#[cfg(feature = "chrono")]
+impl<'a, Tz: chrono::TimeZone> NaiveInTimezone<'a, Tz> {
+    /// Creates the builder for the naive date times in the given time zone.
+    pub fn new(timezone: Tz) -> Self {
+        NaiveInTimezone {
+            timezone,
+            formats: &["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"],
+        }
+    }
+
+    /// Sets the `chrono` formats of the naive date times, tried in the order. An empty list is
+    /// ignored.
+    pub fn formats(mut self, formats: &'a [&'a str]) -> Self {
+        if !formats.is_empty() {
+            self.formats = formats;
+        }
+        self
+    }
+
+    /// Returns the deserialize function.
+    pub fn into_deserializer<'de, D>(
+        self,
+    ) -> impl FnMut(D) -> Result<chrono::DateTime<chrono::Utc>, D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        Tz: 'a,
+    {
+        move |deserializer| deserializer.deserialize_any(NaiveInTimezoneVisitor(&self))
+    }
+
+    fn to_utc<E>(&self, v: &str) -> Result<chrono::DateTime<chrono::Utc>, E>
+    where
+        E: serde::de::Error,
+    {
+        use chrono::LocalResult;
+
+        let naive = self
+            .formats
+            .iter()
+            .find_map(|format| chrono::NaiveDateTime::parse_from_str(v, format).ok())
+            .ok_or_else(|| {
+                E::custom(format!(
+                    "Could not parse a date time from {:?}, tried the formats {:?}",
+                    v, self.formats
+                ))
+            })?;
+        match self.timezone.from_local_datetime(&naive) {
+            LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => {
+                Ok(dt.with_timezone(&chrono::Utc))
+            }
+            LocalResult::None => Err(E::custom(format!(
+                "the local time {:?} does not exist in the time zone",
+                v
+            ))),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+struct NaiveInTimezoneVisitor<'b, 'a, Tz>(&'b NaiveInTimezone<'a, Tz>);
+
+#[cfg(feature = "chrono")]
+impl<'de, 'b, 'a, Tz: chrono::TimeZone> Visitor<'de> for NaiveInTimezoneVisitor<'b, 'a, Tz> {
+    type Value = chrono::DateTime<chrono::Utc>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a naive date time string or an epoch time stamp")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        match v.parse::<i64>() {
+            Ok(timestamp) => datetime_utc_from_timestamp_guess(timestamp),
+            Err(_) => self.0.to_utc(v),
+        }
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        datetime_utc_from_timestamp_guess(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if v > i64::MAX as u64 {
+            return Err(E::custom(format!("Invalid timestamp: {}", v)));
+        }
+        datetime_utc_from_timestamp_guess(v as i64)
+    }
+}
+
+/// Deserializes a number from string or a number.
+///
+/// The value is read with `deserialize_any`, so it works with any self-describing format,
+/// MessagePack and CBOR included. The formats which are not self-describing, like `bincode`,
+/// can't tell what kind of value comes next; wrap the helper with
+/// [`canonical_deserialize_with`](crate::canonical_deserialize_with) to read the value there as
+/// `T` itself.
+///
+/// A single leading `+` is accepted for all the primitive number types, the unsigned ones
+/// included, as their `FromStr` does: `"+42"` is `42`, while `"++42"` and `"+-42"` are errors.
+/// The same holds for the other numeric string helpers of this module.
+///
+/// When an integral string does not fit into a primitive integer type, the error says so and
+/// gives the range of the type, like `value 300 out of range for u8 (0..=255)`, instead of the
+/// generic error of `FromStr`; the other numeric string helpers do the same.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_number_from_string")]
+///     number_from_string: u64,
+/// }
+/// fn main() {
+///     let s = r#" { "number_from_string": "123" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.number_from_string, 123);
+///
+///     let s = r#" { "number_from_string": 444 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.number_from_string, 444);
+///
+///     let s = r#" { "number_from_string": "+42" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.number_from_string, 42);
+///
+///     let s = r#" { "number_from_string": "++42" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+///
+/// The 128-bit integers are supported as well. Note that `serde_json` does not pass the numbers
+/// which do not fit into 64 bits to the deserializer as integers, so such values must come as
+/// strings there; the formats which do report `i128`/`u128` values work with raw numbers too.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_number_from_string")]
+///     amount: u128,
+/// }
+/// fn main() {
+///     let s = r#" { "amount": "340282366920938463463374607431768211455" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.amount, u128::MAX);
+///
+///     let s = r#" { "amount": 42 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.amount, 42);
+/// }
+/// ```
+///
+/// The value is not buffered (unlike with `#[serde(untagged)]`): the string or the number given
+/// by `deserialize_any` goes straight to `FromStr` or to the `Deserialize` of `T`. That code is
+/// monomorphized for every primitive, so there is no separate visitor per primitive type.
+///
+/// For making it work with strong types you must implement `FromStr` trait. It is quite simple.
+///
+/// # Example
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use std::num::{ParseIntError, ParseFloatError};
+///
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct IntId(u64);
+///
+/// impl FromStr for IntId {
+///     type Err = ParseIntError;
+///
+///     fn from_str(s: &str) -> Result<IntId, Self::Err> {
+///         Ok(IntId(u64::from_str(s)?))
+///     }
+/// }
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_number_from_string")]
+///     int_id: IntId,
+/// }
+/// fn main() {
+///     let s = r#"{ "int_id": "123" }"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.int_id.0, 123);
+///
+///     let s = r#"{ "int_id": 444 }"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.int_id.0, 444);
+///
+/// }
+/// ```
+pub fn deserialize_number_from_string<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + serde::Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    deserializer.deserialize_any(NumberFromStringVisitor(PhantomData))
+}
+
+/// Accepts either a number, which is handed over to the `Deserialize` implementation of `T`,
+/// or a string, which is parsed with `FromStr`.
+struct NumberFromStringVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for NumberFromStringVisitor<T>
+where
+    T: FromStr + serde::Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or a numeric string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        v.parse::<T>().map_err(|e| {
+            E::custom(format_args!(
+                "invalid value: string {:?}, expected {}: {}",
+                v,
+                Expecting(&self),
+                describe_number_error::<T>(v, e)
+            ))
+        })
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        T::deserialize(PrimitiveDeserializer::new(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        T::deserialize(PrimitiveDeserializer::new(v))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        T::deserialize(PrimitiveDeserializer::new(v))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        T::deserialize(PrimitiveDeserializer::new(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        T::deserialize(PrimitiveDeserializer::new(v))
+    }
+}
+
+/// Explains why a numeric string could not be parsed into `T`. For the primitive integers an
+/// integral value which does not fit into the type is reported with the type's range, like
+/// `value 300 out of range for u8 (0..=255)`; otherwise it is the error of `FromStr` as is.
+fn describe_number_error<'de, T: Deserialize<'de>>(number: &str, error: impl Display) -> String {
+    match integer_range::<T>() {
+        Some((name, min, max)) if is_integer_out_of_range(number, min, max) => format!(
+            "value {} out of range for {} ({}..={})",
+            number, name, min, max
+        ),
+        _ => error.to_string(),
+    }
+}
+
+/// Returns the name and the range of `T` if it is a primitive integer. It is told by the
+/// primitive the `Deserialize` implementation of `T` asks for, so `isize` and `usize` are
+/// reported as `i64` and `u64`, which serde reads them as.
+fn integer_range<'de, T: Deserialize<'de>>() -> IntegerRange {
+    let range = core::cell::Cell::new(None);
+    let _ = T::deserialize(IntegerRangeProbe(&range));
+    range.get()
+}
+
+/// The name, the minimum and the maximum of a primitive integer.
+type IntegerRange = Option<(&'static str, i128, u128)>;
+
+/// A deserializer recording the range of the integer asked for and failing right away.
+struct IntegerRangeProbe<'a>(&'a core::cell::Cell<IntegerRange>);
+
+macro_rules! probe_integer_ranges {
+    ($($method:ident => $ty:ident,)*) => {
+        $(
+            fn $method<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.0
+                    .set(Some((stringify!($ty), $ty::MIN as i128, $ty::MAX as u128)));
+                Err(serde::de::Error::custom("the range is probed"))
+            }
+        )*
+    };
+}
+
+impl<'a, 'de> Deserializer<'de> for IntegerRangeProbe<'a> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(serde::de::Error::custom("not a primitive integer"))
+    }
+
+    probe_integer_ranges! {
+        deserialize_i8 => i8,
+        deserialize_i16 => i16,
+        deserialize_i32 => i32,
+        deserialize_i64 => i64,
+        deserialize_i128 => i128,
+        deserialize_u8 => u8,
+        deserialize_u16 => u16,
+        deserialize_u32 => u32,
+        deserialize_u64 => u64,
+        deserialize_u128 => u128,
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf option unit unit_struct newtype_struct seq
+        tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Tells whether the string is an integer (the digits with an optional sign) outside of
+/// `min..=max`. The integers too long even for `i128` or `u128` are out of range.
+fn is_integer_out_of_range(number: &str, min: i128, max: u128) -> bool {
+    let (negative, digits) = match number.as_bytes().first() {
+        Some(b'-') => (true, &number[1..]),
+        Some(b'+') => (false, &number[1..]),
+        _ => (false, number),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    if negative {
+        number.parse::<i128>().map_or(true, |value| value < min)
+    } else {
+        digits.parse::<u128>().map_or(true, |value| value > max)
+    }
+}
+
+/// Displays what a visitor expects, in order to reuse it in the custom error messages.
+struct Expecting<'a, V>(&'a V);
+
+impl<'a, 'de, V: Visitor<'de>> Display for Expecting<'a, V> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+}
+
+/// A deserializer holding a single already visited primitive value. Unlike the ones from
+/// `serde::de::value` it also passes itself to `visit_newtype_struct` and `visit_some`, as the
+/// self-describing formats do, so the newtypes and options around numbers keep working.
+struct PrimitiveDeserializer<V, E> {
+    value: V,
+    marker: PhantomData<E>,
+}
+
+impl<V, E> PrimitiveDeserializer<V, E> {
+    fn new(value: V) -> Self {
+        PrimitiveDeserializer {
+            value,
+            marker: PhantomData,
+        }
+    }
+}
+
+macro_rules! impl_primitive_deserializer {
+    ($($ty:ty => $visit:ident,)*) => {
+        $(
+            impl<'de, E> Deserializer<'de> for PrimitiveDeserializer<$ty, E>
+            where
+                E: serde::de::Error,
+            {
+                type Error = E;
+
+                fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, E>
+                where
+                    V: Visitor<'de>,
+                {
+                    visitor.$visit(self.value)
+                }
+
+                fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, E>
+                where
+                    V: Visitor<'de>,
+                {
+                    visitor.visit_some(self)
+                }
+
+                fn deserialize_newtype_struct<V>(
+                    self,
+                    _name: &'static str,
+                    visitor: V,
+                ) -> Result<V::Value, E>
+                where
+                    V: Visitor<'de>,
+                {
+                    visitor.visit_newtype_struct(self)
+                }
+
+                serde::forward_to_deserialize_any! {
+                    bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+                    bytes byte_buf unit unit_struct seq tuple tuple_struct map struct enum
+                    identifier ignored_any
+                }
+            }
+        )*
+    };
+}
+impl_primitive_deserializer! {
+    i64 => visit_i64,
+    u64 => visit_u64,
+    i128 => visit_i128,
+    u128 => visit_u128,
+    f64 => visit_f64,
+}
+
+/// Deserializes an option number from string or a number.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(Debug, serde::Deserialize)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_option_number_from_string")]
+///     option_num: Option<f32>,
+///     #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+///     missing: Option<i32>
+/// }
+/// fn serde_qs_eq(s: &str, result: Option<f32>) {
+///     let a: MyStruct = serde_qs::from_str(s).unwrap();
+///     assert_eq!(a.option_num, result);
+///     assert_eq!(a.missing, None);
+/// }
+/// fn serde_qs_err(s: &str) {
+///     assert!(serde_qs::from_str::<MyStruct>(s).is_err());
+/// }
+/// fn serde_json_eq(s: &str, result: Option<f32>) {
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.option_num, result);
+///     assert_eq!(a.missing, None);
+/// }
+/// fn serde_json_err(s: &str) {
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// fn main() {
+///     serde_qs_eq("option_num=1", Some(1.0));
+///     serde_qs_eq("option_num=-1", Some(-1.0));
+///     serde_qs_eq("option_num=0.1", Some(0.1));
+///     serde_qs_eq("option_num=-0.1", Some(-0.1));
+///     serde_qs_eq("option_num=", None);
+///     serde_qs_eq("option_num", None);
+///
+///     serde_qs_err("option_num=true");
+///     serde_qs_err("option_num=a");
+///     serde_qs_err("option_num[a]=");
+///     serde_qs_err("option_num[]=");
+///
+///     serde_json_eq(r#" { "option_num": "1" } "#, Some(1.0));
+///     serde_json_eq(r#" { "option_num": "-1" } "#, Some(-1.0));
+///     serde_json_eq(r#" { "option_num": "0.1" } "#, Some(0.1));
+///     serde_json_eq(r#" { "option_num": "-0.1" } "#, Some(-0.1));
+///     serde_json_eq(r#" { "option_num": 1 } "#, Some(1.0));
+///     serde_json_eq(r#" { "option_num": -1 } "#, Some(-1.0));
+///     serde_json_eq(r#" { "option_num": 0.1 } "#, Some(0.1));
+///     serde_json_eq(r#" { "option_num": -0.1 } "#, Some(-0.1));
+///     serde_json_eq(r#" { "option_num": "" } "#, None);
+///     serde_json_eq(r#" { "option_num": null } "#, None);
+///
+///     serde_json_err(r#" { "option_num": true } "#);
+///     serde_json_err(r#" { "option_num": "a" } "#);
+///     serde_json_err(r#" { "option_num": {} } "#);
+///     serde_json_err(r#" { "option_num": [] } "#);
+/// }
+/// ```
+pub fn deserialize_option_number_from_string<'de, T, D>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + serde::Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    deserializer.deserialize_any(OptionNumberFromStringVisitor(PhantomData))
+}
+
+/// The same as [`NumberFromStringVisitor`] but also maps `null` and an empty string to `None`.
+struct OptionNumberFromStringVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for OptionNumberFromStringVisitor<T>
+where
+    T: FromStr + serde::Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number, a numeric string or null")
+    }
+
+    fn visit_none<E>(self) -> Result<Option<T>, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_unit<E>(self) -> Result<Option<T>, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Option<T>, E>
+    where
+        E: serde::de::Error,
+    {
+        match v {
+            "" => Ok(None),
+            _ => NumberFromStringVisitor(PhantomData).visit_str(v).map(Some),
+        }
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Option<T>, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i64(v).map(Some)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Option<T>, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u64(v).map(Some)
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Option<T>, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i128(v).map(Some)
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Option<T>, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u128(v).map(Some)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Option<T>, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_f64(v).map(Some)
+    }
+}
+
+macro_rules! wrap_option_number_from_string_fn {
+    (
+        $(#[$attr:meta])*
+        $func:ident,
+        $res:ty
+    ) => {
+        $(#[$attr])*
+        pub fn $func<'de, T, D>(deserializer: D) -> Result<$res, D::Error>
+        where
+            D: Deserializer<'de>,
+            T: FromStr + serde::Deserialize<'de>,
+            <T as FromStr>::Err: Display,
+        {
+            deserialize_option_number_from_string::<T, D>(deserializer).map(Into::into)
+        }
+    };
+}
+wrap_option_number_from_string_fn!(
+    /// Deserializes a `Cell` option number from string or a number. Same logic as [`"deserialize_option_number_from_string"`](https://docs.rs/serde-aux/latest/serde_aux/field_attributes/fn.deserialize_option_number_from_string.html).
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use serde_aux::prelude::*;
+    /// use std::cell::Cell;
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct MyStruct {
+    ///     #[serde(deserialize_with = "deserialize_cell_option_number_from_string")]
+    ///     v: Cell<Option<f32>>
+    /// }
+    /// fn main() {
+    ///     let a = serde_qs::from_str::<MyStruct>("v=-0.1").unwrap();
+    ///     assert_eq!(a.v, Cell::new(Some(-0.1)));
+    /// }
+    /// ```
+    deserialize_cell_option_number_from_string,
+    core::cell::Cell<Option<T>>
+);
+wrap_option_number_from_string_fn!(
+    /// Deserializes a `RefCell` option number from string or a number. Same logic as [`"deserialize_option_number_from_string"`](https://docs.rs/serde-aux/latest/serde_aux/field_attributes/fn.deserialize_option_number_from_string.html).
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use serde_aux::prelude::*;
+    /// use std::cell::RefCell;
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct MyStruct {
+    ///     #[serde(default, deserialize_with = "deserialize_ref_cell_option_number_from_string")]
+    ///     v: RefCell<Option<f32>>
+    /// }
+    /// fn main() {
+    ///     let a = serde_qs::from_str::<MyStruct>("v=-0.1").unwrap();
+    ///     assert_eq!(a.v, RefCell::new(Some(-0.1)));
+    /// }
+    /// ```
+    deserialize_ref_cell_option_number_from_string,
+    core::cell::RefCell<Option<T>>
+);
+wrap_option_number_from_string_fn!(
+    /// Deserializes a `Mutex` option number from string or a number. Same logic as [`"deserialize_option_number_from_string"`](https://docs.rs/serde-aux/latest/serde_aux/field_attributes/fn.deserialize_option_number_from_string.html).
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use serde_aux::prelude::*;
+    /// use std::sync::Mutex;
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct MyStruct {
+    ///     #[serde(default, deserialize_with = "deserialize_mutex_option_number_from_string")]
+    ///     v: Mutex<Option<f32>>
+    /// }
+    /// fn main() {
+    ///     let a = serde_qs::from_str::<MyStruct>("v=-0.1").unwrap();
+    ///     assert_eq!(*a.v.lock().unwrap(), Some(-0.1));
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    deserialize_mutex_option_number_from_string,
+    std::sync::Mutex<Option<T>>
+);
+wrap_option_number_from_string_fn!(
+    /// Deserializes a `RwLock` option number from string or a number. Same logic as [`"deserialize_option_number_from_string"`](https://docs.rs/serde-aux/latest/serde_aux/field_attributes/fn.deserialize_option_number_from_string.html).
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use serde_aux::prelude::*;
+    /// use std::sync::RwLock;
+    ///
+    /// #[derive(Debug, serde::Deserialize)]
+    /// struct MyStruct {
+    ///     #[serde(default, deserialize_with = "deserialize_rw_lock_option_number_from_string")]
+    ///     v: RwLock<Option<f32>>
+    /// }
+    /// fn main() {
+    ///     let a = serde_qs::from_str::<MyStruct>("v=-0.1").unwrap();
+    ///     assert_eq!(*a.v.read().unwrap(), Some(-0.1));
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    deserialize_rw_lock_option_number_from_string,
+    std::sync::RwLock<Option<T>>
+);
+
+/// Builds a deserializer of an `Option<T>` mapping the configured "no value" representations to
+/// `None`, for the providers which have their own idea of an unset field. `null` is always `None`;
+/// the other values are deserialized as [`deserialize_number_from_string`] does, so `T` can come
+/// either as itself or as a string parsed with `FromStr`.
+///
+/// - [`empty_string`](Self::empty_string): the strings which are empty after trimming.
+/// - [`zero`](Self::zero): the number zero, also as a string like `"0"` or `"0.00"`; a string
+///   like `"1e-400"` is not zero, even though it is too small for an `f64`.
+/// - [`token`](Self::token): the strings equal to the token after trimming, ignoring the ASCII
+///   case, like `"N/A"` or `"none"`.
+///
+/// Each of them has an `or_` counterpart for chaining.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn price<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     NoneIf::empty_string()
+///         .or_zero()
+///         .or_token("N/A")
+///         .into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "price")]
+///     price: Option<f64>,
+/// }
+///
+/// fn main() {
+///     for s in &[r#""""#, r#"" ""#, "0", r#""0.00""#, r#""n/a""#, "null"] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "price": {} }}"#, s)).unwrap();
+///         assert_eq!(a.price, None);
+///     }
+///
+///     for s in &["1.5", r#""1.5""#] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "price": {} }}"#, s)).unwrap();
+///         assert_eq!(a.price, Some(1.5));
+///     }
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "price": "-" } "#).is_err());
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NoneIf<'a> {
+    empty_string: bool,
+    zero: bool,
+    tokens: Vec<&'a str>,
+}
+
+impl<'a> NoneIf<'a> {
+    /// Creates the builder mapping only `null` to `None`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the builder mapping the empty strings to `None`.
+    pub fn empty_string() -> Self {
+        Self::new().or_empty_string()
+    }
+
+    /// Creates the builder mapping zero to `None`.
+    pub fn zero() -> Self {
+        Self::new().or_zero()
+    }
+
+    /// Creates the builder mapping the token to `None`.
+    pub fn token(token: &'a str) -> Self {
+        Self::new().or_token(token)
+    }
+
+    /// Maps the empty strings to `None` as well.
+    pub fn or_empty_string(mut self) -> Self {
+        self.empty_string = true;
+        self
+    }
+
+    /// Maps zero to `None` as well.
+    pub fn or_zero(mut self) -> Self {
+        self.zero = true;
+        self
+    }
+
+    /// Maps the token to `None` as well.
+    pub fn or_token(mut self, token: &'a str) -> Self {
+        self.tokens.push(token);
+        self
+    }
+
+    /// Returns the deserialize function.
+    pub fn into_deserializer<'de, T, D>(self) -> impl FnMut(D) -> Result<Option<T>, D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        T: FromStr + Deserialize<'de>,
+        <T as FromStr>::Err: Display,
+    {
+        move |deserializer| {
+            deserializer.deserialize_any(NoneIfVisitor {
+                config: &self,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    fn is_none_str(&self, v: &str) -> bool {
+        let v = v.trim();
+        (self.empty_string && v.is_empty())
+            || (self.zero && is_decimal_zero(v))
+            || self
+                .tokens
+                .iter()
+                .any(|token| token.eq_ignore_ascii_case(v))
+    }
+}
+
+struct NoneIfVisitor<'b, 'a, T> {
+    config: &'b NoneIf<'a>,
+    marker: PhantomData<T>,
+}
+
+impl<'de, 'b, 'a, T> Visitor<'de> for NoneIfVisitor<'b, 'a, T>
+where
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an optional number or a numeric string")
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if self.config.is_none_str(v) {
+            return Ok(None);
+        }
+        NumberFromStringVisitor(PhantomData).visit_str(v).map(Some)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        use serde::de::IntoDeserializer;
+
+        T::deserialize(v.into_deserializer()).map(Some)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if self.config.zero && v == 0 {
+            return Ok(None);
+        }
+        NumberFromStringVisitor(PhantomData).visit_i64(v).map(Some)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if self.config.zero && v == 0 {
+            return Ok(None);
+        }
+        NumberFromStringVisitor(PhantomData).visit_u64(v).map(Some)
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if self.config.zero && v == 0 {
+            return Ok(None);
+        }
+        NumberFromStringVisitor(PhantomData).visit_i128(v).map(Some)
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if self.config.zero && v == 0 {
+            return Ok(None);
+        }
+        NumberFromStringVisitor(PhantomData).visit_u128(v).map(Some)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        if self.config.zero && v == 0.0 {
+            return Ok(None);
+        }
+        NumberFromStringVisitor(PhantomData).visit_f64(v).map(Some)
+    }
+}
+
+/// Builds a deserializer of a scalar `T` applying several coercions at once, in this order:
+///
+/// 1. [`trim`](Self::trim): the strings are trimmed.
+/// 2. [`empty_as_default`](Self::empty_as_default): an empty string (after trimming, if enabled)
+///    gives `T::default()`.
+/// 3. [`from_string_or_number`](Self::from_string_or_number): the strings are parsed with
+///    `FromStr`, as [`deserialize_number_from_string`] does. Without it a string is given to the
+///    `Deserialize` of `T` as is, which suits the `String`s and the enums.
+/// 4. [`clamp`](Self::clamp): the value is limited to the range.
+///
+/// The numbers, the booleans and `null` go to the `Deserialize` of `T` and then are clamped. The
+/// builder is `const`-constructible, apart from the clamping bounds, and cheap to build per call.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn percent<'de, D>(deserializer: D) -> Result<u32, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     Coerce::new()
+///         .trim()
+///         .empty_as_default()
+///         .from_string_or_number()
+///         .clamp(0, 100)
+///         .into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "percent")]
+///     progress: u32,
+/// }
+///
+/// fn main() {
+///     for &(s, expected) in &[(r#"" 42 ""#, 42), ("42", 42), (r#""  ""#, 0), (r#""250""#, 100)] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "progress": {} }}"#, s)).unwrap();
+///         assert_eq!(a.progress, expected);
+///     }
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "progress": "a lot" } "#).is_err());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Coerce<T> {
+    trim: bool,
+    empty_as_default: bool,
+    from_string_or_number: bool,
+    clamp: Option<(T, T)>,
+}
+
+impl<T> Default for Coerce<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Coerce<T> {
+    /// Creates the builder with no coercions.
+    pub const fn new() -> Self {
+        Coerce {
+            trim: false,
+            empty_as_default: false,
+            from_string_or_number: false,
+            clamp: None,
+        }
+    }
+
+    /// Trims the strings.
+    pub const fn trim(mut self) -> Self {
+        self.trim = true;
+        self
+    }
+
+    /// Gives `T::default()` for the empty strings.
+    pub const fn empty_as_default(mut self) -> Self {
+        self.empty_as_default = true;
+        self
+    }
+
+    /// Parses the strings with `FromStr`.
+    pub const fn from_string_or_number(mut self) -> Self {
+        self.from_string_or_number = true;
+        self
+    }
+
+    /// Limits the value to `min..=max`.
+    pub fn clamp(mut self, min: T, max: T) -> Self {
+        self.clamp = Some((min, max));
+        self
+    }
+
+    /// Returns the deserialize function.
+    pub fn into_deserializer<'de, D>(self) -> impl FnMut(D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr + Deserialize<'de> + Default + PartialOrd + Clone,
+        <T as FromStr>::Err: Display,
+    {
+        move |deserializer| {
+            let value = deserializer.deserialize_any(CoerceVisitor(&self))?;
+            Ok(match &self.clamp {
+                Some((min, _)) if value < *min => min.clone(),
+                Some((_, max)) if value > *max => max.clone(),
+                _ => value,
+            })
+        }
+    }
+}
+
+struct CoerceVisitor<'a, T>(&'a Coerce<T>);
+
+impl<'a, 'de, T> Visitor<'de> for CoerceVisitor<'a, T>
+where
+    T: FromStr + Deserialize<'de> + Default,
+    <T as FromStr>::Err: Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string, a number, a boolean or null")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        use serde::de::IntoDeserializer;
+
+        let v = if self.0.trim { v.trim() } else { v };
+        if v.is_empty() && self.0.empty_as_default {
+            Ok(T::default())
+        } else if self.0.from_string_or_number {
+            NumberFromStringVisitor(PhantomData).visit_str(v)
+        } else {
+            T::deserialize(v.into_deserializer())
+        }
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        use serde::de::IntoDeserializer;
+
+        T::deserialize(v.into_deserializer())
+    }
+
+    fn visit_unit<E>(self) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        use serde::de::IntoDeserializer;
+
+        T::deserialize(().into_deserializer())
+    }
+
+    fn visit_none<E>(self) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_unit()
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i64(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u64(v)
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i128(v)
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u128(v)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_f64(v)
+    }
+}
+
+/// Deserializes boolean from anything (string, number, boolean). If input is a string,
+/// it is expected, that it is possible to convert it to a number. The return boolean is
+/// `true` if the number was either `1` or `1.0` after parsing.
+///
+/// For the formats which are not self-describing, like `bincode`, see
+/// [`canonical_deserialize_with`](crate::canonical_deserialize_with).
+///
+/// # Example
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_bool_from_anything")]
+///     boolean: bool,
+/// }
+/// fn main() {
+///     let s = r#"{ "boolean": 1.0 }"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(a.boolean);
+///
+///     let s = r#"{ "boolean": 0.0 }"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(!a.boolean);
+///
+///     let s = r#"{ "boolean": 2.3 }"#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+///
+///     let s = r#"{ "boolean": 1 }"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(a.boolean);
+///
+///     let s = r#"{ "boolean": 0 }"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(!a.boolean);
+///
+///     let s = r#"{ "boolean": 2 }"#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+///
+///     let s = r#"{ "boolean": "1.0" }"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(a.boolean);
+///
+///     let s = r#"{ "boolean": "0.0" }"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(!a.boolean);
+///
+///     let s = r#"{ "boolean": "2.3" }"#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+///
+///     let s = r#"{ "boolean": "1" }"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(a.boolean);
+///
+///     let s = r#"{ "boolean": "0" }"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(!a.boolean);
+///
+///     let s = r#"{ "boolean": "2" }"#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+///
+///     let s = r#"{ "boolean": "foo" }"#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_bool_from_anything<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(BoolFromAnythingVisitor)
+}
+
+struct BoolFromAnythingVisitor;
+
+impl BoolFromAnythingVisitor {
+    fn from_i64<E: serde::de::Error>(i: i64) -> Result<bool, E> {
+        match i {
+            1 => Ok(true),
+            0 => Ok(false),
+            _ => Err(E::custom("The number is neither 1 nor 0")),
+        }
+    }
+
+    fn from_f64<E: serde::de::Error>(f: f64) -> Result<bool, E> {
+        // `f64::abs` needs `std` on the older compilers.
+        let difference = f - 1.0f64;
+        if difference < f64::EPSILON && difference > -f64::EPSILON {
+            Ok(true)
+        } else if f == 0.0f64 {
+            Ok(false)
+        } else {
+            Err(E::custom("The number is neither 1.0 nor 0.0"))
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for BoolFromAnythingVisitor {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a boolean, a number or a string")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        Self::from_i64(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        match v {
+            1 => Ok(true),
+            0 => Ok(false),
+            _ => Err(E::custom("The number is neither 1 nor 0")),
+        }
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        Self::from_f64(v)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        if let Ok(b) = v.parse::<bool>() {
+            Ok(b)
+        } else if let Ok(i) = v.parse::<i64>() {
+            Self::from_i64(i)
+        } else if let Ok(f) = v.parse::<f64>() {
+            Self::from_f64(f)
+        } else {
+            Err(E::custom(format!(
+                "Could not parse boolean from a string: {}",
+                v
+            )))
+        }
+    }
+}
+
+/// Deserializes string from a number. If the original value is a number value,
+/// it will be converted to a string.
+///
+/// For the formats which are not self-describing, like `bincode`, see
+/// [`canonical_deserialize_with`](crate::canonical_deserialize_with).
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_string_from_number")]
+///     number_as_string: String,
+/// }
+/// fn main() {
+///     let s = r#" { "number_as_string": "foo" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.number_as_string, "foo");
+///
+///     let s = r#" { "number_as_string": -13 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.number_as_string, "-13");
+///
+///     let s = r#" { "number_as_string": 24.0034 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.number_as_string, "24.0034");
+/// }
+/// ```
+pub fn deserialize_string_from_number<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(StringFromNumberVisitor)
+}
+
+struct StringFromNumberVisitor;
+
+impl<'de> Visitor<'de> for StringFromNumberVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string or a number")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<String, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_owned())
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<String, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<String, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_string())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<String, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_string())
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<String, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_string())
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<String, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_string())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<String, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_string())
+    }
+}
+
+/// Deserializes an `Option<String>` from a string or a number, the same way as
+/// [`deserialize_string_from_number`] does for a `String`. `null` gives `None`, and so does a
+/// missing field with `#[serde(default)]`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(default, deserialize_with = "deserialize_option_string_from_number")]
+///     code: Option<String>,
+/// }
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "code": 404 } "#).unwrap();
+///     assert_eq!(a.code.as_deref(), Some("404"));
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "code": "E404" } "#).unwrap();
+///     assert_eq!(a.code.as_deref(), Some("E404"));
+///
+///     for s in &[r#" { "code": null } "#, "{}"] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.code, None);
+///     }
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "code": [1] } "#).is_err());
+/// }
+/// ```
+pub fn deserialize_option_string_from_number<'de, D>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct OptionStringFromNumberVisitor;
+
+    impl<'de> Visitor<'de> for OptionStringFromNumberVisitor {
+        type Value = Option<String>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an optional string or number")
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserialize_string_from_number(deserializer).map(Some)
+        }
+    }
+
+    deserializer.deserialize_option(OptionStringFromNumberVisitor)
+}
+
+/// Serializes an `Option<String>` writing `None` as the string `"null"` instead of the JSON
+/// `null`, and `Some` as its string. This is a workaround for the consumers which require the
+/// literal string for the absent values; don't use it otherwise, as the text `"null"` becomes
+/// ambiguous: [`deserialize_option_string_from_literal_null`] reads it back as `None`, so a
+/// `Some("null")` doesn't survive a round trip. See
+/// [`with::option_string_literal_null`](crate::with::option_string_literal_null).
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_option_string_none_as_literal_null")]
+///     comment: Option<String>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { comment: None };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"comment":"null"}"#);
+///
+///     let a = MyStruct { comment: Some("x".to_owned()) };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"comment":"x"}"#);
+/// }
+/// ```
+pub fn serialize_option_string_none_as_literal_null<S>(
+    value: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(value.as_deref().unwrap_or("null"))
+}
+
+/// Deserializes an `Option<String>` taking the string `"null"` (exactly, case-sensitively) and
+/// the real `null` as `None`, and any other string as `Some`. The counterpart of
+/// [`serialize_option_string_none_as_literal_null`], the same interoperability workaround: a
+/// real string `"null"` can't be told from a missing value and becomes `None` too.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(default, deserialize_with = "deserialize_option_string_from_literal_null")]
+///     comment: Option<String>,
+/// }
+///
+/// fn main() {
+///     for s in &[r#" { "comment": "null" } "#, r#" { "comment": null } "#, "{}"] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.comment, None);
+///     }
+///     let a: MyStruct = serde_json::from_str(r#" { "comment": "Null" } "#).unwrap();
+///     assert_eq!(a.comment.as_deref(), Some("Null"));
+/// }
+/// ```
+pub fn deserialize_option_string_from_literal_null<'de, D>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(Option::<String>::deserialize(deserializer)?.filter(|value| value != "null"))
+}
+
+/// Deserializes default value from nullable value. If the original value is `null`,
+/// `Default::default()` is used.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_default_from_null")]
+///     null_as_default: u64,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "null_as_default": 42 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.null_as_default, 42);
+///
+///     let s = r#" { "null_as_default": null } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.null_as_default, 0);
+///
+///     let s = r#" { "null_as_default": "wrong_type" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_default_from_null<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Deserializes default value from nullable value or empty object. If the original value is `null` or `{}`,
+/// `Default::default()` is used.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_default_from_empty_object")]
+///     empty_as_default: Option<MyInnerStruct>,
+/// }
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyInnerStruct {
+///     mandatory: u64,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "empty_as_default": { "mandatory": 42 } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.empty_as_default.unwrap().mandatory, 42);
+///
+///     let s = r#" { "empty_as_default": null } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(a.empty_as_default.is_none());
+///
+///     let s = r#" { "empty_as_default": {} } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(a.empty_as_default.is_none());
+///
+///     let s = r#" { "empty_as_default": { "unknown": 42 } } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_default_from_empty_object<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    #[derive(Debug, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct EmptyObject {}
+
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum EmptyOrNot<Y> {
+        NonEmpty(Y),
+        Empty(EmptyObject),
+    }
+
+    let empty_or_not: EmptyOrNot<T> = EmptyOrNot::deserialize(deserializer)?;
+
+    match empty_or_not {
+        EmptyOrNot::NonEmpty(e) => Ok(e),
+        _ => Ok(T::default()),
+    }
+}
+
+/// Deserializes an `Option<T>` from `null` or an empty object `{}` as `None` and from any other
+/// value as `Some`, like for the nested objects sent as `{}` when they are absent. Unlike
+/// [`deserialize_default_from_empty_object`] the `T` doesn't need to implement `Default` and its
+/// errors are kept: the value is passed to `T` as it comes, without buffering, and a map turns
+/// out to be empty when `T` asks for its first key. So a `{ "name": 1 }` fails telling what is
+/// wrong with the `name`, and where.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct Profile {
+///     name: String,
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(default, deserialize_with = "deserialize_option_none_if_empty_object")]
+///     profile: Option<Profile>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "profile": { "name": "Ann" } } "#).unwrap();
+///     assert_eq!(a.profile.unwrap().name, "Ann");
+///
+///     for s in &[r#" { "profile": {} } "#, r#" { "profile": null } "#, "{}"] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert!(a.profile.is_none());
+///     }
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "profile": { "age": 3 } } "#).unwrap_err();
+///     assert!(e.to_string().contains("missing field `name`"));
+///     let e = serde_json::from_str::<MyStruct>(r#" { "profile": { "name": 1 } } "#).unwrap_err();
+///     assert!(e.to_string().ends_with("at line 1 column 25"), "{}", e);
+/// }
+/// ```
+pub fn deserialize_option_none_if_empty_object<'de, D, T>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    use core::cell::Cell;
+    use serde::de::value::{
+        BorrowedStrDeserializer, MapAccessDeserializer, SeqAccessDeserializer, StrDeserializer,
+    };
+    use serde::de::{DeserializeSeed, IntoDeserializer, MapAccess};
+
+    /// Passes the entries through, telling whether the first key asked for was there.
+    struct EmptinessWatch<'a, A> {
+        map: A,
+        empty: &'a Cell<Option<bool>>,
+    }
+
+    impl<'a, 'de, A> MapAccess<'de> for EmptinessWatch<'a, A>
+    where
+        A: MapAccess<'de>,
+    {
+        type Error = A::Error;
+
+        fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+        where
+            K: DeserializeSeed<'de>,
+        {
+            let key = self.map.next_key_seed(seed)?;
+            if self.empty.get().is_none() {
+                self.empty.set(Some(key.is_none()));
+            }
+            Ok(key)
+        }
+
+        fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+        where
+            V: DeserializeSeed<'de>,
+        {
+            self.map.next_value_seed(seed)
+        }
+
+        fn size_hint(&self) -> Option<usize> {
+            self.map.size_hint()
+        }
+    }
+
+    struct NoneIfEmptyObjectVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for NoneIfEmptyObjectVisitor<T>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = Option<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("null, an empty object or a value")
+        }
+
+        fn visit_none<E>(self) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_unit<E>(self) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Option<T>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(self)
+        }
+
+        fn visit_map<A>(self, map: A) -> Result<Option<T>, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let empty = Cell::new(None);
+            let mut watch = EmptinessWatch { map, empty: &empty };
+            let result = T::deserialize(MapAccessDeserializer::new(&mut watch));
+            match (empty.get(), result) {
+                (Some(true), _) => Ok(None),
+                (Some(false), result) => result.map(Some),
+                // `T` didn't look into the map, as it doesn't take one at all.
+                (None, Ok(value)) => Ok(Some(value)),
+                (None, Err(e)) => match watch.map.next_key::<serde::de::IgnoredAny>()? {
+                    None => Ok(None),
+                    Some(_) => Err(e),
+                },
+            }
+        }
+
+        fn visit_seq<A>(self, seq: A) -> Result<Option<T>, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            T::deserialize(SeqAccessDeserializer::new(seq)).map(Some)
+        }
+
+        fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(BorrowedStrDeserializer::new(v)).map(Some)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(StrDeserializer::new(v)).map(Some)
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(serde::de::value::BytesDeserializer::new(v)).map(Some)
+        }
+
+        fn visit_bool<E>(self, v: bool) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer()).map(Some)
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer()).map(Some)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer()).map(Some)
+        }
+
+        fn visit_i128<E>(self, v: i128) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer()).map(Some)
+        }
+
+        fn visit_u128<E>(self, v: u128) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer()).map(Some)
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<Option<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer()).map(Some)
+        }
+    }
+
+    deserializer.deserialize_any(NoneIfEmptyObjectVisitor(PhantomData))
+}
+
+/// Deserializes a `Vec<T>` and fails if the sequence is empty. Useful for the configuration
+/// values like a list of servers which are required to contain at least one element.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_non_empty_vec")]
+///     servers: Vec<String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "servers": ["first", "second"] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.servers, vec!["first", "second"]);
+///
+///     let s = r#" { "servers": [] } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().contains("expected a non-empty sequence"));
+///
+///     let s = r#" { "servers": "first" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_non_empty_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let vec = Vec::<T>::deserialize(deserializer)?;
+    if vec.is_empty() {
+        return Err(serde::de::Error::invalid_length(0, &"a non-empty sequence"));
+    }
+    Ok(vec)
+}
+
+/// A seed deserializing a sequence element by element and passing each element to a callback
+/// instead of collecting them, so a huge array is processed without holding it in the memory.
+/// The seed gives the number of the elements passed to the callback.
+///
+/// The callback returns anything implementing [`ForEachOutcome`]:
+///
+/// - `()` to go on with the next element;
+/// - [`ForEachControl::Stop`] to stop cleanly: the rest of the sequence is still read, to keep
+///   the input valid, but skipped without being deserialized as `T`;
+/// - an `Err` of a `Result` (of any of these) to fail the deserialization with the error, which
+///   tells the index of the element.
+///
+/// Use it on a deserializer directly, like a `serde_json::Deserializer::from_slice` (or
+/// `from_reader` with the `std` feature of `serde_json`), or from a `deserialize_with` function.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde::de::DeserializeSeed;
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct Event {
+///     kind: String,
+///     bytes: u64,
+/// }
+///
+/// fn main() {
+///     let input = r#"[{ "kind": "get", "bytes": 10 }, { "kind": "put", "bytes": 20 }]"#;
+///     let mut deserializer = serde_json::Deserializer::from_slice(input.as_bytes());
+///
+///     let mut total = 0;
+///     let count = ForEach::new(|event: Event| {
+///         if event.kind == "delete" {
+///             return Err(format!("unexpected {:?}", event));
+///         }
+///         total += event.bytes;
+///         Ok(())
+///     })
+///     .deserialize(&mut deserializer)
+///     .unwrap();
+///     deserializer.end().unwrap();
+///     assert_eq!((count, total), (2, 30));
+///
+///     let input = r#"[{ "kind": "get", "bytes": 10 }, { "kind": "delete", "bytes": 0 }]"#;
+///     let mut deserializer = serde_json::Deserializer::from_slice(input.as_bytes());
+///     let e = ForEach::new(|event: Event| -> Result<(), String> {
+///         match event.kind.as_str() {
+///             "delete" => Err("deleting is not allowed".to_owned()),
+///             _ => Ok(()),
+///         }
+///     })
+///     .deserialize(&mut deserializer)
+///     .unwrap_err();
+///     assert!(e.to_string().contains("element 1: deleting is not allowed"));
+///
+///     let mut deserializer = serde_json::Deserializer::from_str("[1, 2, 3, 4]");
+///     let mut firsts = vec![];
+///     let count = ForEach::new(|n: u8| {
+///         firsts.push(n);
+///         if firsts.len() == 2 { ForEachControl::Stop } else { ForEachControl::Continue }
+///     })
+///     .deserialize(&mut deserializer)
+///     .unwrap();
+///     deserializer.end().unwrap();
+///     assert_eq!((count, firsts), (2, vec![1, 2]));
+/// }
+/// ```
+pub struct ForEach<T, F> {
+    callback: F,
+    element: PhantomData<fn(T)>,
+}
+
+impl<T, F> ForEach<T, F> {
+    /// Creates a seed passing the elements to `callback`.
+    pub fn new(callback: F) -> Self {
+        ForEach {
+            callback,
+            element: PhantomData,
+        }
+    }
+}
+
+/// Tells [`ForEach`] whether to go on after an element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForEachControl {
+    /// Goes on with the next element.
+    Continue,
+    /// Skips the rest of the sequence.
+    Stop,
+}
+
+/// The values a [`ForEach`] callback can return: `()`, a [`ForEachControl`], or a `Result` of
+/// either whose error fails the deserialization.
+pub trait ForEachOutcome {
+    /// Tells whether to go on, or the error message.
+    fn into_control(self) -> Result<ForEachControl, String>;
+}
+
+impl ForEachOutcome for () {
+    fn into_control(self) -> Result<ForEachControl, String> {
+        Ok(ForEachControl::Continue)
+    }
+}
+
+impl ForEachOutcome for ForEachControl {
+    fn into_control(self) -> Result<ForEachControl, String> {
+        Ok(self)
+    }
+}
+
+impl<C: ForEachOutcome, E: Display> ForEachOutcome for Result<C, E> {
+    fn into_control(self) -> Result<ForEachControl, String> {
+        self.map_err(|e| e.to_string())
+            .and_then(ForEachOutcome::into_control)
+    }
+}
+
+impl<'de, T, F, R> serde::de::DeserializeSeed<'de> for ForEach<T, F>
+where
+    T: Deserialize<'de>,
+    F: FnMut(T) -> R,
+    R: ForEachOutcome,
+{
+    type Value = usize;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<usize, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T, F, R> Visitor<'de> for ForEach<T, F>
+where
+    T: Deserialize<'de>,
+    F: FnMut(T) -> R,
+    R: ForEachOutcome,
+{
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A>(mut self, mut seq: A) -> Result<usize, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        use serde::de::Error;
+
+        let mut count = 0;
+        while let Some(element) = seq.next_element::<T>()? {
+            count += 1;
+            match (self.callback)(element).into_control() {
+                Ok(ForEachControl::Continue) => {}
+                Ok(ForEachControl::Stop) => {
+                    while seq.next_element::<serde::de::IgnoredAny>()?.is_some() {}
+                    break;
+                }
+                Err(e) => {
+                    return Err(A::Error::custom(format_args!(
+                        "element {}: {}",
+                        count - 1,
+                        e
+                    )))
+                }
+            }
+        }
+        Ok(count)
+    }
+}
+
+/// Defines a function lifting a `deserialize_with` helper to an `Option`: `null` becomes `None`
+/// and any other value is passed to the helper and wrapped into `Some`. The generated function is
+/// usable in `#[serde(deserialize_with)]` like any other helper of this crate; add
+/// `#[serde(default)]` as well for a missing field to become `None`, serde reports it as missing
+/// otherwise. See [`default_deserialize_with`](crate::default_deserialize_with) for the whole
+/// table.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// serde_aux::option_deserialize_with!(
+///     fn deserialize_option_bool(deserialize_bool_from_anything) -> bool
+/// );
+/// serde_aux::option_deserialize_with!(
+///     fn deserialize_option_u64(deserialize_number_from_string) -> u64
+/// );
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(default, deserialize_with = "deserialize_option_bool")]
+///     boolean: Option<bool>,
+///     #[serde(default, deserialize_with = "deserialize_option_u64")]
+///     number: Option<u64>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "boolean": "1", "number": "42" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.boolean, Some(true));
+///     assert_eq!(a.number, Some(42));
+///
+///     let s = r#" { "boolean": null } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.boolean, None);
+///     assert_eq!(a.number, None);
+///
+///     let s = r#" { "boolean": "foo" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+#[macro_export]
+macro_rules! option_deserialize_with {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident($helper:path) -> $ty:ty) => {
+        $(#[$attr])*
+        $vis fn $name<'de, D>(deserializer: D) -> Result<Option<$ty>, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            struct OptionVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for OptionVisitor {
+                type Value = Option<$ty>;
+
+                fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    formatter.write_str("an optional value")
+                }
+
+                fn visit_none<E>(self) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(None)
+                }
+
+                fn visit_unit<E>(self) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(None)
+                }
+
+                fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    $helper(deserializer).map(Some)
+                }
+            }
+
+            deserializer.deserialize_option(OptionVisitor)
+        }
+    };
+}
+
+/// Defines a function lifting a `deserialize_with` helper so that `null` gives the default value
+/// of the field's type, the same as a missing field does with `#[serde(default)]`. Any other
+/// value is passed to the helper. Together with `#[serde(default)]` the field gets the same
+/// behavior in every self-describing format:
+///
+/// | input        | `#[serde(default, deserialize_with = "...")]` |
+/// |--------------|-----------------------------------------------|
+/// | missing key  | `Default::default()`                          |
+/// | `null`       | `Default::default()`                          |
+/// | a value      | the helper's result                           |
+///
+/// The `#[serde(default)]` attribute can't be replaced by the function: serde doesn't call
+/// `deserialize_with` for a missing field and reports it as missing instead, even for an
+/// `Option`. See [`option_deserialize_with`] for the `Option` fields, which follow the same
+/// table with `None` as the default.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// serde_aux::default_deserialize_with!(
+///     fn deserialize_u64_or_default(deserialize_number_from_string) -> u64
+/// );
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(default, deserialize_with = "deserialize_u64_or_default")]
+///     retries: u64,
+/// }
+///
+/// fn main() {
+///     for (s, retries) in &[
+///         (r#" { "retries": "3" } "#, 3),
+///         (r#" { "retries": null } "#, 0),
+///         ("{}", 0),
+///     ] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.retries, *retries);
+///     }
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "retries": "x" } "#).is_err());
+/// }
+/// ```
+#[macro_export]
+macro_rules! default_deserialize_with {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident($helper:path) -> $ty:ty) => {
+        $(#[$attr])*
+        $vis fn $name<'de, D>(deserializer: D) -> Result<$ty, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            struct DefaultVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for DefaultVisitor {
+                type Value = $ty;
+
+                fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    formatter.write_str("an optional value")
+                }
+
+                fn visit_none<E>(self) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(<$ty as ::core::default::Default>::default())
+                }
+
+                fn visit_unit<E>(self) -> Result<Self::Value, E>
+                where
+                    E: serde::de::Error,
+                {
+                    Ok(<$ty as ::core::default::Default>::default())
+                }
+
+                fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    $helper(deserializer)
+                }
+            }
+
+            deserializer.deserialize_option(DefaultVisitor)
+        }
+    };
+}
+
+/// Defines a function applying a `deserialize_with` helper in the human-readable formats and
+/// reading the plain value of the field's type in the other ones, for the structs shared between
+/// JSON and a format which is not self-describing, like `bincode` or `postcard`.
+///
+/// The helpers of this crate look at what comes next (with `deserialize_any`) to accept both a
+/// number and a string, say, which the formats without the type information in their data can't
+/// support. Those formats write the field as its own type, so the generated function reads it
+/// back the same way there. MessagePack and CBOR are not human readable either but are
+/// self-describing, so the helpers work with them as they are: use the generated function only
+/// with the formats which need it, as it skips the helper's parsing in all of them.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// serde_aux::canonical_deserialize_with!(
+///     fn deserialize_u64(deserialize_number_from_string) -> u64
+/// );
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_u64")]
+///     id: u64,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "id": "42" } "#).unwrap();
+///     assert_eq!(a.id, 42);
+///
+///     let bytes = bincode::serialize(&a).unwrap();
+///     assert_eq!(bincode::deserialize::<MyStruct>(&bytes).unwrap(), a);
+/// }
+/// ```
+#[macro_export]
+macro_rules! canonical_deserialize_with {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident($helper:path) -> $ty:ty) => {
+        $(#[$attr])*
+        $vis fn $name<'de, D>(deserializer: D) -> Result<$ty, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            if serde::Deserializer::is_human_readable(&deserializer) {
+                $helper(deserializer)
+            } else {
+                <$ty as serde::Deserialize<'de>>::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+/// Defines a function applying a `deserialize_with` helper to every element of a sequence, giving
+/// a `Vec` of the results. This makes any helper for a single value usable for arrays.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// serde_aux::vec_deserialize_with!(
+///     fn deserialize_vec_u64(deserialize_number_from_string) -> u64
+/// );
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_vec_u64")]
+///     ids: Vec<u64>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "ids": [1, "2", 3] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.ids, vec![1, 2, 3]);
+///
+///     let s = r#" { "ids": [1, "foo"] } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+///
+///     let s = r#" { "ids": "1" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+#[macro_export]
+macro_rules! vec_deserialize_with {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident($helper:path) -> $ty:ty) => {
+        $(#[$attr])*
+        $vis fn $name<'de, D>(deserializer: D) -> Result<Vec<$ty>, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            struct Element;
+
+            impl<'de> serde::de::DeserializeSeed<'de> for Element {
+                type Value = $ty;
+
+                fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    $helper(deserializer)
+                }
+            }
+
+            struct VecVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for VecVisitor {
+                type Value = Vec<$ty>;
+
+                fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    formatter.write_str("a sequence")
+                }
+
+                fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: serde::de::SeqAccess<'de>,
+                {
+                    let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                    while let Some(value) = seq.next_element_seed(Element)? {
+                        values.push(value);
+                    }
+                    Ok(values)
+                }
+            }
+
+            deserializer.deserialize_seq(VecVisitor)
+        }
+    };
+}
+
+/// Defines a function applying a `deserialize_with` helper to every value of a map. The keys are
+/// deserialized as usual and the map type (`HashMap`, `BTreeMap`, `IndexMap` or any other map
+/// with `insert` and `Default`) is taken from the signature. When a value fails, the error names its key.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::HashMap;
+///
+/// serde_aux::map_values_deserialize_with!(
+///     fn deserialize_flags(deserialize_bool_from_anything) -> HashMap<String, bool>
+/// );
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_flags")]
+///     flags: HashMap<String, bool>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "flags": { "a": 1, "b": "false", "c": true } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.flags["a"], true);
+///     assert_eq!(a.flags["b"], false);
+///     assert_eq!(a.flags["c"], true);
+///
+///     let s = r#" { "flags": { "a": 1, "b": 2 } } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"invalid value for key "b": "#));
+/// }
+/// ```
+#[macro_export]
+macro_rules! map_values_deserialize_with {
+    (
+        $(#[$attr:meta])*
+        $vis:vis fn $name:ident($helper:path) -> $map:ident<$key:ty, $value:ty>
+    ) => {
+        $(#[$attr])*
+        $vis fn $name<'de, D>(deserializer: D) -> Result<$map<$key, $value>, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            struct Value;
+
+            impl<'de> serde::de::DeserializeSeed<'de> for Value {
+                type Value = $value;
+
+                fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    $helper(deserializer)
+                }
+            }
+
+            struct MapVisitor;
+
+            impl<'de> serde::de::Visitor<'de> for MapVisitor {
+                type Value = $map<$key, $value>;
+
+                fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    formatter.write_str("a map")
+                }
+
+                fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+                where
+                    A: serde::de::MapAccess<'de>,
+                {
+                    let mut values = $map::default();
+                    while let Some(key) = map.next_key::<$key>()? {
+                        let value = map.next_value_seed(Value).map_err(|e| {
+                            <A::Error as serde::de::Error>::custom(format_args!(
+                                "invalid value for key {:?}: {}",
+                                key, e
+                            ))
+                        })?;
+                        values.insert(key, value);
+                    }
+                    Ok(values)
+                }
+            }
+
+            deserializer.deserialize_map(MapVisitor)
+        }
+    };
+}
+
+/// Defines the `Option` and the `Vec` variants of a `deserialize_with` helper at once, as
+/// [`option_deserialize_with!`](crate::option_deserialize_with) and
+/// [`vec_deserialize_with!`](crate::vec_deserialize_with) do: `option` maps `null` to `None`
+/// and `vec` applies the helper to every element. The helper may come from this crate or from
+/// any other one, and each variant gets the name given after `as`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn deserialize_celsius<'de, D>(deserializer: D) -> Result<f64, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     deserialize_number_from_string::<f64, D>(deserializer).map(|f| (f - 32.0) / 1.8)
+/// }
+///
+/// serde_aux::derive_variants!(
+///     deserialize_celsius: f64 => option as deserialize_option_celsius, vec as deserialize_vec_celsius
+/// );
+/// serde_aux::derive_variants!(
+///     pub(crate) deserialize_number_from_string: u64 => vec as deserialize_vec_u64
+/// );
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(default, deserialize_with = "deserialize_option_celsius")]
+///     current: Option<f64>,
+///     #[serde(deserialize_with = "deserialize_vec_celsius")]
+///     history: Vec<f64>,
+///     #[serde(deserialize_with = "deserialize_vec_u64")]
+///     ids: Vec<u64>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "current": "212", "history": [32, "50"], "ids": ["1", 2] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.current, Some(100.0));
+///     assert_eq!(a.history, vec![0.0, 10.0]);
+///     assert_eq!(a.ids, vec![1, 2]);
+///
+///     let s = r#" { "current": null, "history": [], "ids": [] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.current, None);
+/// }
+/// ```
+///
+/// Only `option` and `vec` are the variants, any other one does not compile:
+///
+/// ```compile_fail
+/// use serde_aux::prelude::*;
+///
+/// serde_aux::derive_variants!(
+///     deserialize_number_from_string: u64 => map as deserialize_map_u64
+/// );
+///
+/// fn main() {}
+/// ```
+///
+/// Neither does a variant without its name:
+///
+/// ```compile_fail
+/// use serde_aux::prelude::*;
+///
+/// serde_aux::derive_variants!(deserialize_number_from_string: u64 => option);
+///
+/// fn main() {}
+/// ```
+#[macro_export]
+macro_rules! derive_variants {
+    ($vis:vis $helper:path : $ty:ty => $($kind:ident as $name:ident),+ $(,)?) => {
+        $(
+            $crate::derive_variants!(@variant $kind, $vis, $name, $helper, $ty);
+        )+
+    };
+    (@variant option, $vis:vis, $name:ident, $helper:path, $ty:ty) => {
+        $crate::option_deserialize_with!($vis fn $name($helper) -> $ty);
+    };
+    (@variant vec, $vis:vis, $name:ident, $helper:path, $ty:ty) => {
+        $crate::vec_deserialize_with!($vis fn $name($helper) -> $ty);
+    };
+}
+
+/// Serializes a string holding a number as a number. The string is parsed as an integer first
+/// and as a float after that; a string which is not a finite number is an error, and so is a
+/// float which `f64` can't hold exactly, like `"9007199254740993.0"`, instead of writing a
+/// different number. This is the counterpart of [`deserialize_string_from_number`].
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(
+///         serialize_with = "serialize_string_as_number",
+///         deserialize_with = "deserialize_string_from_number"
+///     )]
+///     id: String,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { id: "18446744073709551615".to_owned() };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"id":18446744073709551615}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap().id, a.id);
+///
+///     let a = MyStruct { id: "-1.5".to_owned() };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"id":-1.5}"#);
+///
+///     let a = MyStruct { id: "foo".to_owned() };
+///     assert!(serde_json::to_string(&a).is_err());
+/// }
+/// ```
+pub fn serialize_string_as_number<S>(value: &str, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if let Ok(i) = value.parse::<i64>() {
+        serializer.serialize_i64(i)
+    } else if let Ok(u) = value.parse::<u64>() {
+        serializer.serialize_u64(u)
+    } else if let Ok(i) = value.parse::<i128>() {
+        serializer.serialize_i128(i)
+    } else if let Ok(u) = value.parse::<u128>() {
+        serializer.serialize_u128(u)
+    } else {
+        match value.parse::<f64>() {
+            Ok(f) if f.is_finite() && is_exact_f64(value, f) => serializer.serialize_f64(f),
+            Ok(f) if f.is_finite() => Err(serde::ser::Error::custom(format!(
+                "Could not serialize a string as a number without losing the precision: {:?} \
+                 would be {}",
+                value, f
+            ))),
+            _ => Err(serde::ser::Error::custom(format!(
+                "Could not serialize a string as a number: {:?}",
+                value
+            ))),
+        }
+    }
+}
+
+/// Tells whether `value`, parsed from the decimal `number`, is exactly the number written there:
+/// the strings with more significant digits than `f64` holds (like `"9007199254740993"`, which is
+/// `2^53 + 1`) are rounded silently by the parsing.
+fn is_exact_f64(number: &str, value: f64) -> bool {
+    let written = normalize_decimal(number);
+    written.is_some() && written == normalize_decimal(&value.to_string())
+}
+
+/// Tells whether the string is a decimal number equal to zero, like `"0"` or `"-0.00e5"`. Unlike
+/// parsing an `f64` it does not take the numbers too small for it, like `"1e-400"`, for zero.
+fn is_decimal_zero(number: &str) -> bool {
+    match normalize_decimal(number) {
+        Some((_, digits, _)) => digits.is_empty(),
+        None => false,
+    }
+}
+
+/// Splits a decimal number like `"-0.0120e3"` into the sign, the significant digits and the
+/// position of the decimal point relative to them: `(true, "12", 0)`. Zero is `(false, "", 0)`
+/// whatever its exponent; a nonzero number whose point does not fit into an `i64` is `None`.
+fn normalize_decimal(number: &str) -> Option<(bool, String, i64)> {
+    let number = number.trim();
+    let (negative, number) = match number.as_bytes().first() {
+        Some(b'-') => (true, &number[1..]),
+        Some(b'+') => (false, &number[1..]),
+        _ => (false, number),
+    };
+    let (mantissa, exponent) = match number.find(&['e', 'E'][..]) {
+        Some(index) => {
+            let exponent = &number[index + 1..];
+            let digits = match exponent.as_bytes().split_first() {
+                Some((b'-', _)) | Some((b'+', _)) => &exponent[1..],
+                _ => exponent,
+            };
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            (&number[..index], exponent.parse::<i64>().ok())
+        }
+        None => (number, Some(0)),
+    };
+    let (whole, fraction) = match mantissa.find('.') {
+        Some(index) => (&mantissa[..index], &mantissa[index + 1..]),
+        None => (mantissa, ""),
+    };
+    if (whole.is_empty() && fraction.is_empty())
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+    let digits = format!("{}{}", whole, fraction);
+    let significant = digits.trim_start_matches('0');
+    let leading_zeros = (digits.len() - significant.len()) as i64;
+    let significant = significant.trim_end_matches('0');
+    if significant.is_empty() {
+        return Some((false, String::new(), 0));
+    }
+    let point = (whole.len() as i64)
+        .checked_add(exponent?)?
+        .checked_sub(leading_zeros)?;
+    Some((negative, significant.to_owned(), point))
+}
+
+/// Serializes a number (or anything implementing `Display`) as a string. This is the counterpart
+/// of [`deserialize_number_from_string`].
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_number_as_string")]
+///     id: u64,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { id: 9007199254740993 };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"id":"9007199254740993"}"#);
+/// }
+/// ```
+pub fn serialize_number_as_string<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+/// Serializes an optional number (or anything implementing `Display`) as a string: `Some(123)`
+/// becomes `"123"` and `None` becomes `null`. Add `#[serde(skip_serializing_if =
+/// "Option::is_none")]` to omit the `None`s instead. This is the counterpart of
+/// [`deserialize_option_number_from_string`].
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_option_number_as_string")]
+///     id: Option<u64>,
+///     #[serde(
+///         serialize_with = "serialize_option_number_as_string",
+///         skip_serializing_if = "Option::is_none"
+///     )]
+///     parent_id: Option<u64>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { id: Some(123), parent_id: Some(7) };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"id":"123","parent_id":"7"}"#);
+///
+///     let a = MyStruct { id: None, parent_id: None };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"id":null}"#);
+/// }
+/// ```
+pub fn serialize_option_number_as_string<T, S>(
+    value: &Option<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    match value {
+        Some(value) => serializer.collect_str(value),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serializes a boolean as a number: `1` for `true` and `0` for `false`. The
+/// [`deserialize_bool_from_anything`] reads it back.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_bool_as_int")]
+///     enabled: bool,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { enabled: true };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"enabled":1}"#);
+///
+///     let a = MyStruct { enabled: false };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"enabled":0}"#);
+/// }
+/// ```
+pub fn serialize_bool_as_int<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_u8(*value as u8)
+}
+
+/// Deserializes a `Vec<T>` either from a sequence or from a string with the comma separated
+/// values, each of which is parsed with `FromStr`. An empty string gives an empty vector. See
+/// [`StringOrVecToVec`] for the other separators and options.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_vec_from_string_or_vec")]
+///     list: Vec<u16>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "list": "1,2,3" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.list, vec![1, 2, 3]);
+///
+///     let s = r#" { "list": [4, 5] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.list, vec![4, 5]);
+///
+///     let s = r#" { "list": "" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(a.list.is_empty());
+///
+///     let s = r#" { "list": "1,a" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_vec_from_string_or_vec<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    StringOrVecToVec::new().into_deserializer()(deserializer)
+}
+
+/// Deserializes a `Vec<T>` either from a sequence or from a string with a JSON array inside, like
+/// `"[1,2,3]"`, which some producers send in place of the array itself. Such a string is parsed
+/// with `serde_json` and its errors are reported as they are; the other strings are an error.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_vec_from_json_string_or_seq")]
+///     ids: Vec<u64>,
+/// }
+///
+/// fn main() {
+///     for s in &[r#" { "ids": "[1, 2, 3]" } "#, r#" { "ids": [1, 2, 3] } "#] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.ids, vec![1, 2, 3]);
+///     }
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "ids": "[1, 2" } "#).unwrap_err();
+///     assert!(e.to_string().starts_with("invalid JSON array \"[1, 2\": EOF while parsing"));
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "ids": "1, 2" } "#).is_err());
+/// }
+/// ```
+pub fn deserialize_vec_from_json_string_or_seq<'de, T, D>(
+    deserializer: D,
+) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    struct JsonStringOrSeqVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for JsonStringOrSeqVisitor<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a sequence or a string with a JSON array")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Vec<T>, E>
+        where
+            E: serde::de::Error,
+        {
+            if !v.trim_start().starts_with('[') {
+                return Err(E::invalid_value(serde::de::Unexpected::Str(v), &self));
+            }
+            serde_json::from_str(v)
+                .map_err(|e| E::custom(format!("invalid JSON array {:?}: {}", v, e)))
+        }
+
+        fn visit_seq<A>(self, seq: A) -> Result<Vec<T>, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            Vec::<T>::deserialize(serde::de::value::SeqAccessDeserializer::new(seq))
+        }
+    }
+
+    deserializer.deserialize_any(JsonStringOrSeqVisitor(PhantomData))
+}
+
+/// Deserializes a `HashSet<T>` either from a sequence or from a string with the comma separated
+/// values, the same way as [`deserialize_vec_from_string_or_vec`]. The duplicates are collapsed
+/// silently. See [`StringOrVecToVec`] for the other separators and options.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::HashSet;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_set_from_string_or_seq")]
+///     tags: HashSet<String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "tags": "red,green,red" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.tags.len(), 2);
+///     assert!(a.tags.contains("red") && a.tags.contains("green"));
+///
+///     let s = r#" { "tags": ["blue", "blue"] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.tags.len(), 1);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn deserialize_set_from_string_or_seq<'de, T, D>(
+    deserializer: D,
+) -> Result<std::collections::HashSet<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de> + Eq + core::hash::Hash,
+    <T as FromStr>::Err: Display,
+{
+    StringOrVecToVec::new().into_deserializer()(deserializer)
+}
+
+/// The same as [`deserialize_set_from_string_or_seq`] but gives a `BTreeSet<T>`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::BTreeSet;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_btree_set_from_string_or_seq")]
+///     ids: BTreeSet<u32>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "ids": "3,1,3,2" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.ids.into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+/// }
+/// ```
+pub fn deserialize_btree_set_from_string_or_seq<'de, T, D>(
+    deserializer: D,
+) -> Result<alloc::collections::BTreeSet<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de> + Ord,
+    <T as FromStr>::Err: Display,
+{
+    StringOrVecToVec::new().into_deserializer()(deserializer)
+}
+
+/// Deserializes a fixed-size array of any length, like `[f64; 3]`, either from a sequence or from
+/// a string with the comma separated values, each of which is parsed with `FromStr`. The number of the values
+/// must be exactly the length of the array. See [`StringOrVecToVec::into_array_deserializer`]
+/// for the other separators and options.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_array_from_string_or_seq")]
+///     position: [f64; 3],
+/// }
+///
+/// fn main() {
+///     for s in &[
+///         r#" { "position": "1.0,2.5,-3" } "#,
+///         r#" { "position": [1.0, 2.5, -3] } "#,
+///     ] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.position, [1.0, 2.5, -3.0]);
+///     }
+///
+///     let s = r#" { "position": "1.0,2.5" } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with("expected 3 elements, found 2"));
+///
+///     let s = r#" { "position": [1, 2, 3, 4] } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with("expected 3 elements, found 4"));
+/// }
+/// ```
+pub fn deserialize_array_from_string_or_seq<'de, T, D, const N: usize>(
+    deserializer: D,
+) -> Result<[T; N], D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    StringOrVecToVec::new().into_array_deserializer()(deserializer)
+}
+
+/// Deserializes a `Vec<String>` from a text with one value per line, like the content of a
+/// multi-line text area, or from a sequence of strings, which is taken as is. Both `\n` and
+/// `\r\n` end a line, and the empty lines at the end of the text are dropped, so `"a\r\nb\n\n"`
+/// gives `["a", "b"]`. See [`deserialize_non_blank_lines`] for skipping all the blank lines.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_lines")]
+///     lines: Vec<String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "lines": "first\r\n\nthird\n\n" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.lines, vec!["first", "", "third"]);
+///
+///     let s = r#" { "lines": ["a", "b"] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.lines, vec!["a", "b"]);
+/// }
+/// ```
+pub fn deserialize_lines<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(LinesVisitor { skip_blank: false })
+}
+
+/// The same as [`deserialize_lines`] but skips all the blank lines (the empty ones and the ones
+/// with only whitespace), wherever they are. The other lines are kept as they are, untrimmed.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_non_blank_lines")]
+///     lines: Vec<String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "lines": "\n first\r\n  \r\nthird\n" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.lines, vec![" first", "third"]);
+/// }
+/// ```
+pub fn deserialize_non_blank_lines<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(LinesVisitor { skip_blank: true })
+}
+
+struct LinesVisitor {
+    skip_blank: bool,
+}
+
+impl<'de> Visitor<'de> for LinesVisitor {
+    type Value = Vec<String>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string with the lines or a sequence of strings")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Vec<String>, E>
+    where
+        E: serde::de::Error,
+    {
+        let mut lines: Vec<String> = v
+            .lines()
+            .filter(|line| !(self.skip_blank && line.trim().is_empty()))
+            .map(str::to_owned)
+            .collect();
+        while lines.last().map(String::as_str) == Some("") {
+            lines.pop();
+        }
+        Ok(lines)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Vec<String>, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        core::iter::from_fn(|| seq.next_element::<String>().transpose()).collect()
+    }
+}
+
+/// A separator of the values in a string, see [`StringOrVecToVec`] and [`DelimitedPair`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator<'a> {
+    /// Splits the string by a character.
+    Char(char),
+    /// Splits the string by a substring.
+    Str(&'a str),
+}
+
+impl<'a> From<char> for Separator<'a> {
+    fn from(c: char) -> Self {
+        Separator::Char(c)
+    }
+}
+
+impl<'a> From<&'a str> for Separator<'a> {
+    fn from(s: &'a str) -> Self {
+        Separator::Str(s)
+    }
+}
+
+impl<'a> Separator<'a> {
+    fn split(self, s: &str) -> Vec<&str> {
+        match self {
+            Separator::Char(c) => s.split(c).collect(),
+            Separator::Str(separator) => s.split(separator).collect(),
+        }
+    }
+}
+
+impl<'a> Display for Separator<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Separator::Char(c) => write!(formatter, "{:?}", c),
+            Separator::Str(s) => write!(formatter, "{:?}", s),
+        }
+    }
+}
+
+/// Builds a deserializer of a collection (a `Vec`, a `HashSet`, a `BTreeSet` or anything else
+/// implementing `FromIterator`) either from a sequence or from a string with the separated
+/// values, each of which is parsed with `FromStr`. An empty string always gives an empty
+/// collection. By default the values are separated by a comma and taken as is.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::BTreeSet;
+///
+/// fn vec_from_semicolons<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     StringOrVecToVec::new()
+///         .separator(';')
+///         .trim(true)
+///         .skip_empty(true)
+///         .into_deserializer()(deserializer)
+/// }
+///
+/// fn set_from_pipes<'de, D>(deserializer: D) -> Result<BTreeSet<String>, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     StringOrVecToVec::new().separator(" | ").into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "vec_from_semicolons")]
+///     list: Vec<u8>,
+///     #[serde(deserialize_with = "set_from_pipes")]
+///     set: BTreeSet<String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "list": " 1; 2 ;;3; ", "set": "b | a | b" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.list, vec![1, 2, 3]);
+///     assert_eq!(a.set.into_iter().collect::<Vec<_>>(), vec!["a", "b"]);
+///
+///     let s = r#" { "list": [4, 5], "set": ["c"] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.list, vec![4, 5]);
+///     assert_eq!(a.set.into_iter().collect::<Vec<_>>(), vec!["c"]);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct StringOrVecToVec<'a> {
+    separator: Separator<'a>,
+    trim: bool,
+    skip_empty: bool,
+}
+
+impl<'a> Default for StringOrVecToVec<'a> {
+    fn default() -> Self {
+        StringOrVecToVec {
+            separator: Separator::Char(','),
+            trim: false,
+            skip_empty: false,
+        }
+    }
+}
+
+impl<'a> StringOrVecToVec<'a> {
+    /// Creates a builder splitting the strings by a comma.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the separator of the values, either a `char` or a `&str`.
+    pub fn separator<S: Into<Separator<'a>>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Trims the whitespace around every value before parsing it.
+    pub fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Skips the empty values (after trimming, when enabled) instead of parsing them.
+    pub fn skip_empty(mut self, skip_empty: bool) -> Self {
+        self.skip_empty = skip_empty;
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, T, C, D>(self) -> impl FnMut(D) -> Result<C, D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        T: FromStr + Deserialize<'de>,
+        <T as FromStr>::Err: Display,
+        C: FromIterator<T>,
+    {
+        move |deserializer| {
+            deserializer.deserialize_any(StringOrVecVisitor {
+                options: self,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    /// Creates the deserializer function dropping the repeated values, only the first
+    /// occurrence of each value is kept. The values are compared after parsing, so with
+    /// `trim(true)` `"a, a"` gives a single `a`.
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use serde_aux::prelude::*;
+    ///
+    /// fn tags<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+    /// where
+    ///     D: serde::Deserializer<'de>,
+    /// {
+    ///     StringOrVecToVec::new().trim(true).into_dedup_deserializer()(deserializer)
+    /// }
+    ///
+    /// #[derive(serde::Deserialize, Debug)]
+    /// struct MyStruct {
+    ///     #[serde(deserialize_with = "tags")]
+    ///     tags: Vec<String>,
+    /// }
+    ///
+    /// fn main() {
+    ///     let a: MyStruct = serde_json::from_str(r#" { "tags": "b, a, b" } "#).unwrap();
+    ///     assert_eq!(a.tags, vec!["b", "a"]);
+    ///
+    ///     let a: MyStruct = serde_json::from_str(r#" { "tags": ["c", "c"] } "#).unwrap();
+    ///     assert_eq!(a.tags, vec!["c"]);
+    /// }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn into_dedup_deserializer<'de, T, C, D>(self) -> impl FnMut(D) -> Result<C, D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        T: FromStr + Deserialize<'de> + Eq + Hash,
+        <T as FromStr>::Err: Display,
+        C: FromIterator<T>,
+    {
+        let mut deserialize = self.into_deserializer::<T, Vec<T>, D>();
+        move |deserializer| {
+            let mut values = deserialize(deserializer)?;
+            dedup_keep_first(&mut values);
+            Ok(values.into_iter().collect())
+        }
+    }
+
+    /// Creates the deserializer function of a fixed-size array like `[u8; 4]`, the number of the
+    /// values must be exactly the length of the array.
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use serde_aux::prelude::*;
+    ///
+    /// fn address<'de, D>(deserializer: D) -> Result<[u8; 4], D::Error>
+    /// where
+    ///     D: serde::Deserializer<'de>,
+    /// {
+    ///     StringOrVecToVec::new()
+    ///         .separator('.')
+    ///         .trim(true)
+    ///         .into_array_deserializer()(deserializer)
+    /// }
+    ///
+    /// #[derive(serde::Deserialize, Debug)]
+    /// struct MyStruct {
+    ///     #[serde(deserialize_with = "address")]
+    ///     address: [u8; 4],
+    /// }
+    ///
+    /// fn main() {
+    ///     let a: MyStruct = serde_json::from_str(r#" { "address": "10. 0.0.1" } "#).unwrap();
+    ///     assert_eq!(a.address, [10, 0, 0, 1]);
+    ///
+    ///     let e = serde_json::from_str::<MyStruct>(r#" { "address": "10.0.1" } "#).unwrap_err();
+    ///     assert!(e.to_string().starts_with("expected 4 elements, found 3"));
+    /// }
+    /// ```
+    pub fn into_array_deserializer<'de, T, D, const N: usize>(
+        self,
+    ) -> impl FnMut(D) -> Result<[T; N], D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        T: FromStr + Deserialize<'de>,
+        <T as FromStr>::Err: Display,
+    {
+        let mut deserialize = self.into_deserializer::<T, Vec<T>, D>();
+        move |deserializer| {
+            let values = deserialize(deserializer)?;
+            <[T; N] as core::convert::TryFrom<Vec<T>>>::try_from(values).map_err(|values| {
+                serde::de::Error::custom(format!("expected {} elements, found {}", N, values.len()))
+            })
+        }
+    }
+
+    fn split<'s>(&self, s: &'s str) -> Vec<&'s str> {
+        if s.is_empty() {
+            return Vec::new();
+        }
+        self.separator
+            .split(s)
+            .into_iter()
+            .map(|p| if self.trim { p.trim() } else { p })
+            .filter(|p| !(self.skip_empty && p.is_empty()))
+            .collect()
+    }
+}
+
+struct StringOrVecVisitor<'a, T, C> {
+    options: StringOrVecToVec<'a>,
+    marker: PhantomData<(T, C)>,
+}
+
+impl<'a, 'de, T, C> Visitor<'de> for StringOrVecVisitor<'a, T, C>
+where
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+    C: FromIterator<T>,
+{
+    type Value = C;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence or a string with the separated values")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<C, E>
+    where
+        E: serde::de::Error,
+    {
+        self.options
+            .split(v)
+            .into_iter()
+            .map(|s| s.parse::<T>().map_err(E::custom))
+            .collect()
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<C, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        core::iter::from_fn(|| seq.next_element::<T>().transpose()).collect()
+    }
+}
+
+/// Builds a deserializer of a tuple from a string with two (or three) values joined by a
+/// separator, like `"1920x1080"`, `"10-20"` or `"12.5:48.1"`. Each value is parsed with
+/// `FromStr`. A string with a different number of the values is an error mentioning the
+/// separator.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn resolution<'de, D>(deserializer: D) -> Result<(u32, u32), D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     DelimitedPair::new('x').into_deserializer()(deserializer)
+/// }
+///
+/// fn color<'de, D>(deserializer: D) -> Result<(u8, u8, u8), D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     DelimitedPair::new(", ").into_triple_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "resolution")]
+///     resolution: (u32, u32),
+///     #[serde(deserialize_with = "color")]
+///     color: (u8, u8, u8),
+/// }
+///
+/// fn main() {
+///     let s = r#" { "resolution": "1920x1080", "color": "255, 128, 0" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.resolution, (1920, 1080));
+///     assert_eq!(a.color, (255, 128, 0));
+///
+///     let s = r#" { "resolution": "1920", "color": "255, 128, 0" } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"expected 2 values separated by 'x', found 1 in "1920""#));
+///
+///     let s = r#" { "resolution": "1x2x3", "color": "255, 128, 0" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DelimitedPair<'a> {
+    separator: Separator<'a>,
+}
+
+impl<'a> DelimitedPair<'a> {
+    /// Creates a builder splitting the strings by the separator, either a `char` or a `&str`.
+    pub fn new<S: Into<Separator<'a>>>(separator: S) -> Self {
+        DelimitedPair {
+            separator: separator.into(),
+        }
+    }
+
+    /// Creates the deserializer function of a pair, see the example above.
+    pub fn into_deserializer<'de, T, U, D>(self) -> impl FnMut(D) -> Result<(T, U), D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+        U: FromStr,
+        <U as FromStr>::Err: Display,
+    {
+        move |deserializer| {
+            deserializer.deserialize_str(DelimitedVisitor {
+                separator: self.separator,
+                arity: 2,
+                parse: |values: &[&str]| Ok((parse_value(values, 0)?, parse_value(values, 1)?)),
+            })
+        }
+    }
+
+    /// Creates the deserializer function of a triple, see the example above.
+    pub fn into_triple_deserializer<'de, T, U, V, D>(
+        self,
+    ) -> impl FnMut(D) -> Result<(T, U, V), D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+        U: FromStr,
+        <U as FromStr>::Err: Display,
+        V: FromStr,
+        <V as FromStr>::Err: Display,
+    {
+        move |deserializer| {
+            deserializer.deserialize_str(DelimitedVisitor {
+                separator: self.separator,
+                arity: 3,
+                parse: |values: &[&str]| {
+                    Ok((
+                        parse_value(values, 0)?,
+                        parse_value(values, 1)?,
+                        parse_value(values, 2)?,
+                    ))
+                },
+            })
+        }
+    }
+}
+
+fn parse_value<T>(values: &[&str], index: usize) -> Result<T, String>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    values[index].parse().map_err(|e| {
+        format!(
+            "invalid value {:?} at position {}: {}",
+            values[index], index, e
+        )
+    })
+}
+
+struct DelimitedVisitor<'a, F> {
+    separator: Separator<'a>,
+    arity: usize,
+    parse: F,
+}
+
+impl<'a, 'de, F, O> Visitor<'de> for DelimitedVisitor<'a, F>
+where
+    F: FnOnce(&[&str]) -> Result<O, String>,
+{
+    type Value = O;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a string with {} values separated by {}",
+            self.arity, self.separator
+        )
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<O, E>
+    where
+        E: serde::de::Error,
+    {
+        let values = self.separator.split(v);
+        if values.len() != self.arity {
+            return Err(E::custom(format!(
+                "expected {} values separated by {}, found {} in {:?}",
+                self.arity,
+                self.separator,
+                values.len(),
+                v
+            )));
+        }
+        (self.parse)(&values).map_err(E::custom)
+    }
+}
+
+/// Deserializes a map from a string with the `key=value` pairs separated by a semicolon, like
+/// `"a=1;b=2;c=3"`. Each value is parsed with `FromStr`. The map type is taken from the field: a
+/// `HashMap`, a `BTreeMap`, an `IndexMap` (which keeps the order of the pairs) or anything else
+/// implementing `FromIterator<(String, V)>`. See [`KeyValueStringToMap`] for the other separators
+/// and the details.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::HashMap;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_map_from_kv_string")]
+///     weights: HashMap<String, u8>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "weights": "a=1;b=2;c=3" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.weights.len(), 3);
+///     assert_eq!(a.weights["b"], 2);
+///
+///     let s = r#" { "weights": "a=1;b" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_map_from_kv_string<'de, V, M, D>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    V: FromStr,
+    <V as FromStr>::Err: Display,
+    M: FromIterator<(String, V)>,
+{
+    KeyValueStringToMap::new().into_deserializer()(deserializer)
+}
+
+/// Builds a deserializer of a map (a `HashMap`, a `BTreeMap` or anything else implementing
+/// `FromIterator<(String, V)>`) from a string with the key-value pairs, like `"a=1&b=2"`. The
+/// values are parsed with `FromStr`, the keys are taken as is. By default the pairs are separated
+/// by a semicolon and the keys from the values by an equals sign, and a pair without the
+/// key-value separator is an error.
+///
+/// - The empty pairs (as in `"a=1;;b=2;"`) are skipped, so an empty string gives an empty map.
+/// - Only the first key-value separator of a pair counts: `"a=b=c"` is the key `a` with `b=c`.
+/// - When a key is repeated, the last value wins (for the maps which behave so on `collect`).
+/// - No decoding is done: a percent-encoded query string like `"a=x%20y"` gives `x%20y`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::BTreeMap;
+///
+/// fn flags<'de, D>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     KeyValueStringToMap::new()
+///         .pair_separator('&')
+///         .allow_missing_value(true)
+///         .into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "flags")]
+///     flags: BTreeMap<String, String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "flags": "mode=fast&verbose&level=a=b" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.flags["mode"], "fast");
+///     assert_eq!(a.flags["verbose"], "");
+///     assert_eq!(a.flags["level"], "a=b");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct KeyValueStringToMap<'a> {
+    pair_separator: Separator<'a>,
+    key_value_separator: Separator<'a>,
+    allow_missing_value: bool,
+}
+
+impl<'a> Default for KeyValueStringToMap<'a> {
+    fn default() -> Self {
+        KeyValueStringToMap {
+            pair_separator: Separator::Char(';'),
+            key_value_separator: Separator::Char('='),
+            allow_missing_value: false,
+        }
+    }
+}
+
+impl<'a> KeyValueStringToMap<'a> {
+    /// Creates a builder splitting the pairs by a semicolon and the keys from the values by an
+    /// equals sign.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the separator of the pairs, either a `char` or a `&str`.
+    pub fn pair_separator<S: Into<Separator<'a>>>(mut self, separator: S) -> Self {
+        self.pair_separator = separator.into();
+        self
+    }
+
+    /// Sets the separator of a key from its value, either a `char` or a `&str`.
+    pub fn key_value_separator<S: Into<Separator<'a>>>(mut self, separator: S) -> Self {
+        self.key_value_separator = separator.into();
+        self
+    }
+
+    /// Parses the value of a pair without the key-value separator from an empty string instead
+    /// of failing.
+    pub fn allow_missing_value(mut self, allow_missing_value: bool) -> Self {
+        self.allow_missing_value = allow_missing_value;
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, V, C, D>(self) -> impl FnMut(D) -> Result<C, D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        V: FromStr,
+        <V as FromStr>::Err: Display,
+        C: FromIterator<(String, V)>,
+    {
+        move |deserializer| {
+            deserializer.deserialize_str(KeyValueStringVisitor {
+                options: self,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    fn split_pair<'s>(&self, pair: &'s str) -> Option<(&'s str, &'s str)> {
+        let found = match self.key_value_separator {
+            Separator::Char(c) => pair.find(c).map(|i| (i, c.len_utf8())),
+            Separator::Str(separator) => pair.find(separator).map(|i| (i, separator.len())),
+        };
+        match found {
+            Some((i, len)) => Some((&pair[..i], &pair[i + len..])),
+            None if self.allow_missing_value => Some((pair, "")),
+            None => None,
+        }
+    }
+}
+
+struct KeyValueStringVisitor<'a, V, C> {
+    options: KeyValueStringToMap<'a>,
+    marker: PhantomData<(V, C)>,
+}
+
+impl<'a, 'de, V, C> Visitor<'de> for KeyValueStringVisitor<'a, V, C>
+where
+    V: FromStr,
+    <V as FromStr>::Err: Display,
+    C: FromIterator<(String, V)>,
+{
+    type Value = C;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a string with the key{}value pairs separated by {}",
+            self.options.key_value_separator, self.options.pair_separator
+        )
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<C, E>
+    where
+        E: serde::de::Error,
+    {
+        self.options
+            .pair_separator
+            .split(v)
+            .into_iter()
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = self.options.split_pair(pair).ok_or_else(|| {
+                    E::custom(format!(
+                        "missing the key-value separator {} in the pair {:?}",
+                        self.options.key_value_separator, pair
+                    ))
+                })?;
+                let value = value
+                    .parse::<V>()
+                    .map_err(|e| E::custom(format!("invalid value for key {:?}: {}", key, e)))?;
+                Ok((key.to_owned(), value))
+            })
+            .collect()
+    }
+}
+
+/// Deserializes a `BTreeMap` from a list of the key-value objects, like
+/// `[{"key": "2021-01-01", "value": 5}, ...]`. The keys are parsed with `FromStr` (from a string
+/// or a number) and the values are deserialized as usual; a repeated key is an error. See
+/// [`KeyValueObjectsToMap`] for the other member names and the details.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::BTreeMap;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_map_from_kv_objects")]
+///     visits: BTreeMap<u16, u32>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "visits": [
+///         { "key": "2021", "value": 7 },
+///         { "key": 2020, "value": 5 }
+///     ] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.visits.into_iter().collect::<Vec<_>>(), vec![(2020, 5), (2021, 7)]);
+///
+///     let s = r#" { "visits": [
+///         { "key": "2021", "value": 5 },
+///         { "key": "2021", "value": 7 }
+///     ] } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"duplicate key "2021""#));
+/// }
+/// ```
+pub fn deserialize_map_from_kv_objects<'de, K, V, D>(
+    deserializer: D,
+) -> Result<BTreeMap<K, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: FromStr + Ord,
+    <K as FromStr>::Err: Display,
+    V: Deserialize<'de>,
+{
+    KeyValueObjectsToMap::new().into_deserializer()(deserializer)
+}
+
+/// Builds a deserializer of a `BTreeMap` from a list of the objects holding a key and a value
+/// each, like `[{"id": 1, "name": "a"}, ...]`. By default the members are named `key` and
+/// `value`.
+///
+/// - A key is taken from a string or a number and parsed with `FromStr`.
+/// - A repeated key is an error, as well as a missing member or a member given twice.
+/// - The other members of the objects are ignored.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::BTreeMap;
+///
+/// fn names<'de, D>(deserializer: D) -> Result<BTreeMap<u32, String>, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     KeyValueObjectsToMap::new()
+///         .key_member("id")
+///         .value_member("name")
+///         .into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "names")]
+///     names: BTreeMap<u32, String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "names": [
+///         { "id": 2, "name": "b", "extra": true },
+///         { "id": "1", "name": "a" }
+///     ] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.names[&1], "a");
+///     assert_eq!(a.names[&2], "b");
+///
+///     let s = r#" { "names": [{ "id": 1 }] } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"missing member "name""#));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct KeyValueObjectsToMap<'a> {
+    key_member: &'a str,
+    value_member: &'a str,
+}
+
+impl<'a> Default for KeyValueObjectsToMap<'a> {
+    fn default() -> Self {
+        KeyValueObjectsToMap {
+            key_member: "key",
+            value_member: "value",
+        }
+    }
+}
+
+impl<'a> KeyValueObjectsToMap<'a> {
+    /// Creates a builder reading the `key` and the `value` members of the objects.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name of the member holding the key.
+    pub fn key_member(mut self, name: &'a str) -> Self {
+        self.key_member = name;
+        self
+    }
+
+    /// Sets the name of the member holding the value.
+    pub fn value_member(mut self, name: &'a str) -> Self {
+        self.value_member = name;
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, K, V, D>(
+        self,
+    ) -> impl FnMut(D) -> Result<BTreeMap<K, V>, D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        K: FromStr + Ord,
+        <K as FromStr>::Err: Display,
+        V: Deserialize<'de>,
+    {
+        move |deserializer| {
+            deserializer.deserialize_seq(KeyValueObjectsVisitor {
+                options: self,
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+struct KeyValueObjectsVisitor<'a, K, V> {
+    options: KeyValueObjectsToMap<'a>,
+    marker: PhantomData<(K, V)>,
+}
+
+impl<'a, 'de, K, V> Visitor<'de> for KeyValueObjectsVisitor<'a, K, V>
+where
+    K: FromStr + Ord,
+    <K as FromStr>::Err: Display,
+    V: Deserialize<'de>,
+{
+    type Value = BTreeMap<K, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a sequence of the objects with the {:?} and the {:?} members",
+            self.options.key_member, self.options.value_member
+        )
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        let mut values = BTreeMap::new();
+        while let Some((raw_key, key, value)) = seq.next_element_seed(KeyValueObject {
+            options: self.options,
+            marker: PhantomData,
+        })? {
+            match values.entry(key) {
+                Entry::Occupied(_) => {
+                    return Err(serde::de::Error::custom(format!(
+                        "duplicate key {:?}",
+                        raw_key
+                    )))
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// A single object of [`KeyValueObjectsToMap`], gives the key as received, the parsed key and
+/// the value.
+struct KeyValueObject<'a, K, V> {
+    options: KeyValueObjectsToMap<'a>,
+    marker: PhantomData<(K, V)>,
+}
+
+impl<'a, 'de, K, V> serde::de::DeserializeSeed<'de> for KeyValueObject<'a, K, V>
+where
+    K: FromStr,
+    <K as FromStr>::Err: Display,
+    V: Deserialize<'de>,
+{
+    type Value = (String, K, V);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'a, 'de, K, V> Visitor<'de> for KeyValueObject<'a, K, V>
+where
+    K: FromStr,
+    <K as FromStr>::Err: Display,
+    V: Deserialize<'de>,
+{
+    type Value = (String, K, V);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "an object with the {:?} and the {:?} members",
+            self.options.key_member, self.options.value_member
+        )
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        use serde::de::Error;
+
+        let mut key = None;
+        let mut value = None;
+        while let Some(member) = map.next_key::<String>()? {
+            if member == self.options.key_member {
+                if key.is_some() {
+                    return Err(A::Error::custom(format!("duplicate member {:?}", member)));
+                }
+                key = Some(map.next_value_seed(KeyMember)?);
+            } else if member == self.options.value_member {
+                if value.is_some() {
+                    return Err(A::Error::custom(format!("duplicate member {:?}", member)));
+                }
+                value = Some(map.next_value::<V>()?);
+            } else {
+                map.next_value::<serde::de::IgnoredAny>()?;
+            }
+        }
+        let raw_key = key.ok_or_else(|| {
+            A::Error::custom(format!("missing member {:?}", self.options.key_member))
+        })?;
+        let value = value.ok_or_else(|| {
+            A::Error::custom(format!("missing member {:?}", self.options.value_member))
+        })?;
+        let key = raw_key
+            .parse::<K>()
+            .map_err(|e| A::Error::custom(format!("invalid key {:?}: {}", raw_key, e)))?;
+        Ok((raw_key, key, value))
+    }
+}
+
+/// The key member of [`KeyValueObject`], either a string or a number.
+struct KeyMember;
+
+impl<'de> serde::de::DeserializeSeed<'de> for KeyMember {
+    type Value = String;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_string_from_number(deserializer)
+    }
+}
+
+/// Serializes a sequence as a string with the comma separated values. This is the counterpart
+/// of [`deserialize_vec_from_string_or_vec`]; note that the values containing a comma can't be
+/// read back.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_vec_as_comma_string")]
+///     list: Vec<u16>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { list: vec![1, 2, 3] };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"list":"1,2,3"}"#);
+///
+///     let a = MyStruct { list: vec![] };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"list":""}"#);
+/// }
+/// ```
+pub fn serialize_vec_as_comma_string<T, S>(values: &[T], serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    let mut joined = String::new();
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            joined.push(',');
+        }
+        joined.push_str(&value.to_string());
+    }
+    serializer.serialize_str(&joined)
+}
+
+/// Serializes a sequence writing every element as a string, like `["9007199254740993"]`, for the
+/// consumers (like JavaScript) which lose the precision of the 64-bit integers. The counterpart
+/// is [`deserialize_vec_elements_from_strings`], see
+/// [`with::vec_elements_as_strings`](crate::with::vec_elements_as_strings).
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_vec_elements_as_strings")]
+///     ids: Vec<u64>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { ids: vec![9007199254740993, 1] };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"ids":["9007199254740993","1"]}"#);
+/// }
+/// ```
+pub fn serialize_vec_elements_as_strings<T, S>(
+    values: &[T],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    struct AsString<'a, T>(&'a T);
+
+    impl<'a, T: Display> Serialize for AsString<'a, T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self.0)
+        }
+    }
+
+    serializer.collect_seq(values.iter().map(AsString))
+}
+
+/// Deserializes a `Vec` of the numbers from a string of the numbers and the inclusive ranges
+/// separated with the commas, like the page selections: `"1-5,8,10-12"` gives
+/// `[1, 2, 3, 4, 5, 8, 10, 11, 12]`. The spaces around the parts are ignored and an empty string
+/// gives an empty `Vec`. The element type is any which a `u64` converts into, like `u32`.
+///
+/// A range whose start is greater than its end is an error, and so are the ranges expanding to
+/// more than 65536 numbers in total, so a `"0-18446744073709551615"` doesn't exhaust the memory.
+/// Use [`ExpandRanges`] for another limit. Requires the `ranges` feature.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_expand_ranges")]
+///     pages: Vec<u32>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "pages": "1-5, 8,10-12" } "#).unwrap();
+///     assert_eq!(a.pages, vec![1, 2, 3, 4, 5, 8, 10, 11, 12]);
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "pages": "5-1" } "#).unwrap_err();
+///     assert!(e.to_string().contains("the start 5 is greater than the end 1"));
+///     let s = r#" { "pages": "0-18446744073709551615" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+#[cfg(feature = "ranges")]
+pub fn deserialize_expand_ranges<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    T: core::convert::TryFrom<u64> + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    ExpandRanges::new().into_deserializer()(deserializer)
+}
+
+/// Builds a deserializer of a `Vec` of the numbers from a string of the numbers and the ranges,
+/// as [`deserialize_expand_ranges`] does, with another limit of the total number of the
+/// numbers. Requires the `ranges` feature.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn ids<'de, D>(deserializer: D) -> Result<Vec<u64>, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     ExpandRanges::new().max_len(10).into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "ids")]
+///     ids: Vec<u64>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "ids": "1-8,20,30" } "#).unwrap();
+///     assert_eq!(a.ids.len(), 10);
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "ids": "1-8,20-22" } "#).unwrap_err();
+///     assert!(e.to_string().contains("more than 10 numbers"));
+/// }
+/// ```
+#[cfg(feature = "ranges")]
+#[derive(Debug, Clone, Copy)]
+pub struct ExpandRanges {
+    max_len: usize,
+}
+
+#[cfg(feature = "ranges")]
+impl Default for ExpandRanges {
+    fn default() -> Self {
+        ExpandRanges::new()
+    }
+}
+
+#[cfg(feature = "ranges")]
+impl ExpandRanges {
+    /// Creates a builder allowing up to 65536 numbers.
+    pub fn new() -> Self {
+        ExpandRanges { max_len: 65536 }
+    }
+
+    /// Sets the maximum total number of the numbers.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, T, D>(self) -> impl FnMut(D) -> Result<Vec<T>, D::Error>
+    where
+        T: core::convert::TryFrom<u64> + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        move |deserializer| {
+            use serde::de::Error;
+
+            let value = String::deserialize(deserializer)?;
+            self.expand(&value).map_err(D::Error::custom)
+        }
+    }
+
+    fn expand<'de, T>(&self, value: &str) -> Result<Vec<T>, String>
+    where
+        T: core::convert::TryFrom<u64> + Deserialize<'de>,
+    {
+        let number = |s: &str| {
+            let s = s.trim();
+            s.parse::<u64>()
+                .map_err(|e| format!("invalid number {:?} in {:?}: {}", s, value, e))
+        };
+        let mut numbers = Vec::new();
+        if value.trim().is_empty() {
+            return Ok(numbers);
+        }
+        for part in value.split(',') {
+            let (start, end) = match part.find('-') {
+                Some(i) => (number(&part[..i])?, number(&part[i + 1..])?),
+                None => {
+                    let n = number(part)?;
+                    (n, n)
+                }
+            };
+            if start > end {
+                return Err(format!(
+                    "invalid range {:?}: the start {} is greater than the end {}",
+                    part.trim(),
+                    start,
+                    end
+                ));
+            }
+            if end - start >= (self.max_len - numbers.len()) as u64 {
+                return Err(format!(
+                    "the ranges of {:?} expand to more than {} numbers",
+                    value, self.max_len
+                ));
+            }
+            for n in start..=end {
+                let n = T::try_from(n).map_err(|_| match integer_range::<T>() {
+                    Some((name, min, max)) => format!(
+                        "{} in {:?} is out of range for {} ({}..={})",
+                        n, value, name, min, max
+                    ),
+                    None => format!("{} in {:?} is out of range", n, value),
+                })?;
+                numbers.push(n);
+            }
+        }
+        Ok(numbers)
+    }
+}
+
+/// Deserializes a `Vec<T>` from a sequence of the numbers or the numeric strings, reading every
+/// element as [`deserialize_number_from_string`] does. This is the counterpart of
+/// [`serialize_vec_elements_as_strings`].
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_vec_elements_from_strings")]
+///     ids: Vec<u64>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "ids": ["9007199254740993", 1] } "#).unwrap();
+///     assert_eq!(a.ids, vec![9007199254740993, 1]);
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "ids": ["x"] } "#).is_err());
+/// }
+/// ```
+pub fn deserialize_vec_elements_from_strings<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    struct NumberElement<T>(PhantomData<T>);
+
+    impl<'de, T> serde::de::DeserializeSeed<'de> for NumberElement<T>
+    where
+        T: FromStr + Deserialize<'de>,
+        <T as FromStr>::Err: Display,
+    {
+        type Value = T;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<T, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserialize_number_from_string(deserializer)
+        }
+    }
+
+    struct NumberElementsVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for NumberElementsVisitor<T>
+    where
+        T: FromStr + Deserialize<'de>,
+        <T as FromStr>::Err: Display,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a sequence of numbers or numeric strings")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Vec<T>, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(value) = seq.next_element_seed(NumberElement(PhantomData))? {
+                values.push(value);
+            }
+            Ok(values)
+        }
+    }
+
+    deserializer.deserialize_seq(NumberElementsVisitor(PhantomData))
+}
+
+/// Builds a deserializer of a boolean from the configurable sets of the truthy and falsy tokens,
+/// like **"enabled"**/**"disabled"** or **"oui"**/**"non"**. The tokens are matched against the
+/// strings case-insensitively; the numbers are matched by their string representation and the
+/// plain booleans are accepted as is.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn bool_from_tokens<'de, D>(deserializer: D) -> Result<bool, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     BoolFromTokens::new()
+///         .truthy(&["enabled", "oui", "1"])
+///         .falsy(&["disabled", "non", "0"])
+///         .into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "bool_from_tokens")]
+///     feature: bool,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "feature": "Enabled" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(a.feature);
+///
+///     let s = r#" { "feature": "NON" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(!a.feature);
+///
+///     let s = r#" { "feature": 1 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(a.feature);
+///
+///     let s = r#" { "feature": false } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert!(!a.feature);
+///
+///     let s = r#" { "feature": "yes" } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().contains(r#"truthy tokens ["enabled", "oui", "1"]"#));
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BoolFromTokens {
+    truthy: Vec<String>,
+    falsy: Vec<String>,
+}
+
+impl BoolFromTokens {
+    /// Creates a builder with no tokens.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the tokens deserialized as `true`.
+    pub fn truthy<I>(mut self, tokens: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.truthy
+            .extend(tokens.into_iter().map(|t| t.as_ref().to_lowercase()));
+        self
+    }
+
+    /// Adds the tokens deserialized as `false`.
+    pub fn falsy<I>(mut self, tokens: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.falsy
+            .extend(tokens.into_iter().map(|t| t.as_ref().to_lowercase()));
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, D>(self) -> impl FnMut(D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        move |deserializer| deserializer.deserialize_any(BoolFromTokensVisitor(&self))
+    }
+
+    fn parse<E: serde::de::Error>(&self, token: &str) -> Result<bool, E> {
+        let token = token.to_lowercase();
+        if self.truthy.contains(&token) {
+            Ok(true)
+        } else if self.falsy.contains(&token) {
+            Ok(false)
+        } else {
+            Err(E::custom(format!(
+                "unknown boolean token {:?}, expected one of the truthy tokens {:?} or the falsy tokens {:?}",
+                token, self.truthy, self.falsy
+            )))
+        }
+    }
+}
+
+struct BoolFromTokensVisitor<'a>(&'a BoolFromTokens);
+
+impl<'de, 'a> Visitor<'de> for BoolFromTokensVisitor<'a> {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a boolean or a boolean token")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        self.0.parse(&v.to_string())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        self.0.parse(&v.to_string())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        self.0.parse(&v.to_string())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<bool, E>
+    where
+        E: serde::de::Error,
+    {
+        self.0.parse(v)
+    }
+}
+
+/// Builds a serializer writing a boolean as one of the two tokens, like **"Y"**/**"N"**, and the
+/// matching deserializer (a [`BoolFromTokens`] with just these tokens), so both directions of a
+/// field can be declared with the same tokens.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// const FLAG: BoolAsToken = BoolAsToken::new("Y", "N");
+///
+/// fn serialize_flag<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+/// where
+///     S: serde::Serializer,
+/// {
+///     FLAG.into_serializer()(value, serializer)
+/// }
+///
+/// fn deserialize_flag<'de, D>(deserializer: D) -> Result<bool, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     FLAG.into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_flag", deserialize_with = "deserialize_flag")]
+///     active: bool,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { active: true };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"active":"Y"}"#);
+///     assert!(serde_json::from_str::<MyStruct>(&s).unwrap().active);
+///
+///     let a: MyStruct = serde_json::from_str(r#"{"active":"n"}"#).unwrap();
+///     assert!(!a.active);
+///     assert!(serde_json::from_str::<MyStruct>(r#"{"active":"yes"}"#).is_err());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BoolAsToken<'a> {
+    truthy: &'a str,
+    falsy: &'a str,
+}
+
+impl<'a> BoolAsToken<'a> {
+    /// Creates a builder writing `true` as `truthy` and `false` as `falsy`.
+    pub const fn new(truthy: &'a str, falsy: &'a str) -> Self {
+        BoolAsToken { truthy, falsy }
+    }
+
+    /// Creates the serializer function, see the example above.
+    pub fn into_serializer<S>(self) -> impl Fn(&bool, S) -> Result<S::Ok, S::Error> + 'a
+    where
+        S: Serializer,
+    {
+        move |value, serializer| {
+            serializer.serialize_str(if *value { self.truthy } else { self.falsy })
+        }
+    }
+
+    /// Creates the deserializer function accepting the two tokens (case-insensitively) and the
+    /// plain booleans, see [`BoolFromTokens`].
+    pub fn into_deserializer<'de, D>(self) -> impl FnMut(D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        BoolFromTokens::new()
+            .truthy(&[self.truthy])
+            .falsy(&[self.falsy])
+            .into_deserializer()
+    }
+}
+
+/// Serializes a boolean as **"yes"** or **"no"**. The [`deserialize_bool_from_yes_no`] reads it
+/// back, see also the [`bool_yes_no`](crate::with::bool_yes_no) module doing both.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_bool_as_yes_no")]
+///     subscribed: bool,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { subscribed: false };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"subscribed":"no"}"#);
+/// }
+/// ```
+pub fn serialize_bool_as_yes_no<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    BoolAsToken::new("yes", "no").into_serializer()(value, serializer)
+}
+
+/// Deserializes a boolean from **"yes"** or **"no"** (case-insensitively) or from a plain
+/// boolean, the counterpart of [`serialize_bool_as_yes_no`].
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_bool_from_yes_no")]
+///     subscribed: bool,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "subscribed": "Yes" } "#).unwrap();
+///     assert!(a.subscribed);
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "subscribed": "on" } "#).is_err());
+/// }
+/// ```
+pub fn deserialize_bool_from_yes_no<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    BoolAsToken::new("yes", "no").into_deserializer()(deserializer)
+}
+
+/// Serializes a boolean as **"on"** or **"off"**. The [`deserialize_bool_from_on_off`] reads it
+/// back, see also the [`bool_on_off`](crate::with::bool_on_off) module doing both.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_bool_as_on_off")]
+///     light: bool,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { light: true };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"light":"on"}"#);
+/// }
+/// ```
+pub fn serialize_bool_as_on_off<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    BoolAsToken::new("on", "off").into_serializer()(value, serializer)
+}
+
+/// Deserializes a boolean from **"on"** or **"off"** (case-insensitively) or from a plain
+/// boolean, the counterpart of [`serialize_bool_as_on_off`].
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_bool_from_on_off")]
+///     light: bool,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "light": "OFF" } "#).unwrap();
+///     assert!(!a.light);
+///     let a: MyStruct = serde_json::from_str(r#" { "light": true } "#).unwrap();
+///     assert!(a.light);
+/// }
+/// ```
+pub fn deserialize_bool_from_on_off<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    BoolAsToken::new("on", "off").into_deserializer()(deserializer)
+}
+
+/// Deserializes a number from a string formatted with the locale specific separators, using the
+/// comma as the decimal separator and the dot as the grouping one: **"1.234,56"** is `1234.56`.
+/// The numbers are accepted as well. See [`NumberFromLocaleString`] for the other separators.
+///
+/// The separators are applied strictly, so with the defaults **"1,234"** is always `1.234` and
+/// never one thousand two hundred thirty four. The decimal separator may occur once at most, and
+/// the grouping separators are only allowed before it; the size of the groups is not checked.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_number_from_locale_string")]
+///     price: f64,
+///     #[serde(deserialize_with = "deserialize_number_from_locale_string")]
+///     count: u32,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "price": "1.234,56", "count": "1.000" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.price, 1234.56);
+///     assert_eq!(a.count, 1000);
+///
+///     let s = r#" { "price": "1,234", "count": 7 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.price, 1.234);
+///     assert_eq!(a.count, 7);
+///
+///     let s = r#" { "price": "1,2,3", "count": 1 } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+///
+///     let s = r#" { "price": "1,2.3", "count": 1 } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_number_from_locale_string<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    NumberFromLocaleString::new().into_deserializer()(deserializer)
+}
+
+/// Builds a deserializer of a number from a string with the configurable decimal and grouping
+/// separators. The same rules as in [`deserialize_number_from_locale_string`] apply.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn swiss_number<'de, D>(deserializer: D) -> Result<f64, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     NumberFromLocaleString::new()
+///         .decimal_separator('.')
+///         .grouping_separator(Some('\''))
+///         .into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "swiss_number")]
+///     price: f64,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "price": "1'234.5" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.price, 1234.5);
+///
+///     let s = r#" { "price": "1.234,5" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct NumberFromLocaleString {
+    decimal_separator: char,
+    grouping_separator: Option<char>,
+}
+
+impl Default for NumberFromLocaleString {
+    fn default() -> Self {
+        NumberFromLocaleString {
+            decimal_separator: ',',
+            grouping_separator: Some('.'),
+        }
+    }
+}
+
+impl NumberFromLocaleString {
+    /// Creates a builder with the comma as the decimal separator and the dot as the grouping one.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the decimal separator.
+    pub fn decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Sets the grouping separator, `None` disallows grouping.
+    pub fn grouping_separator(mut self, separator: Option<char>) -> Self {
+        self.grouping_separator = separator;
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, T, D>(self) -> impl FnMut(D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr + Deserialize<'de>,
+        <T as FromStr>::Err: Display,
+    {
+        move |deserializer| {
+            deserializer.deserialize_any(NumberFromLocaleStringVisitor {
+                format: self,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    fn normalize(&self, s: &str) -> Option<String> {
+        let mut parts = s.splitn(2, self.decimal_separator);
+        let integer = parts.next().unwrap_or_default();
+        let fraction = parts.next();
+        if let Some(fraction) = fraction {
+            let mut grouping = self.grouping_separator.into_iter();
+            if fraction.contains(self.decimal_separator) || grouping.any(|g| fraction.contains(g)) {
+                return None;
+            }
+        }
+
+        let mut normalized: String = integer
+            .chars()
+            .filter(|&c| Some(c) != self.grouping_separator)
+            .collect();
+        if let Some(fraction) = fraction {
+            normalized.push('.');
+            normalized.push_str(fraction);
+        }
+        Some(normalized)
+    }
+}
+
+struct NumberFromLocaleStringVisitor<T> {
+    format: NumberFromLocaleString,
+    marker: PhantomData<T>,
+}
+
+impl<'de, T> Visitor<'de> for NumberFromLocaleStringVisitor<T>
+where
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a number or a numeric string with {:?} as the decimal separator",
+            self.format.decimal_separator
+        )?;
+        if let Some(grouping) = self.format.grouping_separator {
+            write!(formatter, " and {:?} as the grouping separator", grouping)?;
+        }
+        Ok(())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        match self.format.normalize(v) {
+            Some(normalized) => normalized.parse::<T>().map_err(|e| {
+                E::custom(format_args!(
+                    "invalid value: string {:?}, expected {}: {}",
+                    v,
+                    Expecting(&self),
+                    describe_number_error::<T>(&normalized, e)
+                ))
+            }),
+            None => Err(E::invalid_value(serde::de::Unexpected::Str(v), &self)),
+        }
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i64(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u64(v)
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i128(v)
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u128(v)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_f64(v)
+    }
+}
+
+/// Deserializes a value and wraps it into `Some`. Together with `#[serde(default)]` on an
+/// `Option<Option<T>>` field this tells a missing field (`None`) from an explicit `null`
+/// (`Some(None)`) and a value (`Some(Some(value))`), which is what the `PATCH`-like requests need.
+/// See [`serialize_double_option`] for the other direction.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(default, deserialize_with = "deserialize_some")]
+///     name: Option<Option<String>>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" {} "#).unwrap();
+///     assert_eq!(a.name, None);
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "name": null } "#).unwrap();
+///     assert_eq!(a.name, Some(None));
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "name": "foo" } "#).unwrap();
+///     assert_eq!(a.name, Some(Some("foo".to_owned())));
+/// }
+/// ```
+pub fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Serializes an `Option<Option<T>>`: `Some(None)` as `null` and `Some(Some(value))` as the value.
+/// The missing field (`None`) can't be expressed by a serializer of the field itself, so use it
+/// with `#[serde(skip_serializing_if = "Option::is_none")]`; without that `None` is written as
+/// `null` as well.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(
+///         serialize_with = "serialize_double_option",
+///         skip_serializing_if = "Option::is_none"
+///     )]
+///     name: Option<Option<String>>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { name: None };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{}"#);
+///
+///     let a = MyStruct { name: Some(None) };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"name":null}"#);
+///
+///     let a = MyStruct { name: Some(Some("foo".to_owned())) };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"name":"foo"}"#);
+/// }
+/// ```
+pub fn serialize_double_option<T, S>(
+    value: &Option<Option<T>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: serde::Serialize,
+    S: Serializer,
+{
+    match value {
+        Some(Some(value)) => serializer.serialize_some(value),
+        Some(None) | None => serializer.serialize_none(),
+    }
+}
+
+/// Consumes any value without looking at it and gives `Default::default()` instead. Useful for
+/// the fields which must be tolerated but are never used, and whose type is not stable: such a
+/// field never fails the deserialization, whatever its shape is. The value is skipped with
+/// `serde::de::IgnoredAny`, so the format must be able to skip values (the self-describing ones
+/// can).
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_ignore_any")]
+///     legacy: (),
+///     #[serde(deserialize_with = "deserialize_ignore_any")]
+///     other: Option<u32>,
+///     name: String,
+/// }
+///
+/// fn main() {
+///     for s in &[
+///         r#" { "legacy": "foo", "other": 1, "name": "a" } "#,
+///         r#" { "legacy": { "foo": [1, 2] }, "other": null, "name": "a" } "#,
+///         r#" { "legacy": [true, 1.5], "other": "bar", "name": "a" } "#,
+///     ] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.other, None);
+///         assert_eq!(a.name, "a");
+///     }
+/// }
+/// ```
+pub fn deserialize_ignore_any<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default,
+{
+    serde::de::IgnoredAny::deserialize(deserializer)?;
+    Ok(T::default())
+}
+
+/// Maps the integer representation (the discriminant) of an enum to its variants, for
+/// [`deserialize_enum_from_repr_or_name`].
+pub trait EnumFromRepr: Sized {
+    /// All the known discriminants, listed in the error messages.
+    const DISCRIMINANTS: &'static [i64];
+
+    /// Returns the variant with the given discriminant, if there is one.
+    fn from_repr(repr: i64) -> Option<Self>;
+}
+
+/// Deserializes an enum either from its integer representation, mapped by the [`EnumFromRepr`]
+/// implementation, or from a variant name, handled by the enum's own `Deserialize`
+/// implementation (so `#[serde(rename)]` and the like are respected).
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug, PartialEq, Clone, Copy)]
+/// #[serde(rename_all = "lowercase")]
+/// #[repr(u8)]
+/// enum Status {
+///     Inactive = 0,
+///     Active = 1,
+///     Banned = 5,
+/// }
+///
+/// impl EnumFromRepr for Status {
+///     const DISCRIMINANTS: &'static [i64] = &[0, 1, 5];
+///
+///     fn from_repr(repr: i64) -> Option<Self> {
+///         match repr {
+///             0 => Some(Status::Inactive),
+///             1 => Some(Status::Active),
+///             5 => Some(Status::Banned),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_enum_from_repr_or_name")]
+///     status: Status,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "status": 5 } "#).unwrap();
+///     assert_eq!(a.status, Status::Banned);
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "status": "active" } "#).unwrap();
+///     assert_eq!(a.status, Status::Active);
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "status": 3 } "#).unwrap_err();
+///     assert!(e.to_string().starts_with("unknown discriminant 3, expected one of [0, 1, 5]"));
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "status": "Active" } "#).is_err());
+/// }
+/// ```
+pub fn deserialize_enum_from_repr_or_name<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: EnumFromRepr + Deserialize<'de>,
+{
+    use serde::de::IntoDeserializer;
+
+    struct EnumFromReprOrNameVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> EnumFromReprOrNameVisitor<T>
+    where
+        T: EnumFromRepr + Deserialize<'de>,
+    {
+        fn from_repr<E: serde::de::Error>(repr: i64) -> Result<T, E> {
+            T::from_repr(repr).ok_or_else(|| {
+                E::custom(format!(
+                    "unknown discriminant {}, expected one of {:?}",
+                    repr,
+                    T::DISCRIMINANTS
+                ))
+            })
+        }
+    }
+
+    impl<'de, T> Visitor<'de> for EnumFromReprOrNameVisitor<T>
+    where
+        T: EnumFromRepr + Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an enum discriminant or a variant name")
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            Self::from_repr(v)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            if v > i64::MAX as u64 {
+                return Err(E::custom(format!(
+                    "unknown discriminant {}, expected one of {:?}",
+                    v,
+                    T::DISCRIMINANTS
+                )));
+            }
+            Self::from_repr(v as i64)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer())
+        }
+
+        fn visit_borrowed_str<E>(self, v: &'de str) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(serde::de::value::BorrowedStrDeserializer::new(v))
+        }
+    }
+
+    deserializer.deserialize_any(EnumFromReprOrNameVisitor(PhantomData))
+}
+
+/// Deserializes an enum from its variant name after trimming the surrounding whitespace and
+/// ignoring the case, so **" ACTIVE "** gives the variant named `"active"`. The variant names are
+/// taken from the enum itself (see [`crate::serde_introspection::serde_introspect`]), so the
+/// `#[serde(rename)]` and `#[serde(rename_all)]` attributes are respected. The values which are
+/// not strings (the numeric tags, the maps of the externally tagged variants) are passed to the
+/// enum untouched.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug, PartialEq)]
+/// #[serde(rename_all = "snake_case")]
+/// enum Status {
+///     Active,
+///     OnHold,
+///     Custom(u8),
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_enum_normalized")]
+///     status: Status,
+/// }
+///
+/// fn main() {
+///     for s in &[r#" { "status": " ACTIVE " } "#, r#" { "status": "active" } "#] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.status, Status::Active);
+///     }
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "status": "On_Hold" } "#).unwrap();
+///     assert_eq!(a.status, Status::OnHold);
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "status": { "custom": 7 } } "#).unwrap();
+///     assert_eq!(a.status, Status::Custom(7));
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "status": " banned " } "#).unwrap_err();
+///     assert!(e.to_string().starts_with("unknown variant `banned`"));
+/// }
+/// ```
+pub fn deserialize_enum_normalized<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer, StrDeserializer};
+    use serde::de::IntoDeserializer;
+
+    struct EnumNormalizedVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for EnumNormalizedVisitor<T>
+    where
+        T: Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an enum variant name")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            let name = v.trim();
+            let folded = name.to_lowercase();
+            match crate::serde_introspection::serde_introspect::<T>()
+                .iter()
+                .find(|variant| variant.to_lowercase() == folded)
+            {
+                Some(variant) => T::deserialize(StrDeserializer::new(variant)),
+                None => T::deserialize(name.into_deserializer()),
+            }
+        }
+
+        fn visit_bool<E>(self, v: bool) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer())
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer())
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer())
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(v.into_deserializer())
+        }
+
+        fn visit_unit<E>(self) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            T::deserialize(().into_deserializer())
+        }
+
+        fn visit_seq<A>(self, seq: A) -> Result<T, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            T::deserialize(SeqAccessDeserializer::new(seq))
+        }
+
+        fn visit_map<A>(self, map: A) -> Result<T, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            T::deserialize(MapAccessDeserializer::new(map))
+        }
+    }
+
+    deserializer.deserialize_any(EnumNormalizedVisitor(PhantomData))
+}
+
+/// Gives the integer representation (the discriminant) of a variant of an enum, for
+/// [`serialize_enum_as_repr`]. For a field-less enum it is usually just `*self as i64`.
+pub trait EnumToRepr {
+    /// Returns the discriminant of the variant.
+    fn to_repr(&self) -> i64;
+}
+
+/// Serializes an enum as its integer representation (the discriminant), given by the
+/// [`EnumToRepr`] implementation. This is the counterpart of
+/// [`deserialize_enum_from_repr_or_name`], see also the
+/// [`enum_repr`](crate::with::enum_repr) module doing both.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(Debug, Clone, Copy)]
+/// enum Status {
+///     Inactive = 0,
+///     Active = 2,
+/// }
+///
+/// impl EnumToRepr for Status {
+///     fn to_repr(&self) -> i64 {
+///         *self as i64
+///     }
+/// }
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_enum_as_repr")]
+///     status: Status,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { status: Status::Active };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"status":2}"#);
+/// }
+/// ```
+pub fn serialize_enum_as_repr<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: EnumToRepr,
+    S: Serializer,
+{
+    serializer.serialize_i64(value.to_repr())
+}
+
+/// Deserializes a map dropping the entries whose values are `null`. The values of a wrong type
+/// still fail the deserialization, only the explicit `null`s are dropped. The map type is taken
+/// from the field: a `HashMap`, a `BTreeMap`, an `IndexMap` (which keeps the order of the entries
+/// in the document) or anything else implementing `FromIterator<(K, V)>`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_map_drop_nulls")]
+///     metadata: HashMap<String, String>,
+///     #[serde(default, deserialize_with = "deserialize_map_drop_nulls")]
+///     sorted: BTreeMap<String, u8>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "metadata": { "a": "x", "b": null, "c": "y" } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.metadata.len(), 2);
+///     assert_eq!(a.metadata["a"], "x");
+///     assert_eq!(a.metadata["c"], "y");
+///
+///     let s = r#" { "metadata": {}, "sorted": { "b": 2, "a": null, "c": 3 } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.sorted.into_iter().collect::<Vec<_>>(), vec![("b".to_owned(), 2), ("c".to_owned(), 3)]);
+///
+///     let s = r#" { "metadata": { "a": 1 } } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_map_drop_nulls<'de, D, K, V, M>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+    M: FromIterator<(K, V)>,
+{
+    struct MapDropNullsVisitor<K, V, M>(PhantomData<(K, V, M)>);
+
+    impl<'de, K, V, M> Visitor<'de> for MapDropNullsVisitor<K, V, M>
+    where
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        M: FromIterator<(K, V)>,
+    {
+        type Value = M;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            core::iter::from_fn(|| map.next_entry::<K, Option<V>>().transpose())
+                .filter_map(|entry| match entry {
+                    Ok((key, Some(value))) => Some(Ok((key, value))),
+                    Ok((_, None)) => None,
+                    Err(e) => Some(Err(e)),
+                })
+                .collect()
+        }
+    }
+
+    deserializer.deserialize_map(MapDropNullsVisitor(PhantomData))
+}
+
+/// Serializes a map with its entries sorted by the key, so a `HashMap`, which iterates in a
+/// random order, gives the same output on every run, like for the documents which are diffed or
+/// signed. Works for any map whose reference iterates over `(&K, &V)`, like `HashMap` or
+/// `IndexMap`.
+///
+/// The entries are collected and sorted on every serialization, which costs O(n log n) and an
+/// allocation. A `BTreeMap` is sorted already, so prefer it (or this helper only on the fields
+/// whose order matters) to applying this everywhere.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::HashMap;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_map_sorted")]
+///     headers: HashMap<String, u32>,
+/// }
+///
+/// fn main() {
+///     let headers = (0..10).map(|i| (format!("h{}", 9 - i), i)).collect();
+///     let a = MyStruct { headers };
+///     let json = serde_json::to_string(&a).unwrap();
+///     assert!(json.starts_with(r#"{"headers":{"h0":9,"h1":8,"h2":7,"#));
+///     assert!(json.ends_with(r#""h9":0}}"#));
+/// }
+/// ```
+pub fn serialize_map_sorted<'a, M, K, V, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Ord + Serialize + 'a,
+    V: Serialize + 'a,
+    S: Serializer,
+{
+    let mut entries: Vec<(&K, &V)> = map.into_iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    serializer.collect_map(entries)
+}
+
+/// Deserializes a map with the string keys lowercased, so `Content-Type` and `content-type` end
+/// up as the same `content-type` entry. When several keys collide this way the last one wins,
+/// use [`LowercaseKeysMap`] to make it an error instead. The map type is taken from the field:
+/// a `HashMap<String, V>`, a `BTreeMap<String, V>` (which keeps the lowercased keys sorted for a
+/// deterministic output) or anything else implementing `FromIterator<(String, V)>`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_map_lowercase_keys")]
+///     headers: HashMap<String, String>,
+///     #[serde(default, deserialize_with = "deserialize_map_lowercase_keys")]
+///     sorted: BTreeMap<String, u8>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "headers": { "Content-Type": "text/plain", "content-type": "text/html" } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.headers.len(), 1);
+///     assert_eq!(a.headers["content-type"], "text/html");
+///
+///     let s = r#" { "headers": {}, "sorted": { "B": 2, "a": 1, "C": 3 } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.sorted.keys().collect::<Vec<_>>(), vec!["a", "b", "c"]);
+/// }
+/// ```
+pub fn deserialize_map_lowercase_keys<'de, D, V, M>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+    M: FromIterator<(String, V)>,
+{
+    LowercaseKeysMap::new().into_deserializer()(deserializer)
+}
+
+/// Builds a deserializer of a map with the string keys lowercased, see
+/// [`deserialize_map_lowercase_keys`]. By default the last of the colliding keys wins.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::HashMap;
+///
+/// fn strict_headers<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     LowercaseKeysMap::new()
+///         .error_on_collision(true)
+///         .into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "strict_headers")]
+///     headers: HashMap<String, String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "headers": { "Accept": "*/*", "Host": "example.com" } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.headers["accept"], "*/*");
+///     assert_eq!(a.headers["host"], "example.com");
+///
+///     let s = r#" { "headers": { "Content-Type": "text/plain", "content-type": "text/html" } } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e
+///         .to_string()
+///         .starts_with(r#"keys "Content-Type" and "content-type" are the same when lowercased"#));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LowercaseKeysMap {
+    error_on_collision: bool,
+}
+
+impl LowercaseKeysMap {
+    /// Creates a builder letting the last of the colliding keys win.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the keys which are the same when lowercased are an error.
+    pub fn error_on_collision(mut self, error_on_collision: bool) -> Self {
+        self.error_on_collision = error_on_collision;
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, V, M, D>(self) -> impl FnMut(D) -> Result<M, D::Error>
+    where
+        D: Deserializer<'de>,
+        V: Deserialize<'de>,
+        M: FromIterator<(String, V)>,
+    {
+        move |deserializer| {
+            deserializer.deserialize_map(LowercaseKeysMapVisitor {
+                options: self,
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+struct LowercaseKeysMapVisitor<V, M> {
+    options: LowercaseKeysMap,
+    marker: PhantomData<(V, M)>,
+}
+
+impl<'de, V, M> Visitor<'de> for LowercaseKeysMapVisitor<V, M>
+where
+    V: Deserialize<'de>,
+    M: FromIterator<(String, V)>,
+{
+    type Value = M;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map with the string keys")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        let mut values = BTreeMap::<String, (String, V)>::new();
+        while let Some((key, value)) = map.next_entry::<String, V>()? {
+            match values.entry(key.to_lowercase()) {
+                Entry::Occupied(entry) if self.options.error_on_collision => {
+                    return Err(serde::de::Error::custom(format!(
+                        "keys {:?} and {:?} are the same when lowercased",
+                        entry.get().0,
+                        key
+                    )))
+                }
+                Entry::Occupied(mut entry) => {
+                    entry.insert((key, value));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert((key, value));
+                }
+            }
+        }
+        Ok(values
+            .into_iter()
+            .map(|(key, (_, value))| (key, value))
+            .collect())
+    }
+}
+
+/// Deserializes a `Vec<T>` from a sequence and fails if any element occurs more than once, naming
+/// the first duplicated element and its index. Useful for the allow-lists where a duplicate is
+/// most likely a typo.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_unique_vec")]
+///     allowed: Vec<String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "allowed": ["a", "b"] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.allowed, vec!["a", "b"]);
+///
+///     let s = r#" { "allowed": ["a", "b", "a"] } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"duplicate element "a" at index 2"#));
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn deserialize_unique_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Eq + core::hash::Hash + fmt::Debug,
+{
+    let values = Vec::<T>::deserialize(deserializer)?;
+    check_unique(&values)?;
+    Ok(values)
+}
+
+/// Deserializes a `HashSet<T>` either from a sequence or from a string with the comma separated
+/// values, like [`deserialize_set_from_string_or_seq`], but fails on the duplicates instead of
+/// collapsing them, naming the first duplicated element and its index.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::HashSet;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_unique_set_from_string_or_seq")]
+///     ports: HashSet<u16>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "ports": "80,443" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.ports.len(), 2);
+///
+///     let s = r#" { "ports": "80,443,80" } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with("duplicate element 80 at index 2"));
+///
+///     let s = r#" { "ports": [443, 443] } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn deserialize_unique_set_from_string_or_seq<'de, T, D>(
+    deserializer: D,
+) -> Result<std::collections::HashSet<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de> + Eq + core::hash::Hash + fmt::Debug,
+    <T as FromStr>::Err: Display,
+{
+    let values: Vec<T> = StringOrVecToVec::new().into_deserializer()(deserializer)?;
+    check_unique(&values)?;
+    Ok(values.into_iter().collect())
+}
+
+#[cfg(feature = "std")]
+fn check_unique<T, E>(values: &[T]) -> Result<(), E>
+where
+    T: Eq + core::hash::Hash + fmt::Debug,
+    E: serde::de::Error,
+{
+    let mut seen = std::collections::HashSet::with_capacity(values.len());
+    for (index, value) in values.iter().enumerate() {
+        if !seen.insert(value) {
+            return Err(E::custom(format!(
+                "duplicate element {:?} at index {}",
+                value, index
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Deserializes a string applying the Unicode normalization form C (canonical composition) to it,
+/// so the precomposed and the decomposed forms of the same text compare equal. The numbers are
+/// converted to strings, like in [`deserialize_string_from_number`]. Requires the `unicode`
+/// feature.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_nfc_string")]
+///     name: String,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "name": "Jose\u0301" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.name, "Jos\u{e9}");
+///
+///     let s = r#" { "name": 42 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.name, "42");
+/// }
+/// ```
+#[cfg(feature = "unicode")]
+pub fn deserialize_nfc_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use unicode_normalization::UnicodeNormalization;
+
+    deserialize_string_from_number(deserializer).map(|s| s.nfc().collect())
+}
+
+/// Deserializes a string applying the Unicode normalization form KC (compatibility composition)
+/// to it, which also folds the compatibility characters like the ligatures or the full-width
+/// forms. Otherwise the same as [`deserialize_nfc_string`]. Requires the `unicode` feature.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_nfkc_string")]
+///     name: String,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "name": "\ufb01le \uff21" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.name, "file A");
+/// }
+/// ```
+#[cfg(feature = "unicode")]
+pub fn deserialize_nfkc_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use unicode_normalization::UnicodeNormalization;
+
+    deserialize_string_from_number(deserializer).map(|s| s.nfkc().collect())
+}
+
+/// Deserializes a string, accepting the byte strings as well and replacing the invalid UTF-8
+/// sequences in them with `U+FFFD REPLACEMENT CHARACTER`, as `String::from_utf8_lossy` does.
+/// Useful with the formats having a separate byte string type, like MessagePack or CBOR, when
+/// the producer is not careful about the encoding.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use serde::de::value::{BytesDeserializer, Error};
+///
+/// let deserializer = BytesDeserializer::<Error>::new(b"caf\xe9");
+/// assert_eq!(deserialize_string_lossy(deserializer).unwrap(), "caf\u{fffd}");
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_string_lossy")]
+///     name: String,
+/// }
+///
+/// let a: MyStruct = serde_json::from_str(r#" { "name": "café" } "#).unwrap();
+/// assert_eq!(a.name, "café");
+/// ```
+pub fn deserialize_string_lossy<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct StringLossyVisitor;
+
+    impl<'de> Visitor<'de> for StringLossyVisitor {
+        type Value = String;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a string or a byte string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<String, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v.to_owned())
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<String, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v)
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<String, E>
+        where
+            E: serde::de::Error,
+        {
+            match core::str::from_utf8(v) {
+                Ok(s) => Ok(s.to_owned()),
+                Err(_) => Ok(lossy(v)),
+            }
+        }
+
+        fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<String, E>
+        where
+            E: serde::de::Error,
+        {
+            String::from_utf8(v).or_else(|e| Ok(lossy(e.as_bytes())))
+        }
+    }
+
+    fn lossy(bytes: &[u8]) -> String {
+        crate::lenient::report(
+            "deserialize_string_lossy",
+            crate::lenient::LenientAction::Coerced,
+            || crate::lenient::escape_bytes(bytes),
+        );
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+
+    deserializer.deserialize_any(StringLossyVisitor)
+}
+
+/// Deserializes an `OsString` from a string or, for the paths which are not valid UTF-8, from a
+/// byte string or a sequence of bytes (as `Vec<u8>` is serialized by default).
+///
+/// # Platform differences
+///
+/// - On Unix the bytes are taken as they are, via `std::os::unix::ffi::OsStrExt::from_bytes`,
+///   so any byte sequence is accepted.
+/// - On the other platforms (Windows included) the bytes must be valid UTF-8, otherwise an error
+///   is returned: there is no lossless way to build an `OsString` from arbitrary bytes there and
+///   a lossy conversion would silently corrupt the path.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::ffi::OsString;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_os_string_flexible")]
+///     name: OsString,
+/// }
+///
+/// let a: MyStruct = serde_json::from_str(r#" { "name": "file" } "#).unwrap();
+/// assert_eq!(a.name, "file");
+/// let a: MyStruct = serde_json::from_str(r#" { "name": [102, 105, 108, 101] } "#).unwrap();
+/// assert_eq!(a.name, "file");
+///
+/// // Not valid UTF-8: accepted on Unix only.
+/// let result = serde_json::from_str::<MyStruct>(r#" { "name": [102, 255] } "#);
+/// #[cfg(unix)]
+/// {
+///     use std::os::unix::ffi::OsStrExt;
+///     assert_eq!(result.unwrap().name.as_bytes(), b"f\xff");
+/// }
+/// #[cfg(not(unix))]
+/// assert!(result.is_err());
+/// ```
+#[cfg(feature = "std")]
+pub fn deserialize_os_string_flexible<'de, D>(deserializer: D) -> Result<OsString, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct OsStringVisitor;
+
+    impl<'de> Visitor<'de> for OsStringVisitor {
+        type Value = OsString;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a string, a byte string or a sequence of bytes")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<OsString, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v.into())
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<OsString, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v.into())
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<OsString, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visit_byte_buf(v.to_vec())
+        }
+
+        #[cfg(unix)]
+        fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<OsString, E>
+        where
+            E: serde::de::Error,
+        {
+            use std::os::unix::ffi::OsStringExt;
+
+            Ok(OsString::from_vec(v))
+        }
+
+        #[cfg(not(unix))]
+        fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<OsString, E>
+        where
+            E: serde::de::Error,
+        {
+            String::from_utf8(v).map(Into::into).map_err(|e| {
+                E::invalid_value(
+                    serde::de::Unexpected::Bytes(e.as_bytes()),
+                    &"a valid UTF-8 byte string on this platform",
+                )
+            })
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<OsString, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(byte) = seq.next_element::<u8>()? {
+                bytes.push(byte);
+            }
+            self.visit_byte_buf(bytes)
+        }
+    }
+
+    deserializer.deserialize_any(OsStringVisitor)
+}
+
+/// Deserializes a `Vec<u8>` from a sequence of integers like `[1, 255, 0]`, each of which has to
+/// be in `0..=255` (the error names the index of the first one which is not), from a string (as
+/// its UTF-8 bytes) or from a native byte string of the formats having them, like MessagePack or
+/// CBOR.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_byte_vec_flexible")]
+///     payload: Vec<u8>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "payload": [1, 255, 0] } "#).unwrap();
+///     assert_eq!(a.payload, vec![1, 255, 0]);
+///     let a: MyStruct = serde_json::from_str(r#" { "payload": "hi" } "#).unwrap();
+///     assert_eq!(a.payload, b"hi".to_vec());
+///
+///     let s = r#" { "payload": [1, 256, -1] } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with("invalid byte 256 at index 1, expected 0..=255"));
+/// }
+/// ```
+pub fn deserialize_byte_vec_flexible<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct ByteVecVisitor;
+
+    impl<'de> Visitor<'de> for ByteVecVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a sequence of bytes, a string or a byte string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Vec<u8>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v.as_bytes().to_vec())
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Vec<u8>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v.into_bytes())
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Vec<u8>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Vec<u8>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Vec<u8>, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            use serde::de::Error;
+
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(byte) = seq
+                .next_element_seed(ByteElement)
+                .map_err(|e| A::Error::custom(format!("at index {}: {}", bytes.len(), e)))?
+            {
+                if !(0..=255).contains(&byte) {
+                    return Err(A::Error::custom(format!(
+                        "invalid byte {} at index {}, expected 0..=255",
+                        byte,
+                        bytes.len()
+                    )));
+                }
+                bytes.push(byte as u8);
+            }
+            Ok(bytes)
+        }
+    }
+
+    /// An element of a sequence of bytes, taken as any integer to report the out-of-range ones.
+    struct ByteElement;
+
+    impl<'de> serde::de::DeserializeSeed<'de> for ByteElement {
+        type Value = i128;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<i128, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for ByteElement {
+        type Value = i128;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an integer")
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<i128, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v.into())
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<i128, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v.into())
+        }
+    }
+
+    deserializer.deserialize_any(ByteVecVisitor)
+}
+
+/// Deserializes a `PathBuf` the same way as [`deserialize_os_string_flexible`] does, see the
+/// platform differences there.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::path::PathBuf;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_path_buf_flexible")]
+///     path: PathBuf,
+/// }
+///
+/// let a: MyStruct = serde_json::from_str(r#" { "path": "/etc/hosts" } "#).unwrap();
+/// assert_eq!(a.path, PathBuf::from("/etc/hosts"));
+/// let a: MyStruct = serde_json::from_str(r#" { "path": [47, 116, 109, 112] } "#).unwrap();
+/// assert_eq!(a.path, PathBuf::from("/tmp"));
+/// ```
+#[cfg(feature = "std")]
+pub fn deserialize_path_buf_flexible<'de, D>(deserializer: D) -> Result<PathBuf, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_os_string_flexible(deserializer).map(PathBuf::from)
+}
+
+/// Deserializes a size in bytes from a string like `"512MB"`, `"1.5GiB"` or `"100"`, or from a
+/// number of bytes.
+///
+/// - The decimal (`KB`, `MB`, `GB`, `TB`, `PB`, `EB`) and the binary (`KiB`, `MiB`, `GiB`,
+///   `TiB`, `PiB`, `EiB`) units are supported, case-insensitively; `B` or no unit means bytes.
+///   Whitespace is allowed between the number and the unit.
+/// - A fractional size is rounded to the nearest byte, the halves are rounded up: `"1.5B"` is
+///   `2`. The rounding is exact, the number is not converted to a float on the way.
+/// - The sizes past `u64::MAX` bytes and the negative sizes are errors.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_bytes_size_from_string")]
+///     cache_size: u64,
+/// }
+///
+/// fn main() {
+///     for &(s, bytes) in &[
+///         (r#""512MB""#, 512_000_000),
+///         (r#""1.5GiB""#, 1_610_612_736),
+///         (r#""10 kb""#, 10_000),
+///         (r#""0.5B""#, 1),
+///         (r#""100""#, 100),
+///         ("100", 100),
+///     ] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "cache_size": {} }}"#, s)).unwrap();
+///         assert_eq!(a.cache_size, bytes);
+///     }
+///
+///     for s in &[r#""16EiB""#, r#""5XB""#, r#""-1KB""#, "-1"] {
+///         let json = format!(r#"{{ "cache_size": {} }}"#, s);
+///         assert!(serde_json::from_str::<MyStruct>(&json).is_err());
+///     }
+/// }
+/// ```
+pub fn deserialize_bytes_size_from_string<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct BytesSizeVisitor;
+
+    impl<'de> Visitor<'de> for BytesSizeVisitor {
+        type Value = u64;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number of bytes or a string with a size like \"512MB\"")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<u64, E>
+        where
+            E: serde::de::Error,
+        {
+            parse_bytes_size(v).map_err(|e| {
+                E::custom(format_args!(
+                    "invalid value: string {:?}, expected {}: {}",
+                    v,
+                    Expecting(&self),
+                    e
+                ))
+            })
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<u64, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(v)
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<u64, E>
+        where
+            E: serde::de::Error,
+        {
+            if v < 0 {
+                return Err(E::invalid_value(serde::de::Unexpected::Signed(v), &self));
+            }
+            Ok(v as u64)
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<u64, E>
+        where
+            E: serde::de::Error,
+        {
+            if !v.is_finite() || v < 0.0 {
+                return Err(E::invalid_value(serde::de::Unexpected::Float(v), &self));
+            }
+            self.visit_str(&v.to_string())
+        }
+    }
+
+    deserializer.deserialize_any(BytesSizeVisitor)
+}
+
+fn parse_bytes_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let unit_start = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (number, unit) = (&s[..unit_start], s[unit_start..].trim_start());
+    let multiplier: u128 = match unit.to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        "pb" => 1_000_000_000_000_000,
+        "eb" => 1_000_000_000_000_000_000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        "pib" => 1 << 50,
+        "eib" => 1 << 60,
+        _ => return Err(format!("unknown unit {:?}", unit)),
+    };
+
+    let mut parts = number.splitn(2, '.');
+    let whole = parts.next().unwrap_or_default();
+    let fraction = parts.next().unwrap_or_default();
+    if (whole.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+        return Err(format!("invalid number {:?}", number));
+    }
+    if fraction.len() > 18 {
+        return Err("too many fractional digits".to_owned());
+    }
+
+    let overflow = || "the size does not fit into u64".to_owned();
+    let whole: u128 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| overflow())?
+    };
+    let (fraction, denominator): (u128, u128) = if fraction.is_empty() {
+        (0, 1)
+    } else {
+        let denominator = 10u128.pow(fraction.len() as u32);
+        (fraction.parse().map_err(|_| overflow())?, denominator)
+    };
+    // At most 10^18 * 2^60, which fits into u128 easily.
+    let scaled = fraction * multiplier;
+    let mut rounded = scaled / denominator;
+    if (scaled % denominator) * 2 >= denominator {
+        rounded += 1;
+    }
+    let bytes = whole
+        .checked_mul(multiplier)
+        .and_then(|bytes| bytes.checked_add(rounded))
+        .ok_or_else(overflow)?;
+    if bytes > u128::from(u64::MAX) {
+        return Err(overflow());
+    }
+    Ok(bytes as u64)
+}
+
+/// Deserializes an `f64` from a fraction like `"3/4"`, a mixed number like `"1 1/2"` or
+/// `"-2 1/4"`, or from a plain number or numeric string like `"0.75"`. The whitespace around the
+/// slash is allowed. A zero denominator is an error naming the original string. See
+/// [`deserialize_fraction_from_string`] for the numerator and the denominator themselves.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_ratio_from_string")]
+///     cups: f64,
+/// }
+///
+/// fn main() {
+///     for &(s, cups) in &[
+///         (r#""3/4""#, 0.75),
+///         (r#""3 / 4""#, 0.75),
+///         (r#""1 1/2""#, 1.5),
+///         (r#""-2 1/4""#, -2.25),
+///         (r#""0.5""#, 0.5),
+///         ("2", 2.0),
+///     ] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "cups": {} }}"#, s)).unwrap();
+///         assert_eq!(a.cups, cups);
+///     }
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "cups": "1/0" } "#).unwrap_err();
+///     assert!(e.to_string().contains(r#"division by zero in "1/0""#));
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "cups": "1/2/3" } "#).is_err());
+/// }
+/// ```
+pub fn deserialize_ratio_from_string<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match deserializer.deserialize_any(RatioVisitor)? {
+        Ratio::Fraction(numerator, denominator) => Ok(numerator as f64 / denominator as f64),
+        Ratio::Number(number) => Ok(number),
+    }
+}
+
+/// Deserializes the numerator and the denominator of a fraction like `"3/4"` or `"1 1/2"`
+/// (giving `(3, 2)`) from a string, or of an integer from a number or a numeric string (giving
+/// the denominator of `1`). The fraction is not reduced, so `"2/4"` is `(2, 4)`; the sign is kept
+/// in the numerator. Unlike [`deserialize_ratio_from_string`] the decimal numbers like `"0.5"`
+/// are errors.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_fraction_from_string")]
+///     aspect: (i64, i64),
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "aspect": "16 / 9" } "#).unwrap();
+///     assert_eq!(a.aspect, (16, 9));
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "aspect": "-1 1/2" } "#).unwrap();
+///     assert_eq!(a.aspect, (-3, 2));
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "aspect": 2 } "#).unwrap();
+///     assert_eq!(a.aspect, (2, 1));
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "aspect": "0.5" } "#).is_err());
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "aspect": "4/0" } "#).is_err());
+/// }
+/// ```
+pub fn deserialize_fraction_from_string<'de, D>(deserializer: D) -> Result<(i64, i64), D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match deserializer.deserialize_any(RatioVisitor)? {
+        Ratio::Fraction(numerator, denominator) => Ok((numerator, denominator)),
+        Ratio::Number(number) => Err(serde::de::Error::invalid_value(
+            serde::de::Unexpected::Float(number),
+            &"a fraction or an integer",
+        )),
+    }
+}
+
+/// A value read by [`RatioVisitor`]: the fractions and the integers are kept exact, the other
+/// numbers are floats.
+enum Ratio {
+    Fraction(i64, i64),
+    Number(f64),
+}
+
+struct RatioVisitor;
+
+impl<'de> Visitor<'de> for RatioVisitor {
+    type Value = Ratio;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or a string with a fraction like \"3/4\" or \"1 1/2\"")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Ratio, E>
+    where
+        E: serde::de::Error,
+    {
+        parse_ratio(v).map_err(|e| {
+            E::custom(format_args!(
+                "invalid value: string {:?}, expected {}: {}",
+                v,
+                Expecting(&self),
+                e
+            ))
+        })
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Ratio, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Ratio::Fraction(v, 1))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Ratio, E>
+    where
+        E: serde::de::Error,
+    {
+        if v > i64::MAX as u64 {
+            return Ok(Ratio::Number(v as f64));
+        }
+        Ok(Ratio::Fraction(v as i64, 1))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Ratio, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Ratio::Number(v))
+    }
+}
+
+fn parse_ratio(s: &str) -> Result<Ratio, String> {
+    let s = s.trim();
+    let mut parts = s.splitn(2, '/');
+    let left = parts.next().unwrap_or_default().trim();
+    let denominator = match parts.next() {
+        Some(denominator) => denominator.trim(),
+        None => {
+            return match s.parse::<i64>() {
+                Ok(number) => Ok(Ratio::Fraction(number, 1)),
+                Err(_) => s
+                    .parse::<f64>()
+                    .map(Ratio::Number)
+                    .map_err(|_| format!("invalid number {:?}", s)),
+            };
+        }
+    };
+    let integer = |part: &str| {
+        part.parse::<i64>()
+            .map_err(|_| format!("invalid integer {:?}", part))
+    };
+    let denominator = integer(denominator)?;
+    if denominator == 0 {
+        return Err(format!("division by zero in {:?}", s));
+    }
+    if denominator < 0 {
+        return Err(format!("negative denominator in {:?}", s));
+    }
+    let mut words = left.split_whitespace();
+    let (whole, numerator) = match (words.next(), words.next(), words.next()) {
+        (Some(numerator), None, None) => (None, integer(numerator)?),
+        (Some(whole), Some(numerator), None) => (Some(integer(whole)?), integer(numerator)?),
+        _ => return Err(format!("invalid fraction {:?}", s)),
+    };
+    let numerator = match whole {
+        None => numerator,
+        Some(_) if numerator < 0 => return Err(format!("invalid mixed number {:?}", s)),
+        Some(whole) => {
+            let overflow = || format!("the fraction {:?} does not fit into i64", s);
+            let scaled = whole.checked_mul(denominator).ok_or_else(overflow)?;
+            if whole < 0 || left.starts_with('-') {
+                scaled.checked_sub(numerator).ok_or_else(overflow)?
+            } else {
+                scaled.checked_add(numerator).ok_or_else(overflow)?
+            }
+        }
+    };
+    Ok(Ratio::Fraction(numerator, denominator))
+}
+
+/// Deserializes a geographic point as a `(latitude, longitude)` pair of `f64`s from any of the
+/// shapes the location providers use:
+///
+/// - An object with the `lat` and `lng` (or `lon`) members, or with the `latitude` and
+///   `longitude` ones. The other members are ignored.
+/// - An array of two numbers in the GeoJSON order, that is `[longitude, latitude]`.
+/// - A string with the comma separated latitude and longitude, like `"52.52, 13.40"`.
+///
+/// The coordinates may be numbers or numeric strings. A latitude outside of `-90..=90` or a
+/// longitude outside of `-180..=180` is an error.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_lat_lng_flexible")]
+///     location: (f64, f64),
+/// }
+///
+/// fn main() {
+///     for s in &[
+///         r#" { "location": { "lat": 52.52, "lng": 13.4 } } "#,
+///         r#" { "location": { "latitude": "52.52", "longitude": 13.4, "accuracy": 5 } } "#,
+///         r#" { "location": [13.4, 52.52] } "#,
+///         r#" { "location": "52.52, 13.4" } "#,
+///     ] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.location, (52.52, 13.4));
+///     }
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "location": [52.52, 113.4] } "#).unwrap_err();
+///     assert!(e.to_string().starts_with("latitude 113.4 is out of range"));
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "location": { "lat": 1 } } "#).unwrap_err();
+///     assert!(e.to_string().starts_with("missing field `lng`"));
+/// }
+/// ```
+pub fn deserialize_lat_lng_flexible<'de, D>(deserializer: D) -> Result<(f64, f64), D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct Coordinate;
+
+    impl<'de> serde::de::DeserializeSeed<'de> for Coordinate {
+        type Value = f64;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<f64, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserialize_number_from_string(deserializer)
+        }
+    }
+
+    struct LatLngVisitor;
+
+    impl<'de> Visitor<'de> for LatLngVisitor {
+        type Value = (f64, f64);
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str(
+                "an object with the latitude and the longitude, a [longitude, latitude] array \
+                 or a \"latitude,longitude\" string",
+            )
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<(f64, f64), E>
+        where
+            E: serde::de::Error,
+        {
+            let mut parts = v.split(',').map(str::trim);
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(lat), Some(lng), None) => match (lat.parse(), lng.parse()) {
+                    (Ok(lat), Ok(lng)) => check_lat_lng(lat, lng),
+                    _ => Err(E::invalid_value(serde::de::Unexpected::Str(v), &self)),
+                },
+                _ => Err(E::invalid_value(serde::de::Unexpected::Str(v), &self)),
+            }
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<(f64, f64), A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            use serde::de::Error;
+
+            let lng = seq
+                .next_element_seed(Coordinate)?
+                .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+            let lat = seq
+                .next_element_seed(Coordinate)?
+                .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+            if seq.next_element::<serde::de::IgnoredAny>()?.is_some() {
+                return Err(A::Error::invalid_length(3, &self));
+            }
+            check_lat_lng(lat, lng)
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<(f64, f64), A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            use serde::de::Error;
+
+            let (mut lat, mut lng) = (None, None);
+            while let Some(key) = map.next_key::<String>()? {
+                let slot = match key.as_str() {
+                    "lat" | "latitude" => &mut lat,
+                    "lng" | "lon" | "longitude" => &mut lng,
+                    _ => {
+                        map.next_value::<serde::de::IgnoredAny>()?;
+                        continue;
+                    }
+                };
+                if slot.is_some() {
+                    return Err(A::Error::custom(format_args!(
+                        "duplicate coordinate {:?}",
+                        key
+                    )));
+                }
+                *slot = Some(map.next_value_seed(Coordinate)?);
+            }
+            let lat = lat.ok_or_else(|| A::Error::missing_field("lat"))?;
+            let lng = lng.ok_or_else(|| A::Error::missing_field("lng"))?;
+            check_lat_lng(lat, lng)
+        }
+    }
+
+    fn check_lat_lng<E: serde::de::Error>(lat: f64, lng: f64) -> Result<(f64, f64), E> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(E::custom(format_args!(
+                "latitude {} is out of range -90..=90",
+                lat
+            )));
+        }
+        if !(-180.0..=180.0).contains(&lng) {
+            return Err(E::custom(format_args!(
+                "longitude {} is out of range -180..=180",
+                lng
+            )));
+        }
+        Ok((lat, lng))
+    }
+
+    deserializer.deserialize_any(LatLngVisitor)
+}
+
+/// Deserializes a number from a string with an SI-like suffix, like `"1.5k"` or `"2.3M"`, or
+/// from a plain number or numeric string. The suffixes are `k`/`K` (thousands), `M` (millions)
+/// and `B`/`G` (billions).
+///
+/// The scaling is done on the decimal digits, so `"1.55k"` is exactly `1550`. For the integer
+/// targets the scaled value has to be integral and in the range of the type: `"1.5555k"`
+/// (`1555.5`) is an error for a `u64` but fine for an `f64`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_number_from_si_string")]
+///     followers: u64,
+///     #[serde(deserialize_with = "deserialize_number_from_si_string")]
+///     score: f64,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "followers": "1.55k", "score": "1.5555k" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.followers, 1550);
+///     assert_eq!(a.score, 1555.5);
+///
+///     let s = r#" { "followers": "2M", "score": 0.5 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.followers, 2_000_000);
+///     assert_eq!(a.score, 0.5);
+///
+///     let s = r#" { "followers": "1.5555k", "score": 1 } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+///
+///     let s = r#" { "followers": "-1k", "score": 1 } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_number_from_si_string<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    deserializer.deserialize_any(NumberFromSiStringVisitor(PhantomData))
+}
+
+struct NumberFromSiStringVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for NumberFromSiStringVisitor<T>
+where
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or a numeric string with an optional k, M, B or G suffix")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        let s = v.trim();
+        let exponent = match s.chars().last() {
+            Some('k') | Some('K') => 3,
+            Some('M') => 6,
+            Some('B') | Some('G') => 9,
+            _ => return NumberFromStringVisitor(PhantomData).visit_str(v),
+        };
+        let number = s[..s.len() - 1].trim_end();
+        let scaled = shift_decimal_point(number, exponent)
+            .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), &self))?;
+        scaled.parse::<T>().map_err(|e| {
+            E::custom(format_args!(
+                "invalid value: string {:?} ({}), expected {}: {}",
+                v,
+                scaled,
+                Expecting(&self),
+                describe_number_error::<T>(&scaled, e)
+            ))
+        })
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i64(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u64(v)
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i128(v)
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u128(v)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_f64(v)
+    }
+}
+
+/// Deserializes a number from the beginning of a string, throwing away whatever follows it:
+/// `"1234 items"` is `1234`, `"56ms"` is `56` and `"78%"` is `78`. The plain numbers and numeric
+/// strings are accepted as well.
+///
+/// This is deliberately lossy: the trailing text is not looked at at all, so `"5 thousand"` is
+/// `5` and `"3.5 GB"` is the same as `"3.5 MB"`. Use it only for the scraped data where nothing
+/// better is available, and prefer [`NumberWithUnits`] which at least checks the units.
+///
+/// The longest prefix looking like a number (a sign, the digits, a fraction and an exponent) is
+/// parsed, after trimming the whitespace. A string not starting with a number is an error.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_number_from_prefix_of_string")]
+///     count: u32,
+///     #[serde(deserialize_with = "deserialize_number_from_prefix_of_string")]
+///     ratio: f64,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "count": " 1234 items", "ratio": "78.5%" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.count, 1234);
+///     assert_eq!(a.ratio, 78.5);
+///
+///     let s = r#" { "count": 5, "ratio": "-1e-3 units" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.count, 5);
+///     assert_eq!(a.ratio, -0.001);
+///
+///     let s = r#" { "count": "about 5", "ratio": 1 } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"no number at the beginning of "about 5""#));
+/// }
+/// ```
+pub fn deserialize_number_from_prefix_of_string<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    deserializer.deserialize_any(NumberFromPrefixVisitor {
+        units: None,
+        marker: PhantomData,
+    })
+}
+
+/// Builds a deserializer of a number followed by one of the known units, like `"56 ms"` or
+/// `"78%"`, throwing the unit away. The strict sibling of
+/// [`deserialize_number_from_prefix_of_string`]: after the number only the whitespace and one
+/// of the units (compared ignoring the ASCII case) may follow, anything else is an error. A
+/// number without a unit, as well as a plain number, is accepted.
+///
+/// Note that the unit is checked but not applied, `"5 s"` and `"5 ms"` are both `5` when both
+/// units are allowed.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn latency<'de, D>(deserializer: D) -> Result<u64, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     NumberWithUnits::new(&["ms", "msec"]).into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "latency")]
+///     latency: u64,
+/// }
+///
+/// fn main() {
+///     for s in &[r#""56 ms""#, r#""56MS""#, r#""56msec ""#, r#""56""#, "56"] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "latency": {} }}"#, s)).unwrap();
+///         assert_eq!(a.latency, 56);
+///     }
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "latency": "56 s" } "#).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"unknown unit "s" in "56 s", expected one of ["ms", "msec"]"#));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct NumberWithUnits<'a> {
+    units: &'a [&'a str],
+}
+
+impl<'a> NumberWithUnits<'a> {
+    /// Creates a builder allowing the units from the list after the number.
+    pub fn new(units: &'a [&'a str]) -> Self {
+        NumberWithUnits { units }
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, T, D>(self) -> impl FnMut(D) -> Result<T, D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        T: FromStr + Deserialize<'de>,
+        <T as FromStr>::Err: Display,
+    {
+        move |deserializer| {
+            deserializer.deserialize_any(NumberFromPrefixVisitor {
+                units: Some(self.units),
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+/// Reads the number at the beginning of a string, checking the rest against the `units` if they
+/// are given.
+struct NumberFromPrefixVisitor<'a, T> {
+    units: Option<&'a [&'a str]>,
+    marker: PhantomData<T>,
+}
+
+impl<'a, 'de, T> Visitor<'de> for NumberFromPrefixVisitor<'a, T>
+where
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.units {
+            Some(units) => write!(
+                formatter,
+                "a number or a string with a number and one of the units {:?}",
+                units
+            ),
+            None => formatter.write_str("a number or a string starting with a number"),
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        let s = v.trim();
+        let (number, rest) = s.split_at(numeric_prefix_len(s));
+        if number.is_empty() {
+            return Err(E::custom(format_args!(
+                "no number at the beginning of {:?}",
+                v
+            )));
+        }
+        match self.units {
+            Some(units) => {
+                let unit = rest.trim_start();
+                if !unit.is_empty() && !units.iter().any(|u| u.eq_ignore_ascii_case(unit)) {
+                    return Err(E::custom(format_args!(
+                        "unknown unit {:?} in {:?}, expected one of {:?}",
+                        unit, v, units
+                    )));
+                }
+            }
+            None if !rest.is_empty() => crate::lenient::report(
+                "deserialize_number_from_prefix_of_string",
+                crate::lenient::LenientAction::Coerced,
+                || v.to_owned(),
+            ),
+            None => {}
+        }
+        number.parse::<T>().map_err(|e| {
+            E::custom(format_args!(
+                "invalid value: string {:?}, expected {}: {}",
+                v,
+                Expecting(&self),
+                describe_number_error::<T>(number, e)
+            ))
+        })
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i64(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u64(v)
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i128(v)
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u128(v)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_f64(v)
+    }
+}
+
+/// Returns the length of the longest prefix of `s` looking like a decimal number: an optional
+/// sign, the digits with an optional fraction and an optional exponent. The point and the
+/// exponent are taken only when there is a digit after them, so the `e` of `"5em"` stays in the
+/// rest.
+fn numeric_prefix_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let digits_from = |mut i: usize| {
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        i
+    };
+    let mut end = match bytes.first() {
+        Some(b'-') | Some(b'+') => 1,
+        _ => 0,
+    };
+    let start = end;
+    end = digits_from(end);
+    let mut has_digits = end > start;
+    if bytes.get(end) == Some(&b'.') {
+        let fraction_end = digits_from(end + 1);
+        if fraction_end > end + 1 {
+            has_digits = true;
+            end = fraction_end;
+        }
+    }
+    if !has_digits {
+        return 0;
+    }
+    if let Some(b'e') | Some(b'E') = bytes.get(end) {
+        let sign = match bytes.get(end + 1) {
+            Some(b'-') | Some(b'+') => 1,
+            _ => 0,
+        };
+        let exponent_end = digits_from(end + 1 + sign);
+        if exponent_end > end + 1 + sign {
+            end = exponent_end;
+        }
+    }
+    end
+}
+
+/// Builds a deserializer of a measurement converted to a number in the base unit, from either
+/// a string with the unit after the number (`"5km"`, `"5 km"`) or an object with the number and
+/// the unit (`{ "value": 5, "unit": "km" }`, the value can be a numeric string too). Every unit
+/// is registered with [`unit`](Self::unit) and its multiplier to the base unit; the units are
+/// case-sensitive, as `m` and `M` are not the same unit.
+///
+/// - An unknown unit is an error listing the registered ones.
+/// - A missing unit (a plain number, a string without the unit or an object without `unit`) is
+///   an error, unless a default unit is set with [`default_unit`](Self::default_unit).
+///
+/// The number is read as an `f64`, so the digits beyond its precision are rounded, as parsing an
+/// `f64` does, and the result is the rounded product with the multiplier.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn meters<'de, D>(deserializer: D) -> Result<f64, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     UnitNumber::new()
+///         .unit("m", 1.0)
+///         .unit("km", 1000.0)
+///         .unit("cm", 0.01)
+///         .into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "meters")]
+///     distance: f64,
+/// }
+///
+/// fn main() {
+///     for s in &[r#""5km""#, r#""5 km""#, r#"{ "value": 5, "unit": "km" }"#, r#"{ "value": "5", "unit": "km" }"#] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "distance": {} }}"#, s)).unwrap();
+///         assert_eq!(a.distance, 5000.0);
+///     }
+///     let a: MyStruct = serde_json::from_str(r#" { "distance": "250cm" } "#).unwrap();
+///     assert_eq!(a.distance, 2.5);
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "distance": "5mi" } "#).unwrap_err();
+///     assert!(e.to_string().contains(r#"unknown unit "mi" in "5mi", expected one of ["m", "km", "cm"]"#));
+///     let e = serde_json::from_str::<MyStruct>(r#" { "distance": 5 } "#).unwrap_err();
+///     assert!(e.to_string().contains("missing unit"));
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UnitNumber<'a> {
+    units: Vec<(&'a str, f64)>,
+    default_unit: Option<&'a str>,
+}
+
+impl<'a> UnitNumber<'a> {
+    /// Creates a builder without any units.
+    pub fn new() -> Self {
+        UnitNumber {
+            units: Vec::new(),
+            default_unit: None,
+        }
+    }
+
+    /// Registers a unit: a number in it is multiplied by `multiplier` to get the base unit.
+    pub fn unit(mut self, name: &'a str, multiplier: f64) -> Self {
+        self.units.push((name, multiplier));
+        self
+    }
+
+    /// Takes the numbers without a unit in `unit`, which has to be registered too.
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use serde_aux::prelude::*;
+    ///
+    /// fn grams<'de, D>(deserializer: D) -> Result<f64, D::Error>
+    /// where
+    ///     D: serde::Deserializer<'de>,
+    /// {
+    ///     UnitNumber::new()
+    ///         .unit("g", 1.0)
+    ///         .unit("kg", 1000.0)
+    ///         .default_unit("kg")
+    ///         .into_deserializer()(deserializer)
+    /// }
+    ///
+    /// #[derive(serde::Deserialize, Debug)]
+    /// struct MyStruct {
+    ///     #[serde(deserialize_with = "grams")]
+    ///     weight: f64,
+    /// }
+    ///
+    /// fn main() {
+    ///     for s in &["2", r#""2""#, r#"{ "value": 2 }"#, r#""2000 g""#] {
+    ///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "weight": {} }}"#, s)).unwrap();
+    ///         assert_eq!(a.weight, 2000.0);
+    ///     }
+    /// }
+    /// ```
+    pub fn default_unit(mut self, unit: &'a str) -> Self {
+        self.default_unit = Some(unit);
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, D>(self) -> impl FnMut(D) -> Result<f64, D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+    {
+        move |deserializer| deserializer.deserialize_any(UnitNumberVisitor(&self))
+    }
+
+    /// Converts `value` given in `unit` (or the default one) to the base unit, `input` is for the
+    /// errors.
+    fn convert(&self, value: f64, unit: Option<&str>, input: &dyn Display) -> Result<f64, String> {
+        let names = || self.units.iter().map(|&(name, _)| name).collect::<Vec<_>>();
+        let unit = match unit.or(self.default_unit) {
+            Some(unit) => unit,
+            None => {
+                return Err(format!(
+                    "missing unit in {}, expected one of {:?}",
+                    input,
+                    names()
+                ))
+            }
+        };
+        match self.units.iter().find(|&&(name, _)| name == unit) {
+            Some(&(_, multiplier)) => Ok(value * multiplier),
+            None => Err(format!(
+                "unknown unit {:?} in {}, expected one of {:?}",
+                unit,
+                input,
+                names()
+            )),
+        }
+    }
+}
+
+struct UnitNumberVisitor<'b, 'a>(&'b UnitNumber<'a>);
+
+impl<'b, 'a, 'de> Visitor<'de> for UnitNumberVisitor<'b, 'a> {
+    type Value = f64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .write_str("a number with a unit, as a string or an object with `value` and `unit`")
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<f64, E>
+    where
+        E: serde::de::Error,
+    {
+        self.0.convert(v, None, &v).map_err(E::custom)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<f64, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_f64(v as f64)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<f64, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_f64(v as f64)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<f64, E>
+    where
+        E: serde::de::Error,
+    {
+        let v = v.trim();
+        let len = numeric_prefix_len(v);
+        let value = v[..len].parse::<f64>().map_err(|_| {
+            E::custom(format_args!(
+                "invalid value: string {:?}, expected {}",
+                v,
+                Expecting(&self)
+            ))
+        })?;
+        let unit = v[len..].trim_start();
+        let unit = if unit.is_empty() { None } else { Some(unit) };
+        self.0
+            .convert(value, unit, &format_args!("{:?}", v))
+            .map_err(E::custom)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<f64, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        use serde::de::Error;
+
+        const FIELDS: &[&str] = &["value", "unit"];
+
+        let mut value: Option<f64> = None;
+        let mut unit: Option<String> = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "value" if value.is_some() => return Err(A::Error::duplicate_field("value")),
+                "value" => {
+                    value = Some(map.next_value_seed(NumberOrStringSeed)?);
+                }
+                "unit" if unit.is_some() => return Err(A::Error::duplicate_field("unit")),
+                "unit" => unit = Some(map.next_value()?),
+                other => return Err(A::Error::unknown_field(other, FIELDS)),
+            }
+        }
+        let value = value.ok_or_else(|| A::Error::missing_field("value"))?;
+        let input = match unit {
+            Some(ref unit) => format!("{{ value: {}, unit: {:?} }}", value, unit),
+            None => format!("{{ value: {} }}", value),
+        };
+        self.0
+            .convert(value, unit.as_deref(), &input)
+            .map_err(A::Error::custom)
+    }
+}
+
+/// Reads an `f64` from a number or a numeric string.
+struct NumberOrStringSeed;
+
+impl<'de> serde::de::DeserializeSeed<'de> for NumberOrStringSeed {
+    type Value = f64;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<f64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(NumberFromStringVisitor(PhantomData))
+    }
+}
+
+/// Deserializes a number from a string with the underscores as the digit separators, like
+/// `"1_000_000"` or `"3.141_592"`, or from a plain number or numeric string. An underscore is
+/// only allowed between two digits, so the typos like `"1__000"`, `"_100"`, `"100_"` or
+/// `"1_.5"` are errors.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_number_from_underscored_string")]
+///     limit: u64,
+///     #[serde(deserialize_with = "deserialize_number_from_underscored_string")]
+///     ratio: f64,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "limit": "1_000_000", "ratio": "1_000.000_5" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.limit, 1_000_000);
+///     assert_eq!(a.ratio, 1_000.000_5);
+///
+///     let s = r#" { "limit": 10, "ratio": "0.5" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.limit, 10);
+///     assert_eq!(a.ratio, 0.5);
+///
+///     for limit in &["1__000", "_100", "100_"] {
+///         let s = format!(r#" {{ "limit": "{}", "ratio": 1 }} "#, limit);
+///         assert!(serde_json::from_str::<MyStruct>(&s).is_err());
+///     }
+/// }
+/// ```
+pub fn deserialize_number_from_underscored_string<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    deserializer.deserialize_any(NumberFromUnderscoredStringVisitor(PhantomData))
+}
+
+struct NumberFromUnderscoredStringVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for NumberFromUnderscoredStringVisitor<T>
+where
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or a numeric string with the underscores between the digits")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        let number = remove_digit_separators(v)
+            .ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), &self))?;
+        number.parse::<T>().map_err(|e| {
+            E::custom(format_args!(
+                "invalid value: string {:?}, expected {}: {}",
+                v,
+                Expecting(&self),
+                describe_number_error::<T>(&number, e)
+            ))
+        })
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i64(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u64(v)
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i128(v)
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u128(v)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_f64(v)
+    }
+}
+
+/// Removes the underscores separating the digits of a number like `"1_000.5"`. Returns `None` if
+/// an underscore is not between two ASCII digits.
+fn remove_digit_separators(number: &str) -> Option<String> {
+    let bytes = number.as_bytes();
+    let mut result = String::with_capacity(number.len());
+    for (i, c) in number.char_indices() {
+        if c != '_' {
+            result.push(c);
+            continue;
+        }
+        let before = i.checked_sub(1).map(|j| bytes[j]);
+        let after = bytes.get(i + 1).copied();
+        match (before, after) {
+            (Some(b), Some(a)) if b.is_ascii_digit() && a.is_ascii_digit() => {}
+            _ => return None,
+        }
+    }
+    Some(result)
+}
+
+/// Deserializes an integer strictly, the opposite of [`deserialize_number_from_string`]: only a
+/// JSON integer or a string made of the ASCII digits with an optional leading `-` are accepted.
+/// The floats (`3.0` too), the exponents, the whitespace, the signs like `+` and the separators
+/// are rejected, so a producer can't sneak an approximation into a quantity field. The errors say
+/// "float not allowed" for the fractional and exponential numbers, and "not a number" for the
+/// rest.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_integer_strict")]
+///     quantity: i64,
+/// }
+///
+/// fn main() {
+///     for s in &[r#" { "quantity": -42 } "#, r#" { "quantity": "-42" } "#] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.quantity, -42);
+///     }
+///
+///     for s in &[r#" { "quantity": 3.0 } "#, r#" { "quantity": 1e3 } "#, r#" { "quantity": "2.5" } "#] {
+///         let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///         assert!(e.to_string().starts_with("float not allowed"), "{}", e);
+///     }
+///
+///     for s in &[r#" { "quantity": "1,000" } "#, r#" { "quantity": " 1" } "#, r#" { "quantity": "+1" } "#] {
+///         let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///         assert!(e.to_string().starts_with("not a number"), "{}", e);
+///     }
+/// }
+/// ```
+pub fn deserialize_integer_strict<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    deserializer.deserialize_any(IntegerStrictVisitor(PhantomData))
+}
+
+struct IntegerStrictVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for IntegerStrictVisitor<T>
+where
+    T: FromStr + Deserialize<'de>,
+    <T as FromStr>::Err: Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an integer or a string with an integer inside")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        let digits = match v.as_bytes().split_first() {
+            Some((b'-', digits)) => digits,
+            _ => v.as_bytes(),
+        };
+        if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+            let is_float = v.contains(&['.', 'e', 'E'][..]) && normalize_decimal(v).is_some();
+            return Err(if is_float && v.trim() == v {
+                E::custom(format_args!("float not allowed: {:?}", v))
+            } else {
+                E::custom(format_args!("not a number: {:?}", v))
+            });
+        }
+        v.parse::<T>()
+            .map_err(|e| E::custom(describe_number_error::<T>(v, e)))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i64(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u64(v)
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_i128(v)
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        NumberFromStringVisitor(PhantomData).visit_u128(v)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        Err(E::custom(format_args!("float not allowed: {}", v)))
+    }
+}
+
+/// Multiplies a decimal number like `-1.25` by `10^exponent` by moving its decimal point, giving
+/// the shortest representation (`-1250` for the exponent of 3). Returns `None` if `number` is not
+/// a plain decimal number.
+fn shift_decimal_point(number: &str, exponent: usize) -> Option<String> {
+    let (sign, unsigned) = match number.as_bytes().first() {
+        Some(b'-') => ("-", &number[1..]),
+        Some(b'+') => ("", &number[1..]),
+        _ => ("", number),
+    };
+    let mut parts = unsigned.splitn(2, '.');
+    let whole = parts.next().unwrap_or_default();
+    let fraction = parts.next().unwrap_or_default();
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+        return None;
+    }
+
+    let mut digits = whole.to_owned();
+    digits.push_str(fraction);
+    for _ in fraction.len()..exponent {
+        digits.push('0');
+    }
+    let point = whole.len() + exponent;
+    let (whole, fraction) = digits.split_at(point);
+    let whole = whole.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+
+    let mut shifted = sign.to_owned();
+    shifted.push_str(if whole.is_empty() { "0" } else { whole });
+    if !fraction.is_empty() {
+        shifted.push('.');
+        shifted.push_str(fraction);
+    }
+    Some(shifted)
+}
+
+/// Deserializes a `std::time::SystemTime` from a seconds time stamp, either a number or a string
+/// with a number inside like **"1519927261"**. Doesn't need chrono. The negative time stamps are
+/// the times before the epoch; a time stamp which the platform's `SystemTime` can't represent is
+/// an error.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_system_time_from_seconds")]
+///     time: SystemTime,
+/// }
+/// fn main() {
+///     let s = r#" { "time": "1519927261" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.time, UNIX_EPOCH + Duration::from_secs(1519927261));
+///
+///     let s = r#" { "time": -86400 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.time, UNIX_EPOCH - Duration::from_secs(86400));
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn deserialize_system_time_from_seconds<'de, D>(
+    deserializer: D,
+) -> Result<std::time::SystemTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let number = deserialize_number_from_string::<i64, D>(deserializer)?;
+    system_time_from_timestamp(number, 1)
+}
+
+/// Deserializes a `std::time::SystemTime` from a milliseconds time stamp, either a number or a
+/// string with a number inside like **"1519927261900"**. The same rules as in
+/// [`deserialize_system_time_from_seconds`] apply.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_system_time_from_milliseconds")]
+///     time: SystemTime,
+/// }
+/// fn main() {
+///     let s = r#" { "time": "1519927261900" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.time, UNIX_EPOCH + Duration::from_millis(1519927261900));
+///
+///     let s = r#" { "time": -1500 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.time, UNIX_EPOCH - Duration::from_millis(1500));
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn deserialize_system_time_from_milliseconds<'de, D>(
+    deserializer: D,
+) -> Result<std::time::SystemTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let number = deserialize_number_from_string::<i64, D>(deserializer)?;
+    system_time_from_timestamp(number, 1_000)
+}
+
+/// Converts a time stamp counting `units_per_second` units since the epoch into a system time.
+#[cfg(feature = "std")]
+fn system_time_from_timestamp<E>(
+    timestamp: i64,
+    units_per_second: u64,
+) -> Result<std::time::SystemTime, E>
+where
+    E: serde::de::Error,
+{
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let magnitude = timestamp.unsigned_abs();
+    let duration = Duration::from_secs(magnitude / units_per_second)
+        + Duration::from_nanos(magnitude % units_per_second * (1_000_000_000 / units_per_second));
+    let time = if timestamp < 0 {
+        UNIX_EPOCH.checked_sub(duration)
+    } else {
+        UNIX_EPOCH.checked_add(duration)
+    };
+    time.ok_or_else(|| E::custom(format!("Invalid timestamp: {}", timestamp)))
+}
+
+/// Serializes a `std::time::SystemTime` as a seconds time stamp, the counterpart of
+/// [`deserialize_system_time_from_seconds`]. The fractions of a second are dropped, rounding
+/// towards the past, so the times before the epoch give the negative time stamps.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_system_time_as_seconds")]
+///     time: SystemTime,
+/// }
+/// fn main() {
+///     let a = MyStruct { time: UNIX_EPOCH + Duration::from_millis(1519927261900) };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":1519927261}"#);
+///
+///     let a = MyStruct { time: UNIX_EPOCH - Duration::from_millis(1500) };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"time":-2}"#);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn serialize_system_time_as_seconds<S>(
+    time: &std::time::SystemTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    use core::convert::TryFrom;
+    use std::time::UNIX_EPOCH;
+
+    let seconds = match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => i128::from(after.as_secs()),
+        Err(e) => {
+            let before = e.duration();
+            -i128::from(before.as_secs()) - i128::from(before.subsec_nanos() > 0)
+        }
+    };
+    let seconds = i64::try_from(seconds).map_err(|_| {
+        serde::ser::Error::custom(format!("the time stamp {} does not fit into i64", seconds))
+    })?;
+    serializer.serialize_i64(seconds)
+}
+
+/// Defines a module for `#[serde(with)]` which serializes and deserializes the date times as the
+/// strings in a custom `chrono` format, keeping the format string in one place. The module
+/// works with `chrono::NaiveDateTime` and has the submodules for the other types:
+///
+/// - `utc` for `chrono::DateTime<Utc>`, the strings are read and written as UTC;
+/// - `date` for `chrono::NaiveDate`, for the formats without the time like `"%Y%m%d"`;
+/// - `option`, `option_utc` and `option_date` for the `Option`s of the above, `None` is `null`.
+///
+/// The format string itself is available as the `FORMAT` constant of the module. The generated
+/// code refers to the `chrono` and `serde` crates, so both have to be the dependencies of the
+/// crate using the macro.
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+///
+/// serde_aux::datetime_format!(legacy_format, "%d/%m/%Y %H:%M");
+/// serde_aux::datetime_format!(pub compact_date, "%Y%m%d");
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "legacy_format")]
+///     created: NaiveDateTime,
+///     #[serde(with = "legacy_format::option_utc")]
+///     updated: Option<DateTime<Utc>>,
+///     #[serde(with = "compact_date::date")]
+///     day: NaiveDate,
+/// }
+///
+/// fn main() {
+///     let s = r#"{"created":"02/01/2023 03:04","updated":null,"day":"20230102"}"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     let day = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+///     assert_eq!(a.created, day.and_hms_opt(3, 4, 0).unwrap());
+///     assert_eq!(a.updated, None);
+///     assert_eq!(a.day, day);
+///     assert_eq!(serde_json::to_string(&a).unwrap(), s);
+///     assert_eq!(legacy_format::FORMAT, "%d/%m/%Y %H:%M");
+///
+///     let s = r#"{"created":"2023-01-02 03:04","updated":null,"day":"20230102"}"#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+#[macro_export]
+macro_rules! datetime_format {
+    ($(#[$attr:meta])* $vis:vis $name:ident, $format:expr) => {
+        $(#[$attr])*
+        $vis mod $name {
+            /// The format of the date times.
+            pub const FORMAT: &str = $format;
+
+            fn parse_error<E: serde::de::Error>(s: &str, e: chrono::ParseError) -> E {
+                E::custom(format!(
+                    "invalid date time {:?} for the format {:?}: {}",
+                    s, FORMAT, e
+                ))
+            }
+
+            fn parse_naive_date_time<E: serde::de::Error>(
+                s: &str,
+            ) -> Result<chrono::NaiveDateTime, E> {
+                chrono::NaiveDateTime::parse_from_str(s, FORMAT).map_err(|e| parse_error(s, e))
+            }
+
+            fn parse_naive_date<E: serde::de::Error>(s: &str) -> Result<chrono::NaiveDate, E> {
+                chrono::NaiveDate::parse_from_str(s, FORMAT).map_err(|e| parse_error(s, e))
+            }
+
+            fn parse_utc<E: serde::de::Error>(
+                s: &str,
+            ) -> Result<chrono::DateTime<chrono::Utc>, E> {
+                parse_naive_date_time(s)
+                    .map(|dt| chrono::TimeZone::from_utc_datetime(&chrono::Utc, &dt))
+            }
+
+            /// Serializes a `chrono::NaiveDateTime` in the format.
+            pub fn serialize<S>(
+                value: &chrono::NaiveDateTime,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serializer.collect_str(&value.format(FORMAT))
+            }
+
+            /// Deserializes a `chrono::NaiveDateTime` in the format.
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<chrono::NaiveDateTime, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+                parse_naive_date_time(&s)
+            }
+
+            /// The same for `chrono::DateTime<Utc>`.
+            pub mod utc {
+                /// Serializes a `chrono::DateTime<Utc>` in the format.
+                pub fn serialize<S>(
+                    value: &chrono::DateTime<chrono::Utc>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    serializer.collect_str(&value.format(super::FORMAT))
+                }
+
+                /// Deserializes a `chrono::DateTime<Utc>` in the format.
+                pub fn deserialize<'de, D>(
+                    deserializer: D,
+                ) -> Result<chrono::DateTime<chrono::Utc>, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+                    super::parse_utc(&s)
+                }
+            }
+
+            /// The same for `chrono::NaiveDate`.
+            pub mod date {
+                /// Serializes a `chrono::NaiveDate` in the format.
+                pub fn serialize<S>(
+                    value: &chrono::NaiveDate,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    serializer.collect_str(&value.format(super::FORMAT))
+                }
+
+                /// Deserializes a `chrono::NaiveDate` in the format.
+                pub fn deserialize<'de, D>(deserializer: D) -> Result<chrono::NaiveDate, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+                    super::parse_naive_date(&s)
+                }
+            }
+
+            /// The same for `Option<chrono::NaiveDateTime>`.
+            pub mod option {
+                /// Serializes an `Option<chrono::NaiveDateTime>` in the format or as `null`.
+                pub fn serialize<S>(
+                    value: &Option<chrono::NaiveDateTime>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    match value {
+                        Some(value) => {
+                            serializer.serialize_some(&value.format(super::FORMAT).to_string())
+                        }
+                        None => serializer.serialize_none(),
+                    }
+                }
+
+                /// Deserializes an `Option<chrono::NaiveDateTime>` in the format or from `null`.
+                pub fn deserialize<'de, D>(
+                    deserializer: D,
+                ) -> Result<Option<chrono::NaiveDateTime>, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    let s = <Option<String> as serde::Deserialize>::deserialize(deserializer)?;
+                    s.map(|s| super::parse_naive_date_time(&s)).transpose()
+                }
+            }
+
+            /// The same for `Option<chrono::DateTime<Utc>>`.
+            pub mod option_utc {
+                /// Serializes an `Option<chrono::DateTime<Utc>>` in the format or as `null`.
+                pub fn serialize<S>(
+                    value: &Option<chrono::DateTime<chrono::Utc>>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    match value {
+                        Some(value) => {
+                            serializer.serialize_some(&value.format(super::FORMAT).to_string())
+                        }
+                        None => serializer.serialize_none(),
+                    }
+                }
+
+                /// Deserializes an `Option<chrono::DateTime<Utc>>` in the format or from `null`.
+                pub fn deserialize<'de, D>(
+                    deserializer: D,
+                ) -> Result<Option<chrono::DateTime<chrono::Utc>>, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    let s = <Option<String> as serde::Deserialize>::deserialize(deserializer)?;
+                    s.map(|s| super::parse_utc(&s)).transpose()
+                }
+            }
+
+            /// The same for `Option<chrono::NaiveDate>`.
+            pub mod option_date {
+                /// Serializes an `Option<chrono::NaiveDate>` in the format or as `null`.
+                pub fn serialize<S>(
+                    value: &Option<chrono::NaiveDate>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    match value {
+                        Some(value) => {
+                            serializer.serialize_some(&value.format(super::FORMAT).to_string())
+                        }
+                        None => serializer.serialize_none(),
+                    }
+                }
+
+                /// Deserializes an `Option<chrono::NaiveDate>` in the format or from `null`.
+                pub fn deserialize<'de, D>(
+                    deserializer: D,
+                ) -> Result<Option<chrono::NaiveDate>, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    let s = <Option<String> as serde::Deserialize>::deserialize(deserializer)?;
+                    s.map(|s| super::parse_naive_date(&s)).transpose()
+                }
+            }
+        }
+    };
+}
+
+/// Deserializes a `std::time::Duration` from a string with one or more whitespace-separated
+/// values with units, like `"5s"`, `"1h 30m 15s"` or `"2d 4h"`, summing them up. A number (or a
+/// numeric string) without a unit is taken as seconds.
+///
+/// The units are `ns`, `us`, `ms`, `s`, `m`, `h`, `d` (24 hours) and `w` (7 days), the values are
+/// the non-negative numbers, possibly with a fraction like `"1.5s"` (exact down to a nanosecond).
+/// An unknown unit, a repeated unit (`"1h 1h"`) and an overflowing duration are errors.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::time::Duration;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_duration_composite")]
+///     timeout: Duration,
+/// }
+///
+/// fn main() {
+///     for &(s, secs) in &[
+///         (r#""5s""#, 5),
+///         (r#""1h 30m 15s""#, 5415),
+///         (r#"" 2d  4h ""#, 187200),
+///         (r#""90""#, 90),
+///         ("90", 90),
+///     ] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "timeout": {} }}"#, s)).unwrap();
+///         assert_eq!(a.timeout, Duration::from_secs(secs));
+///     }
+///
+///     for s in &[r#""1s 500ms""#, r#""1.5s""#, r#""0.025m""#, "1.5"] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "timeout": {} }}"#, s)).unwrap();
+///         assert_eq!(a.timeout, Duration::from_millis(1500));
+///     }
+///
+///     for s in &[r#""1h 1h""#, r#""1y""#, r#""h""#, r#""""#, r#""-1s""#] {
+///         let json = format!(r#"{{ "timeout": {} }}"#, s);
+///         assert!(serde_json::from_str::<MyStruct>(&json).is_err());
+///     }
+/// }
+/// ```
+pub fn deserialize_duration_composite<'de, D>(
+    deserializer: D,
+) -> Result<core::time::Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    use core::time::Duration;
+
+    struct DurationCompositeVisitor;
+
+    impl<'de> Visitor<'de> for DurationCompositeVisitor {
+        type Value = Duration;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number of seconds or a string with a duration like \"1h 30m\"")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Duration, E>
+        where
+            E: serde::de::Error,
+        {
+            parse_duration_composite(v).map_err(|e| {
+                E::custom(format_args!(
+                    "invalid value: string {:?}, expected {}: {}",
+                    v,
+                    Expecting(&self),
+                    e
+                ))
+            })
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Duration, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Duration::from_secs(v))
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Duration, E>
+        where
+            E: serde::de::Error,
+        {
+            if v < 0 {
+                return Err(E::invalid_value(serde::de::Unexpected::Signed(v), &self));
+            }
+            Ok(Duration::from_secs(v as u64))
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<Duration, E>
+        where
+            E: serde::de::Error,
+        {
+            if !v.is_finite() || v < 0.0 {
+                return Err(E::invalid_value(serde::de::Unexpected::Float(v), &self));
+            }
+            self.visit_str(&v.to_string())
+        }
+    }
+
+    deserializer.deserialize_any(DurationCompositeVisitor)
+}
+
+fn parse_duration_composite(s: &str) -> Result<core::time::Duration, String> {
+    use core::time::Duration;
+
+    const UNITS: &[(&str, u128)] = &[
+        ("ns", 1),
+        ("us", 1_000),
+        ("ms", 1_000_000),
+        ("s", 1_000_000_000),
+        ("m", 60 * 1_000_000_000),
+        ("h", 60 * 60 * 1_000_000_000),
+        ("d", 24 * 60 * 60 * 1_000_000_000),
+        ("w", 7 * 24 * 60 * 60 * 1_000_000_000),
+    ];
+
+    let overflow = || "the duration is too long".to_owned();
+    let mut seen = [false; 8];
+    let mut total: u128 = 0;
+    let mut parts = s.split_whitespace().peekable();
+    if parts.peek().is_none() {
+        return Err("no duration given".to_owned());
+    }
+    for part in parts {
+        let unit_start = part
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(part.len());
+        let (number, unit) = part.split_at(unit_start);
+        if number.is_empty() {
+            return Err(format!("missing the number in {:?}", part));
+        }
+        let unit = if unit.is_empty() { "s" } else { unit };
+        let index = UNITS
+            .iter()
+            .position(|&(u, _)| u == unit)
+            .ok_or_else(|| format!("unknown unit {:?}", unit))?;
+        if seen[index] {
+            return Err(format!("repeated unit {:?}", unit));
+        }
+        seen[index] = true;
+
+        let mut digits = number.splitn(2, '.');
+        let whole = digits.next().unwrap_or_default();
+        let fraction = digits.next().unwrap_or_default();
+        if whole.is_empty() || (number.contains('.') && fraction.is_empty()) {
+            return Err(format!("invalid number {:?}", number));
+        }
+        if fraction.contains('.') {
+            return Err(format!("invalid number {:?}", number));
+        }
+        if fraction.len() > 18 {
+            return Err("too many fractional digits".to_owned());
+        }
+        let factor = UNITS[index].1;
+        let whole: u128 = whole.parse::<u64>().map_err(|_| overflow())?.into();
+        // The fraction below a nanosecond is dropped.
+        let fraction = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse::<u128>().map_err(|_| overflow())? * factor
+                / 10u128.pow(fraction.len() as u32)
+        };
+        total = whole
+            .checked_mul(factor)
+            .and_then(|nanos| nanos.checked_add(fraction))
+            .and_then(|nanos| nanos.checked_add(total))
+            .ok_or_else(overflow)?;
+    }
+    let seconds = total / 1_000_000_000;
+    if seconds > u128::from(u64::MAX) {
+        return Err(overflow());
+    }
+    Ok(Duration::new(
+        seconds as u64,
+        (total % 1_000_000_000) as u32,
+    ))
+}
+
+/// Deserializes a map of the durations, like the timeouts of a config, parsing every value as
+/// [`deserialize_duration_composite`] does: the values may be the numbers of seconds or the
+/// strings with the units, mixed freely. When a value fails, the error names its key. The map
+/// type is taken from the field: a `HashMap<String, Duration>`, a `BTreeMap<String, Duration>`
+/// or anything else implementing `FromIterator<(String, Duration)>`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_duration_map")]
+///     timeouts: HashMap<String, Duration>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "timeouts": { "connect": "5s", "read": 30, "write": "1.5s" } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.timeouts["connect"], Duration::from_secs(5));
+///     assert_eq!(a.timeouts["read"], Duration::from_secs(30));
+///     assert_eq!(a.timeouts["write"], Duration::from_millis(1500));
+///
+///     let s = r#" { "timeouts": { "connect": "5s", "read": "soon" } } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"invalid value for key "read": "#));
+/// }
+/// ```
+pub fn deserialize_duration_map<'de, D, M>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: FromIterator<(String, core::time::Duration)>,
+{
+    struct DurationValue;
+
+    impl<'de> serde::de::DeserializeSeed<'de> for DurationValue {
+        type Value = core::time::Duration;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserialize_duration_composite(deserializer)
+        }
+    }
+
+    struct DurationMapVisitor<M>(PhantomData<M>);
+
+    impl<'de, M> Visitor<'de> for DurationMapVisitor<M>
+    where
+        M: FromIterator<(String, core::time::Duration)>,
+    {
+        type Value = M;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map of the durations")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: serde::de::MapAccess<'de>,
+        {
+            let mut values = Vec::new();
+            while let Some(key) = map.next_key::<String>()? {
+                let value = map.next_value_seed(DurationValue).map_err(|e| {
+                    <A::Error as serde::de::Error>::custom(format_args!(
+                        "invalid value for key {:?}: {}",
+                        key, e
+                    ))
+                })?;
+                values.push((key, value));
+            }
+            Ok(values.into_iter().collect())
+        }
+    }
+
+    deserializer.deserialize_map(DurationMapVisitor(PhantomData))
+}
+
+/// An atomic integer (`AtomicU64`, `AtomicUsize` and the others from `std::sync::atomic`), for
+/// [`deserialize_atomic_from_string_or_number`] and [`serialize_atomic`]. `AtomicI64` and
+/// `AtomicU64` are only there on the targets supporting the 64-bit atomics.
+pub trait AtomicInteger {
+    /// The integer type stored in the atomic.
+    type Value;
+
+    /// Creates the atomic holding the value.
+    fn new(value: Self::Value) -> Self;
+
+    /// Loads the value with `Ordering::Relaxed`.
+    fn load_relaxed(&self) -> Self::Value;
+}
+
+macro_rules! impl_atomic_integer {
+    ($($(#[$attr:meta])* $atomic:ident($value:ty)),*) => {
+        $(
+            $(#[$attr])*
+            impl AtomicInteger for core::sync::atomic::$atomic {
+                type Value = $value;
+
+                fn new(value: $value) -> Self {
+                    core::sync::atomic::$atomic::new(value)
+                }
+
+                fn load_relaxed(&self) -> $value {
+                    self.load(core::sync::atomic::Ordering::Relaxed)
+                }
+            }
+        )*
+    };
+}
+
+impl_atomic_integer!(
+    AtomicI8(i8),
+    AtomicI16(i16),
+    AtomicI32(i32),
+    #[cfg(target_has_atomic = "64")]
+    AtomicI64(i64),
+    AtomicIsize(isize),
+    AtomicU8(u8),
+    AtomicU16(u16),
+    AtomicU32(u32),
+    #[cfg(target_has_atomic = "64")]
+    AtomicU64(u64),
+    AtomicUsize(usize)
+);
+
+/// Deserializes an atomic integer (see [`AtomicInteger`]) from a number or a numeric string,
+/// the same way [`deserialize_number_from_string`] does for the plain integers.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(
+///         deserialize_with = "deserialize_atomic_from_string_or_number",
+///         serialize_with = "serialize_atomic"
+///     )]
+///     requests: AtomicU64,
+///     #[serde(
+///         deserialize_with = "deserialize_atomic_from_string_or_number",
+///         serialize_with = "serialize_atomic"
+///     )]
+///     workers: AtomicUsize,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "requests": "123", "workers": 4 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.requests.load(Ordering::Relaxed), 123);
+///     assert_eq!(a.workers.load(Ordering::Relaxed), 4);
+///
+///     a.requests.fetch_add(1, Ordering::Relaxed);
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"requests":124,"workers":4}"#);
+///
+///     let s = r#" { "requests": "-1", "workers": 4 } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_atomic_from_string_or_number<'de, D, A>(deserializer: D) -> Result<A, D::Error>
+where
+    D: Deserializer<'de>,
+    A: AtomicInteger,
+    A::Value: FromStr + Deserialize<'de>,
+    <A::Value as FromStr>::Err: Display,
+{
+    deserialize_number_from_string::<A::Value, D>(deserializer).map(A::new)
+}
+
+/// Serializes an atomic integer (see [`AtomicInteger`]) as a number, loading it with
+/// `Ordering::Relaxed`. See [`deserialize_atomic_from_string_or_number`] for an example.
+pub fn serialize_atomic<A, S>(value: &A, serializer: S) -> Result<S::Ok, S::Error>
+where
+    A: AtomicInteger,
+    A::Value: serde::Serialize,
+    S: Serializer,
+{
+    serde::Serialize::serialize(&value.load_relaxed(), serializer)
+}
+
+/// An enum with a catch-all variant keeping the unknown names, usually `Other(String)`, for
+/// [`deserialize_enum_with_fallback`] and [`serialize_enum_with_fallback`].
+pub trait EnumWithFallback: Sized {
+    /// Wraps an unknown variant name into the catch-all variant.
+    fn from_unknown(name: String) -> Self;
+
+    /// Returns the name kept in the catch-all variant, or `None` for the other variants.
+    fn as_unknown(&self) -> Option<&str>;
+}
+
+/// Deserializes an enum from a variant name, handing the unknown names over to the catch-all
+/// variant of the [`EnumWithFallback`] implementation instead of failing. The known names are
+/// handled by the enum's own `Deserialize` implementation, so `#[serde(rename)]` and the like are
+/// respected; only the strings are accepted. Useful when the upstream may add new values at
+/// any time.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// #[serde(rename_all = "snake_case")]
+/// enum Payment {
+///     Card,
+///     BankTransfer,
+///     Other(String),
+/// }
+///
+/// impl EnumWithFallback for Payment {
+///     fn from_unknown(name: String) -> Self {
+///         Payment::Other(name)
+///     }
+///
+///     fn as_unknown(&self) -> Option<&str> {
+///         match self {
+///             Payment::Other(name) => Some(name),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(
+///         deserialize_with = "deserialize_enum_with_fallback",
+///         serialize_with = "serialize_enum_with_fallback"
+///     )]
+///     payment: Payment,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "payment": "bank_transfer" } "#).unwrap();
+///     assert_eq!(a.payment, Payment::BankTransfer);
+///
+///     let s = r#"{"payment":"crypto"}"#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.payment, Payment::Other("crypto".to_owned()));
+///     assert_eq!(serde_json::to_string(&a).unwrap(), s);
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "payment": 1 } "#).is_err());
+/// }
+/// ```
+pub fn deserialize_enum_with_fallback<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: EnumWithFallback + Deserialize<'de>,
+{
+    use serde::de::IntoDeserializer;
+
+    struct EnumWithFallbackVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for EnumWithFallbackVisitor<T>
+    where
+        T: EnumWithFallback + Deserialize<'de>,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a variant name")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            let known: Result<T, E> = T::deserialize(v.into_deserializer());
+            Ok(known.unwrap_or_else(|_| {
+                report_fallback(v);
+                T::from_unknown(v.to_owned())
+            }))
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<T, E>
+        where
+            E: serde::de::Error,
+        {
+            let known: Result<T, E> = T::deserialize(v.as_str().into_deserializer());
+            Ok(known.unwrap_or_else(|_| {
+                report_fallback(&v);
+                T::from_unknown(v)
+            }))
+        }
+    }
+
+    fn report_fallback(name: &str) {
+        crate::lenient::report(
+            "deserialize_enum_with_fallback",
+            crate::lenient::LenientAction::Fallback,
+            || name.to_owned(),
+        );
+    }
+
+    deserializer.deserialize_str(EnumWithFallbackVisitor(PhantomData))
+}
+
+/// Serializes an enum with the [`EnumWithFallback`] catch-all variant: the kept unknown name is
+/// written back as is, the other variants are serialized by the enum's own `Serialize`
+/// implementation. See [`deserialize_enum_with_fallback`] for an example.
+pub fn serialize_enum_with_fallback<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: EnumWithFallback + serde::Serialize,
+    S: Serializer,
+{
+    match value.as_unknown() {
+        Some(name) => serializer.serialize_str(name),
+        None => value.serialize(serializer),
+    }
+}
+
+/// Builds a serializer writing a float as a string with a fixed number of the fraction digits,
+/// like `"10.50"`. The value is formatted as `format!("{:.N}", value)` does: the exact binary
+/// value is rounded to the nearest, so `2.675` (stored as `2.67499999...`) gives `"2.67"` and
+/// `0.125` (stored exactly, a tie) gives `"0.12"`, the ties going to the even digit. The
+/// non-finite values are written as `"NaN"`, `"inf"` and `"-inf"`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn price<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+/// where
+///     S: serde::Serializer,
+/// {
+///     FloatWithPrecision::new(2).into_serializer()(value, serializer)
+/// }
+///
+/// fn discount<S>(value: &Option<f32>, serializer: S) -> Result<S::Ok, S::Error>
+/// where
+///     S: serde::Serializer,
+/// {
+///     FloatWithPrecision::new(1).into_option_serializer()(value, serializer)
+/// }
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "price")]
+///     price: f64,
+///     #[serde(serialize_with = "discount")]
+///     discount: Option<f32>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { price: 10.5, discount: Some(0.25) };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"price":"10.50","discount":"0.2"}"#);
+///
+///     let a = MyStruct { price: 2.675, discount: None };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"price":"2.67","discount":null}"#);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct FloatWithPrecision {
+    precision: usize,
+}
+
+impl FloatWithPrecision {
+    /// Creates a builder writing `precision` fraction digits.
+    pub fn new(precision: usize) -> Self {
+        FloatWithPrecision { precision }
+    }
+
+    /// Creates the serializer function, see the example above.
+    pub fn into_serializer<T, S>(self) -> impl Fn(&T, S) -> Result<S::Ok, S::Error>
+    where
+        T: Copy + Into<f64>,
+        S: Serializer,
+    {
+        move |value, serializer| {
+            serializer.collect_str(&format_args!("{:.*}", self.precision, (*value).into()))
+        }
+    }
+
+    /// Creates the serializer function of an `Option`, writing `None` as `null`.
+    pub fn into_option_serializer<T, S>(self) -> impl Fn(&Option<T>, S) -> Result<S::Ok, S::Error>
+    where
+        T: Copy + Into<f64>,
+        S: Serializer,
+    {
+        move |value, serializer| match value {
+            Some(value) => {
+                serializer.serialize_some(&format!("{:.*}", self.precision, (*value).into()))
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Builds a serializer writing a number as a string padded with the leading zeros to a width,
+/// like `"0000012345"` for the width 10, and the matching deserializer reading such strings
+/// back. The value is anything whose `Display` gives a number, like the `u64` newtypes of IDs.
+///
+/// - A negative number keeps its sign first, `-42` gives `"-000000042"`.
+/// - A number longer than the width is written as it is, without the padding, rather than
+///   truncated: a cut number would be read back as another value.
+/// - The deserializer strips the leading zeros (keeping the last digit) before parsing with
+///   `FromStr`, so it accepts any width, and also reads the plain numbers.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct OrderId(u64);
+///
+/// impl std::fmt::Display for OrderId {
+///     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+///         write!(f, "{}", self.0)
+///     }
+/// }
+///
+/// impl std::str::FromStr for OrderId {
+///     type Err = std::num::ParseIntError;
+///
+///     fn from_str(s: &str) -> Result<Self, Self::Err> {
+///         s.parse().map(OrderId)
+///     }
+/// }
+///
+/// const ORDER_ID: PaddedNumberString = PaddedNumberString::new(10);
+///
+/// fn serialize_order_id<S>(value: &OrderId, serializer: S) -> Result<S::Ok, S::Error>
+/// where
+///     S: serde::Serializer,
+/// {
+///     ORDER_ID.into_serializer()(value, serializer)
+/// }
+///
+/// fn deserialize_order_id<'de, D>(deserializer: D) -> Result<OrderId, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     ORDER_ID.into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_order_id", deserialize_with = "deserialize_order_id")]
+///     id: OrderId,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { id: OrderId(12345) };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"id":"0000012345"}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+///
+///     let a = MyStruct { id: OrderId(12345678901) };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"id":"12345678901"}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PaddedNumberString {
+    width: usize,
+}
+
+impl PaddedNumberString {
+    /// Creates a builder padding the numbers to `width` characters, including the sign.
+    pub const fn new(width: usize) -> Self {
+        PaddedNumberString { width }
+    }
+
+    /// Creates the serializer function, see the example above.
+    pub fn into_serializer<T, S>(self) -> impl Fn(&T, S) -> Result<S::Ok, S::Error>
+    where
+        T: Display,
+        S: Serializer,
+    {
+        move |value, serializer| serializer.serialize_str(&self.pad(&value.to_string()))
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, T, D>(self) -> impl FnMut(D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        move |deserializer| deserializer.deserialize_any(PaddedNumberStringVisitor(PhantomData))
+    }
+
+    fn pad(&self, number: &str) -> String {
+        let (sign, digits) = match number.as_bytes().split_first() {
+            Some((b'-', _)) => ("-", &number[1..]),
+            _ => ("", number),
+        };
+        let zeros = self.width.saturating_sub(number.len());
+        let mut padded = String::with_capacity(number.len() + zeros);
+        padded.push_str(sign);
+        for _ in 0..zeros {
+            padded.push('0');
+        }
+        padded.push_str(digits);
+        padded
+    }
+}
+
+struct PaddedNumberStringVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for PaddedNumberStringVisitor<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a number or a string of a number padded with zeros")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        let (sign, digits) = match v.as_bytes().split_first() {
+            Some((b'-', _)) => ("-", &v[1..]),
+            _ => ("", v),
+        };
+        let stripped = digits.trim_start_matches('0');
+        let stripped = if stripped.is_empty() && !digits.is_empty() {
+            "0"
+        } else {
+            stripped
+        };
+        format!("{}{}", sign, stripped).parse().map_err(|e| {
+            E::custom(format_args!(
+                "invalid value: string {:?}, expected {}: {}",
+                v,
+                Expecting(&self),
+                e
+            ))
+        })
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<T, E>
+    where
+        E: serde::de::Error,
+    {
+        self.visit_str(&v.to_string())
+    }
+}
+
+/// Deserializes an `Option<f64>` mapping the non-finite values to `None`: `null`, an empty
+/// string, a NaN or an infinity, either a real float (from the formats supporting them) or a
+/// string spelling like `"NaN"`, `"inf"`, `"-Infinity"` (case-insensitive), all give `None`. The
+/// finite numbers and the numeric strings give `Some`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(default, deserialize_with = "deserialize_option_finite_f64")]
+///     temperature: Option<f64>,
+/// }
+///
+/// fn main() {
+///     for s in &[r#""NaN""#, r#""nan""#, r#""inf""#, r#""-Infinity""#, r#""""#, "null"] {
+///         let a: MyStruct = serde_json::from_str(&format!(r#"{{ "temperature": {} }}"#, s)).unwrap();
+///         assert_eq!(a.temperature, None);
+///     }
+///
+///     let a: MyStruct = serde_json::from_str(r#" { "temperature": "-1.5" } "#).unwrap();
+///     assert_eq!(a.temperature, Some(-1.5));
+///     let a: MyStruct = serde_json::from_str(r#" { "temperature": 20 } "#).unwrap();
+///     assert_eq!(a.temperature, Some(20.0));
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "temperature": "hot" } "#).is_err());
+/// }
+/// ```
+pub fn deserialize_option_finite_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct OptionFiniteF64Visitor;
+
+    impl<'de> Visitor<'de> for OptionFiniteF64Visitor {
+        type Value = Option<f64>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number, a numeric string or null")
+        }
+
+        fn visit_none<E>(self) -> Result<Option<f64>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_unit<E>(self) -> Result<Option<f64>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Option<f64>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(self)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Option<f64>, E>
+        where
+            E: serde::de::Error,
+        {
+            if is_non_finite_float_spelling(v) {
+                return Ok(None);
+            }
+            OptionNumberFromStringVisitor::<f64>(PhantomData)
+                .visit_str(v)
+                .map(|v| v.filter(|v| v.is_finite()))
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Option<f64>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Some(v as f64))
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Option<f64>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Some(v as f64))
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<Option<f64>, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(Some(v).filter(|v| v.is_finite()))
+        }
+    }
+
+    deserializer.deserialize_any(OptionFiniteF64Visitor)
+}
+
+/// Tells whether the string spells a NaN or an infinity, like `"NaN"` or `"-Infinity"`.
+fn is_non_finite_float_spelling(s: &str) -> bool {
+    let s = s.trim();
+    let unsigned = s.trim_start_matches(&['+', '-'][..]);
+    s.len() - unsigned.len() <= 1
+        && ["nan", "inf", "infinity"]
+            .iter()
+            .any(|spelling| unsigned.eq_ignore_ascii_case(spelling))
+}
+
+/// Deserializes an `f32` or an `f64` from a number or a numeric string, as
+/// [`deserialize_number_from_string`] does, and fails if the value is a NaN or an infinity, from
+/// whatever representation it came (a real float of a format supporting them or a string like
+/// `"NaN"`). The error names the received value. See [`deserialize_option_finite_f64`] for the
+/// lenient counterpart.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_finite_float")]
+///     amount: f64,
+///     #[serde(deserialize_with = "deserialize_finite_float")]
+///     rate: f32,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "amount": "10.5", "rate": 0.5 } "#).unwrap();
+///     assert_eq!(a.amount, 10.5);
+///     assert_eq!(a.rate, 0.5);
+///
+///     let s = r#" { "amount": "NaN", "rate": 0.5 } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with("invalid value: NaN, expected a finite number"));
+///
+///     let s = r#" { "amount": 1, "rate": "-inf" } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_finite_float<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de> + Copy + Into<f64> + Display,
+    <T as FromStr>::Err: Display,
+{
+    let value = deserialize_number_from_string::<T, D>(deserializer)?;
+    if !value.into().is_finite() {
+        return Err(serde::de::Error::custom(format!(
+            "invalid value: {}, expected a finite number",
+            value
+        )));
+    }
+    Ok(value)
+}
+
+/// Serializes an `f64` as a number, or as `null` if it is a NaN or an infinity, whatever the
+/// format would do with them otherwise. [`deserialize_option_finite_f64`] reads it back.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "serialize_float_nan_as_null")]
+///     temperature: f64,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { temperature: 21.5 };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"temperature":21.5}"#);
+///
+///     for &temperature in &[f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+///         let a = MyStruct { temperature };
+///         assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"temperature":null}"#);
+///     }
+/// }
+/// ```
+pub fn serialize_float_nan_as_null<S>(value: &f64, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if value.is_finite() {
+        serializer.serialize_f64(*value)
+    } else {
+        serializer.serialize_none()
+    }
+}
+
+/// Serializes an `Option<f64>` as [`serialize_float_nan_as_null`] does, so `None`, `Some(NaN)`
+/// and `Some` of an infinity all become `null`. [`deserialize_option_finite_f64`] reads it back.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(
+///         serialize_with = "serialize_option_float_nan_as_null",
+///         deserialize_with = "deserialize_option_finite_f64"
+///     )]
+///     temperature: Option<f64>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { temperature: Some(21.5) };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"temperature":21.5}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap().temperature, Some(21.5));
+///
+///     for &temperature in &[None, Some(f64::NAN), Some(f64::NEG_INFINITY)] {
+///         let s = serde_json::to_string(&MyStruct { temperature }).unwrap();
+///         assert_eq!(s, r#"{"temperature":null}"#);
+///         assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap().temperature, None);
+///     }
+/// }
+/// ```
+pub fn serialize_option_float_nan_as_null<S>(
+    value: &Option<f64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(value) => serialize_float_nan_as_null(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Deserializes a value from the inner object of a single-key envelope, like `{"data": {...}}`
+/// or `{"result": {...}}`, so no wrapper struct is needed. The key has to be `data` or `result`;
+/// a missing key, a different one or more than one key is an error. [`serialize_wrap_envelope`]
+/// writes the value back. See [`Envelope`] for the other key names.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct User {
+///     id: u64,
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_unwrap_envelope")]
+///     user: User,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "user": { "data": { "id": 1 } } } "#).unwrap();
+///     assert_eq!(a.user.id, 1);
+///     let a: MyStruct = serde_json::from_str(r#" { "user": { "result": { "id": 2 } } } "#).unwrap();
+///     assert_eq!(a.user.id, 2);
+///
+///     let s = r#" { "user": { "data": { "id": 1 }, "meta": {} } } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+///     let s = r#" { "user": { "payload": { "id": 1 } } } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+///     let s = r#" { "user": {} } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_unwrap_envelope<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Envelope::new().into_deserializer()(deserializer)
+}
+
+/// Serializes a value wrapped into a single-key envelope, `{"data": value}`. This is the
+/// counterpart of [`deserialize_unwrap_envelope`]; see [`Envelope`] for the other key names.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct User {
+///     id: u64,
+/// }
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(
+///         serialize_with = "serialize_wrap_envelope",
+///         deserialize_with = "deserialize_unwrap_envelope"
+///     )]
+///     user: User,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { user: User { id: 1 } };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"user":{"data":{"id":1}}}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap().user, a.user);
+/// }
+/// ```
+pub fn serialize_wrap_envelope<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    Envelope::new().into_serializer()(value, serializer)
+}
+
+/// Builds the (de)serializers of the values wrapped into a single-key envelope, like
+/// `{"payload": {...}}`. By default the key is `data` or `result` when deserializing and `data`
+/// when serializing.
+///
+/// - The envelope must have exactly one key, which is one of the accepted ones.
+/// - The inner value is deserialized right from the envelope, nothing is buffered.
+/// - The serializer writes the first of the accepted keys.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn unwrap_payload<'de, D>(deserializer: D) -> Result<Vec<u32>, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     Envelope::new().keys(&["payload"]).into_deserializer()(deserializer)
+/// }
+///
+/// fn wrap_payload<S>(value: &Vec<u32>, serializer: S) -> Result<S::Ok, S::Error>
+/// where
+///     S: serde::Serializer,
+/// {
+///     Envelope::new().keys(&["payload"]).into_serializer()(value, serializer)
+/// }
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(serialize_with = "wrap_payload", deserialize_with = "unwrap_payload")]
+///     ids: Vec<u32>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "ids": { "payload": [1, 2] } } "#).unwrap();
+///     assert_eq!(a.ids, vec![1, 2]);
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"ids":{"payload":[1,2]}}"#);
+///
+///     let s = r#" { "ids": { "data": [1, 2] } } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"unexpected envelope key "data", expected "payload""#));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Envelope<'a> {
+    keys: &'a [&'a str],
+}
+
+impl<'a> Default for Envelope<'a> {
+    fn default() -> Self {
+        Envelope {
+            keys: &["data", "result"],
+        }
+    }
+}
+
+impl<'a> Envelope<'a> {
+    /// Creates a builder accepting the `data` and the `result` keys.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the accepted keys of the envelope, the first one is used for serializing. An empty
+    /// list is treated as the default one.
+    pub fn keys(mut self, keys: &'a [&'a str]) -> Self {
+        if !keys.is_empty() {
+            self.keys = keys;
+        }
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, T, D>(self) -> impl FnMut(D) -> Result<T, D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        move |deserializer| {
+            deserializer.deserialize_map(EnvelopeVisitor {
+                keys: self.keys,
+                marker: PhantomData,
+            })
+        }
+    }
+
+    /// Creates the serializer function, see the example above.
+    pub fn into_serializer<T, S>(self) -> impl Fn(&T, S) -> Result<S::Ok, S::Error> + 'a
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        move |value, serializer| {
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry(self.keys[0], value)?;
+            map.end()
+        }
+    }
+}
+
+struct EnvelopeVisitor<'a, T> {
+    keys: &'a [&'a str],
+    marker: PhantomData<T>,
+}
+
+impl<'a, 'de, T> Visitor<'de> for EnvelopeVisitor<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a map with a single key, one of {:?}", self.keys)
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<T, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        use serde::de::Error;
+
+        let expected = || {
+            self.keys
+                .iter()
+                .map(|key| format!("{:?}", key))
+                .collect::<Vec<_>>()
+                .join(" or ")
+        };
+        let key = map
+            .next_key::<String>()?
+            .ok_or_else(|| A::Error::custom(format!("missing envelope key {}", expected())))?;
+        if !self.keys.contains(&key.as_str()) {
+            return Err(A::Error::custom(format!(
+                "unexpected envelope key {:?}, expected {}",
+                key,
+                expected()
+            )));
+        }
+        let value = map.next_value::<T>()?;
+        if let Some(other) = map.next_key::<String>()? {
+            return Err(A::Error::custom(format!(
+                "the envelope must have a single key, found {:?} besides {:?}",
+                other, key
+            )));
+        }
+        Ok(value)
+    }
+}
+
+/// Deserializes the value at a path of the keys through the nested maps, like `["name"]` of
+/// `{"name": "x", "email": "y"}`. The content is buffered into a `serde_json::Value` first, so a
+/// self-describing format is required. When a key of the path is missing (or the value it is
+/// looked up in is not a map), the path is treated as `null`: an `Option` becomes `None` and the
+/// other types fail with an error naming the full path. See [`nested_path!`](crate::nested_path)
+/// for defining the `deserialize_with` functions.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn author_name<'de, D>(deserializer: D) -> Result<String, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     deserialize_nested_path(deserializer, &["profile", "name"])
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(rename = "author", deserialize_with = "author_name")]
+///     author_name: String,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "author": { "profile": { "name": "x" } } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.author_name, "x");
+///
+///     let s = r#" { "author": { "id": 1 } } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"missing key "profile" of the path "profile.name""#));
+/// }
+/// ```
+pub fn deserialize_nested_path<'de, T, D>(deserializer: D, path: &[&str]) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    use serde::de::Error;
+
+    let full_path = || path.join(".");
+    let mut value = serde_json::Value::deserialize(deserializer)?;
+    for key in path {
+        match value.get_mut(*key) {
+            Some(inner) => value = inner.take(),
+            None => {
+                return T::deserialize(serde_json::Value::Null).map_err(|_| {
+                    D::Error::custom(format!(
+                        "missing key {:?} of the path {:?}",
+                        key,
+                        full_path()
+                    ))
+                })
+            }
+        }
+    }
+    T::deserialize(value)
+        .map_err(|e| D::Error::custom(format!("invalid value at {:?}: {}", full_path(), e)))
+}
+
+/// Defines a function deserializing a field from a path of the keys through the nested maps,
+/// see [`deserialize_nested_path`](crate::field_attributes::deserialize_nested_path). The path
+/// starts at the value of the field; with `#[serde(flatten)]` it starts at the containing
+/// struct, so several fields can be picked from the same nested object without modeling it.
+///
+/// # Example:
+///
+/// ```rust
+/// serde_aux::nested_path!(fn author_name(["author", "name"]) -> String);
+/// serde_aux::nested_path!(fn author_email(["author", "contact", "email"]) -> Option<String>);
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     title: String,
+///     #[serde(flatten, deserialize_with = "author_name")]
+///     author_name: String,
+///     #[serde(flatten, deserialize_with = "author_email")]
+///     author_email: Option<String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "title": "t", "author": { "name": "x", "contact": { "email": "y" } } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.title, "t");
+///     assert_eq!(a.author_name, "x");
+///     assert_eq!(a.author_email.as_deref(), Some("y"));
+///
+///     let s = r#" { "title": "t", "author": { "name": "x" } } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.author_email, None);
+///
+///     let s = r#" { "title": "t", "author": { "id": 1 } } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"missing key "name" of the path "author.name""#));
+/// }
+/// ```
+#[macro_export]
+macro_rules! nested_path {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident([$($key:expr),+ $(,)?]) -> $ty:ty) => {
+        $(#[$attr])*
+        $vis fn $name<'de, D>(deserializer: D) -> Result<$ty, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            $crate::field_attributes::deserialize_nested_path(deserializer, &[$($key),+])
+        }
+    };
+}
+
+/// Deserializes the value of the first of the `keys` which is present and not `null`, in the
+/// order of the `keys` (not of the input), like `["user_id", "uid"]` during a rename. The content
+/// is buffered into a `serde_json::Value` first, so a self-describing format is required. When
+/// none of the keys is set, the value is deserialized from `null`, so an `Option` becomes `None`,
+/// and the other types fail as a missing field named by the first key. See
+/// [`first_non_null!`](crate::first_non_null) for defining the `deserialize_with` functions.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn user_id<'de, D>(deserializer: D) -> Result<u64, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     deserialize_first_non_null(deserializer, &["user_id", "uid"])
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(flatten, deserialize_with = "user_id")]
+///     user_id: u64,
+/// }
+///
+/// fn main() {
+///     for s in &[
+///         r#" { "user_id": 1 } "#,
+///         r#" { "uid": 1 } "#,
+///         r#" { "uid": 1, "user_id": null } "#,
+///         r#" { "uid": 2, "user_id": 1 } "#,
+///     ] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(a.user_id, 1);
+///     }
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "uid": null } "#).unwrap_err();
+///     assert!(e.to_string().starts_with("missing field `user_id`"));
+/// }
+/// ```
+pub fn deserialize_first_non_null<'de, T, D>(
+    deserializer: D,
+    keys: &[&'static str],
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    use serde::de::Error;
+
+    let mut value = serde_json::Value::deserialize(deserializer)?;
+    for key in keys {
+        match value.get_mut(*key).map(serde_json::Value::take) {
+            Some(serde_json::Value::Null) | None => continue,
+            Some(inner) => {
+                return T::deserialize(inner)
+                    .map_err(|e| D::Error::custom(format!("invalid value at {:?}: {}", key, e)))
+            }
+        }
+    }
+    T::deserialize(serde_json::Value::Null).map_err(|_| match keys.first() {
+        Some(key) => D::Error::missing_field(key),
+        None => D::Error::custom("no keys to look the value up by"),
+    })
+}
+
+/// Defines a function deserializing a field from the first of the keys which is present and not
+/// `null`, see
+/// [`deserialize_first_non_null`](crate::field_attributes::deserialize_first_non_null). Use it
+/// with `#[serde(flatten)]`, so the keys are looked up in the containing struct.
+///
+/// # Example:
+///
+/// ```rust
+/// serde_aux::first_non_null!(fn user_id(["user_id", "uid"]) -> u64);
+/// serde_aux::first_non_null!(fn nickname(["nickname", "nick"]) -> Option<String>);
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     name: String,
+///     #[serde(flatten, deserialize_with = "user_id")]
+///     user_id: u64,
+///     #[serde(flatten, deserialize_with = "nickname")]
+///     nickname: Option<String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "name": "x", "user_id": null, "uid": 1, "nick": "y" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.name, "x");
+///     assert_eq!(a.user_id, 1);
+///     assert_eq!(a.nickname.as_deref(), Some("y"));
+///
+///     let s = r#" { "name": "x", "uid": 1 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.nickname, None);
+/// }
+/// ```
+#[macro_export]
+macro_rules! first_non_null {
+    ($(#[$attr:meta])* $vis:vis fn $name:ident([$($key:expr),+ $(,)?]) -> $ty:ty) => {
+        $(#[$attr])*
+        $vis fn $name<'de, D>(deserializer: D) -> Result<$ty, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            $crate::field_attributes::deserialize_first_non_null(deserializer, &[$($key),+])
+        }
+    };
+}
+
+/// Deserializes a `Vec` replacing the `null` elements with `T::default()`, so `[1, null, 3]`
+/// gives `[1, 0, 3]` and the positions of the other elements are kept. For the formats which
+/// are not self-describing, like `bincode`, see
+/// [`canonical_deserialize_with`](crate::canonical_deserialize_with).
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_vec_null_elements_as_default")]
+///     samples: Vec<u64>,
+///     #[serde(deserialize_with = "deserialize_vec_null_elements_as_default")]
+///     labels: Vec<String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "samples": [1, null, 3], "labels": [null, "b"] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.samples, vec![1, 0, 3]);
+///     assert_eq!(a.labels, vec!["", "b"]);
+///
+///     let s = r#" { "samples": [1, "2"], "labels": [] } "#;
+///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
+/// }
+/// ```
+pub fn deserialize_vec_null_elements_as_default<'de, T, D>(
+    deserializer: D,
+) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    struct NullElementsAsDefaultVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for NullElementsAsDefaultVisitor<T>
+    where
+        T: Deserialize<'de> + Default,
+    {
+        type Value = Vec<T>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a sequence")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Vec<T>, A::Error>
+        where
+            A: serde::de::SeqAccess<'de>,
+        {
+            let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+            while let Some(value) = seq.next_element::<Option<T>>()? {
+                values.push(value.unwrap_or_else(|| {
+                    crate::lenient::report(
+                        "deserialize_vec_null_elements_as_default",
+                        crate::lenient::LenientAction::Defaulted,
+                        || "null".to_owned(),
+                    );
+                    T::default()
+                }));
+            }
+            Ok(values)
+        }
+    }
+
+    deserializer.deserialize_seq(NullElementsAsDefaultVisitor(PhantomData))
+}
+
+/// Deserializes a `Vec` dropping the repeated elements: only the first occurrence of each value
+/// is kept, in the order they come. See [`StringOrVecToVec::into_dedup_deserializer`] for the
+/// strings with the separated values.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_vec_dedup")]
+///     tags: Vec<String>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "tags": ["b", "a", "b", "c", "a"] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(a.tags, vec!["b", "a", "c"]);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn deserialize_vec_dedup<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Eq + Hash,
+{
+    let mut values = Vec::<T>::deserialize(deserializer)?;
+    dedup_keep_first(&mut values);
+    Ok(values)
+}
+
+/// Removes the repeated elements keeping the first occurrences in their order.
+#[cfg(feature = "std")]
+fn dedup_keep_first<T: Eq + Hash>(values: &mut Vec<T>) {
+    let keep = {
+        let mut seen = HashSet::with_capacity(values.len());
+        values.iter().map(|v| seen.insert(v)).collect::<Vec<_>>()
+    };
+    let mut keep = keep.into_iter();
+    values.retain(|_| keep.next().unwrap_or(true));
+}
+
+/// Deserializes a `Vec` and sorts it, so the order of the elements does not depend on the
+/// input. See [`SortedVecByKey`] for sorting by a key.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_sorted_vec")]
+///     ids: Vec<u32>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "ids": [3, 1, 2, 1] } "#).unwrap();
+///     assert_eq!(a.ids, vec![1, 1, 2, 3]);
+/// }
+/// ```
+pub fn deserialize_sorted_vec<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Ord,
+{
+    let mut values = Vec::<T>::deserialize(deserializer)?;
+    values.sort();
+    Ok(values)
+}
+
+/// Builds a deserializer of a `Vec` sorted by a key extracted from the elements. The sort is
+/// stable, so the elements with equal keys keep their order.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct Item {
+///     name: String,
+///     priority: u8,
+/// }
+///
+/// fn by_priority<'de, D>(deserializer: D) -> Result<Vec<Item>, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     SortedVecByKey::new(|item: &Item| item.priority).into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "by_priority")]
+///     items: Vec<Item>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "items": [
+///         { "name": "a", "priority": 2 },
+///         { "name": "b", "priority": 1 },
+///         { "name": "c", "priority": 2 }
+///     ] } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     let names: Vec<&str> = a.items.iter().map(|item| item.name.as_str()).collect();
+///     assert_eq!(names, vec!["b", "a", "c"]);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SortedVecByKey<F> {
+    key: F,
+}
+
+impl<F> SortedVecByKey<F> {
+    /// Creates a builder sorting by the key the function gives for an element.
+    pub fn new(key: F) -> Self {
+        SortedVecByKey { key }
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, T, K, D>(mut self) -> impl FnMut(D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        move |deserializer| {
+            let mut values = Vec::<T>::deserialize(deserializer)?;
+            values.sort_by_key(&mut self.key);
+            Ok(values)
+        }
+    }
+}
+
+/// Deserializes an identifier-like string, a non-empty one made of the ASCII letters, the digits,
+/// `_` and `-` (as `[A-Za-z0-9_-]+`). The error names the first offending character and its
+/// index (counted in characters). See [`StringMatching`] for the other character sets, the
+/// patterns and stripping the invalid characters instead.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_string_matching")]
+///     id: String,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "id": "user_42-a" } "#).unwrap();
+///     assert_eq!(a.id, "user_42-a");
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "id": "user 42" } "#).unwrap_err();
+///     assert!(e.to_string().starts_with(r#"invalid character ' ' at index 4 in "user 42""#));
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "id": "" } "#).is_err());
+/// }
+/// ```
+pub fn deserialize_string_matching<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    StringMatching::new(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-').into_deserializer()(
+        deserializer,
+    )
+}
+
+/// Builds a deserializer of a string validated by a predicate of its characters or (with the
+/// `regex` feature) by a pattern. By default an empty string is an error and so is a string with
+/// a character the predicate rejects, the error names the first such character and its index
+/// (counted in characters).
+///
+/// - With `strip_invalid(true)` the rejected characters are removed instead; a string which
+///   becomes empty is still an error unless the empty strings are allowed.
+/// - A pattern is checked with `Regex::is_match`, so anchor it with `^` and `$` to match the whole
+///   string. Stripping does not apply to the patterns.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn digits<'de, D>(deserializer: D) -> Result<String, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     StringMatching::new(|c| c.is_ascii_digit())
+///         .strip_invalid(true)
+///         .into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "digits")]
+///     phone: String,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "phone": "+1 (555) 010-99" } "#).unwrap();
+///     assert_eq!(a.phone, "155501099");
+///
+///     assert!(serde_json::from_str::<MyStruct>(r#" { "phone": "n/a" } "#).is_err());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct StringMatching<'a> {
+    rule: StringRule<'a>,
+    strip_invalid: bool,
+    allow_empty: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum StringRule<'a> {
+    Predicate(fn(char) -> bool),
+    #[cfg(feature = "regex")]
+    Pattern(&'a regex::Regex),
+    #[cfg(not(feature = "regex"))]
+    #[allow(dead_code)]
+    Unused(PhantomData<&'a ()>),
+}
+
+impl<'a> StringMatching<'a> {
+    /// Creates a builder accepting the strings made of the characters the predicate accepts.
+    pub fn new(predicate: fn(char) -> bool) -> Self {
+        StringMatching {
+            rule: StringRule::Predicate(predicate),
+            strip_invalid: false,
+            allow_empty: false,
+        }
+    }
+
+    /// Creates a builder accepting the strings the pattern matches. Requires the `regex`
+    /// feature.
+    ///
+    /// # Example:
+    ///
+    /// ```rust
+    /// use serde_aux::prelude::*;
+    ///
+    /// fn version<'de, D>(deserializer: D) -> Result<String, D::Error>
+    /// where
+    ///     D: serde::Deserializer<'de>,
+    /// {
+    ///     let pattern = regex::Regex::new(r"^\d+\.\d+\.\d+$").unwrap();
+    ///     let mut deserialize = StringMatching::pattern(&pattern).into_deserializer();
+    ///     deserialize(deserializer)
+    /// }
+    ///
+    /// #[derive(serde::Deserialize, Debug)]
+    /// struct MyStruct {
+    ///     #[serde(deserialize_with = "version")]
+    ///     version: String,
+    /// }
+    ///
+    /// fn main() {
+    ///     let a: MyStruct = serde_json::from_str(r#" { "version": "1.2.3" } "#).unwrap();
+    ///     assert_eq!(a.version, "1.2.3");
+    ///
+    ///     let e = serde_json::from_str::<MyStruct>(r#" { "version": "1.2" } "#).unwrap_err();
+    ///     assert!(e.to_string().starts_with(r#""1.2" does not match the pattern"#));
+    /// }
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn pattern(pattern: &'a regex::Regex) -> Self {
+        StringMatching {
+            rule: StringRule::Pattern(pattern),
+            strip_invalid: false,
+            allow_empty: false,
+        }
+    }
+
+    /// Removes the characters the predicate rejects instead of failing.
+    pub fn strip_invalid(mut self, strip_invalid: bool) -> Self {
+        self.strip_invalid = strip_invalid;
+        self
+    }
+
+    /// Accepts the empty strings (before or after stripping).
+    pub fn allow_empty(mut self, allow_empty: bool) -> Self {
+        self.allow_empty = allow_empty;
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, D>(self) -> impl FnMut(D) -> Result<String, D::Error> + 'a
+    where
+        D: Deserializer<'de>,
+    {
+        move |deserializer| {
+            use serde::de::Error;
+
+            let value = String::deserialize(deserializer)?;
+            let value = self.check(value).map_err(D::Error::custom)?;
+            if value.is_empty() && !self.allow_empty {
+                return Err(D::Error::custom("expected a non-empty string"));
+            }
+            Ok(value)
+        }
+    }
+
+    fn check(&self, value: String) -> Result<String, String> {
+        match self.rule {
+            StringRule::Predicate(predicate) if self.strip_invalid => {
+                let stripped: String = value.chars().filter(|&c| predicate(c)).collect();
+                if stripped.len() != value.len() {
+                    crate::lenient::report(
+                        "StringMatching",
+                        crate::lenient::LenientAction::Coerced,
+                        || value,
+                    );
+                }
+                Ok(stripped)
+            }
+            StringRule::Predicate(predicate) => {
+                match value.chars().enumerate().find(|&(_, c)| !predicate(c)) {
+                    Some((index, c)) => Err(format!(
+                        "invalid character {:?} at index {} in {:?}",
+                        c, index, value
+                    )),
+                    None => Ok(value),
+                }
+            }
+            #[cfg(feature = "regex")]
+            StringRule::Pattern(pattern) if pattern.is_match(&value) => Ok(value),
+            #[cfg(feature = "regex")]
+            StringRule::Pattern(pattern) => Err(format!(
+                "{:?} does not match the pattern {:?}",
+                value,
+                pattern.as_str()
+            )),
+            #[cfg(not(feature = "regex"))]
+            StringRule::Unused(_) => Ok(value),
+        }
+    }
+}
+
+/// Builds a deserializer of a string limited to a number of bytes (of UTF-8), like for a storage
+/// with the column sizes in bytes, where a limit in characters doesn't help. A longer string is
+/// an error by default.
+///
+/// With `truncate(true)` a longer string is cut instead, at the last character boundary within
+/// the limit, so a multibyte character straddling it is dropped whole and the result can be a
+/// few bytes shorter than the limit, but is always a valid string.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// fn title<'de, D>(deserializer: D) -> Result<String, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     StringMaxBytes::new(5).truncate(true).into_deserializer()(deserializer)
+/// }
+///
+/// fn code<'de, D>(deserializer: D) -> Result<String, D::Error>
+/// where
+///     D: serde::Deserializer<'de>,
+/// {
+///     StringMaxBytes::new(4).into_deserializer()(deserializer)
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "title")]
+///     title: String,
+///     #[serde(deserialize_with = "code")]
+///     code: String,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "title": "Grün", "code": "ab" } "#).unwrap();
+///     assert_eq!(a.title, "Grün");
+///     // "ß" takes the 5th and the 6th bytes of 7.
+///     let a: MyStruct = serde_json::from_str(r#" { "title": "Grüße", "code": "ab" } "#).unwrap();
+///     assert_eq!(a.title, "Grü");
+///
+///     let e = serde_json::from_str::<MyStruct>(r#" { "title": "", "code": "abcde" } "#).unwrap_err();
+///     assert!(e.to_string().contains("5 bytes, the maximum is 4"));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct StringMaxBytes {
+    max: usize,
+    truncate: bool,
+}
+
+impl StringMaxBytes {
+    /// Creates a builder accepting the strings of at most `max` bytes.
+    pub fn new(max: usize) -> Self {
+        StringMaxBytes {
+            max,
+            truncate: false,
+        }
+    }
+
+    /// Cuts the longer strings on a character boundary instead of failing.
+    pub fn truncate(mut self, truncate: bool) -> Self {
+        self.truncate = truncate;
+        self
+    }
+
+    /// Creates the deserializer function, see the example above.
+    pub fn into_deserializer<'de, D>(self) -> impl FnMut(D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        move |deserializer| {
+            use serde::de::Error;
+
+            let mut value = String::deserialize(deserializer)?;
+            if value.len() <= self.max {
+                return Ok(value);
+            }
+            if !self.truncate {
+                return Err(D::Error::custom(format_args!(
+                    "string {:?} has {} bytes, the maximum is {}",
+                    value,
+                    value.len(),
+                    self.max
+                )));
+            }
+            let end = (0..=self.max)
+                .rev()
+                .find(|&i| value.is_char_boundary(i))
+                .unwrap_or(0);
+            crate::lenient::report(
+                "StringMaxBytes",
+                crate::lenient::LenientAction::Coerced,
+                || value.clone(),
+            );
+            value.truncate(end);
+            Ok(value)
+        }
+    }
+}
+
+/// Deserializes a value from a URL-encoded query string inside a string, like
+/// `"status=active&limit=10"`, as the fields of a struct (or the entries of a map). Requires the
+/// `querystring` feature.
+///
+/// - The keys and the values are percent-decoded and a `+` is a space, as in the HTML forms. A
+///   leading `?` is skipped.
+/// - The values are parsed into the numbers, the booleans (`true` or `false`) and the unit enum
+///   variants the target asks for.
+/// - A key repeated several times goes to a sequence field, like a `Vec`; for any other field it
+///   is an error naming the key. A single value goes to a sequence field as one element.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug, PartialEq)]
+/// struct Filter {
+///     status: String,
+///     limit: u32,
+///     #[serde(default)]
+///     tag: Vec<String>,
+/// }
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_from_query_string")]
+///     filter: Filter,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "filter": "status=active&limit=10&tag=a%20b&tag=c" } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(
+///         a.filter,
+///         Filter {
+///             status: "active".to_owned(),
+///             limit: 10,
+///             tag: vec!["a b".to_owned(), "c".to_owned()],
+///         }
+///     );
+///
+///     let s = r#" { "filter": "status=active&limit=10&limit=20" } "#;
+///     let e = serde_json::from_str::<MyStruct>(s).unwrap_err();
+///     assert!(e.to_string().contains(r#"duplicate key "limit""#));
+/// }
+/// ```
+#[cfg(feature = "querystring")]
+pub fn deserialize_from_query_string<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: serde::de::DeserializeOwned,
+    D: Deserializer<'de>,
+{
+    use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+    use serde::de::{Error as _, IntoDeserializer};
+
+    fn decode(s: &str) -> Result<String, Error> {
+        percent_encoding::percent_decode_str(&s.replace('+', " "))
+            .decode_utf8()
+            .map(|decoded| decoded.into_owned())
+            .map_err(|_| Error::custom(format_args!("invalid UTF-8 in {:?}", s)))
+    }
+
+    /// The values of a key, in the order of the query string.
+    struct QueryValue {
+        key: String,
+        values: Vec<String>,
+    }
+
+    impl QueryValue {
+        fn single(mut self) -> Result<String, Error> {
+            if self.values.len() > 1 {
+                return Err(Error::custom(format_args!(
+                    "duplicate key {:?}, expected a single value",
+                    self.key
+                )));
+            }
+            Ok(self.values.pop().unwrap_or_default())
+        }
+
+        fn parse<V>(self) -> Result<V, Error>
+        where
+            V: FromStr,
+            V::Err: Display,
+        {
+            let key = self.key.clone();
+            let value = self.single()?;
+            value.parse().map_err(|e| {
+                Error::custom(format_args!(
+                    "invalid value {:?} for key {:?}: {}",
+                    value, key, e
+                ))
+            })
+        }
+    }
+
+    macro_rules! deserialize_parsed {
+        ($($method:ident => $visit:ident,)*) => {
+            $(
+                fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                    visitor.$visit(self.parse()?)
+                }
+            )*
+        };
+    }
+
+    impl<'de> Deserializer<'de> for QueryValue {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_string(self.single()?)
+        }
+
+        deserialize_parsed! {
+            deserialize_bool => visit_bool,
+            deserialize_i8 => visit_i8,
+            deserialize_i16 => visit_i16,
+            deserialize_i32 => visit_i32,
+            deserialize_i64 => visit_i64,
+            deserialize_u8 => visit_u8,
+            deserialize_u16 => visit_u16,
+            deserialize_u32 => visit_u32,
+            deserialize_u64 => visit_u64,
+            deserialize_f32 => visit_f32,
+            deserialize_f64 => visit_f64,
+            deserialize_char => visit_char,
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_some(self)
+        }
+
+        fn deserialize_newtype_struct<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_newtype_struct(self)
+        }
+
+        fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            let key = self.key;
+            visitor.visit_seq(SeqDeserializer::new(self.values.into_iter().map(|value| {
+                QueryValue {
+                    key: key.clone(),
+                    values: alloc::vec![value],
+                }
+            })))
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            _variants: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Error> {
+            visitor.visit_enum(self.single()?.into_deserializer())
+        }
+
+        fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            visitor.visit_unit()
+        }
+
+        serde::forward_to_deserialize_any! {
+            i128 u128 str string bytes byte_buf unit unit_struct tuple tuple_struct map struct
+            identifier
+        }
+    }
+
+    impl<'de> IntoDeserializer<'de, Error> for QueryValue {
+        type Deserializer = Self;
+
+        fn into_deserializer(self) -> Self {
+            self
+        }
+    }
+
+    fn parse_query(query: &str) -> Result<Vec<(String, QueryValue)>, Error> {
+        let query = match query.as_bytes().split_first() {
+            Some((b'?', _)) => &query[1..],
+            _ => query,
+        };
+        let mut entries: Vec<(String, QueryValue)> = Vec::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = match pair.find('=') {
+                Some(i) => (&pair[..i], &pair[i + 1..]),
+                None => (pair, ""),
+            };
+            let (key, value) = (decode(key)?, decode(value)?);
+            match entries.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, entry)) => entry.values.push(value),
+                None => entries.push((
+                    key.clone(),
+                    QueryValue {
+                        key,
+                        values: alloc::vec![value],
+                    },
+                )),
+            }
+        }
+        Ok(entries)
+    }
+
+    let query = String::deserialize(deserializer)?;
+    parse_query(&query)
+        .and_then(|entries| T::deserialize(MapDeserializer::new(entries.into_iter())))
+        .map_err(|e| D::Error::custom(format_args!("invalid query string {:?}: {}", query, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use std::cell::{Cell, RefCell};
+    #[cfg(feature = "std")]
+    use std::sync::{Mutex, RwLock};
+    use std::{
+        borrow::ToOwned,
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+    #[derive(Debug, serde::Deserialize)]
+    struct MyStruct {
+        #[serde(
+            default,
+            deserialize_with = "deserialize_cell_option_number_from_string"
+        )]
+        cell: Cell<Option<f32>>,
+        #[serde(
+            default,
+            deserialize_with = "deserialize_ref_cell_option_number_from_string"
+        )]
+        ref_cell: RefCell<Option<f32>>,
+        #[cfg(feature = "std")]
+        #[serde(
+            default,
+            deserialize_with = "deserialize_mutex_option_number_from_string"
+        )]
+        mutex: Mutex<Option<f32>>,
+        #[cfg(feature = "std")]
+        #[serde(
+            default,
+            deserialize_with = "deserialize_rw_lock_option_number_from_string"
+        )]
+        rw_lock: RwLock<Option<f32>>,
+    }
+    macro_rules! serde_qs_eq {
+        ($s:literal, $result:expr) => {
+            let a: MyStruct = serde_qs::from_str($s).unwrap();
+            assert_eq!(a.cell, Cell::new($result));
+            assert_eq!(a.ref_cell, RefCell::new($result));
+            #[cfg(feature = "std")]
+            assert_eq!(*a.mutex.lock().unwrap(), $result);
+            #[cfg(feature = "std")]
+            assert_eq!(*a.rw_lock.read().unwrap(), $result);
+        };
+    }
+    macro_rules! serde_qs_err {
+        ($rest:literal) => {
+            assert!(serde_qs::from_str::<MyStruct>(concat!("cell", $rest)).is_err());
+            assert!(serde_qs::from_str::<MyStruct>(concat!("ref_cell", $rest)).is_err());
+            #[cfg(feature = "std")]
+            assert!(serde_qs::from_str::<MyStruct>(concat!("mutex", $rest)).is_err());
+            #[cfg(feature = "std")]
+            assert!(serde_qs::from_str::<MyStruct>(concat!("rw_lock", $rest)).is_err());
+        };
+    }
+    macro_rules! serde_json_eq {
+        ($s:literal, $result:expr) => {
+            let a: MyStruct = serde_json::from_str($s).unwrap();
+            assert_eq!(a.cell, Cell::new($result));
+            assert_eq!(a.ref_cell, RefCell::new($result));
+            #[cfg(feature = "std")]
+            assert_eq!(*a.mutex.lock().unwrap(), $result);
+            #[cfg(feature = "std")]
+            assert_eq!(*a.rw_lock.read().unwrap(), $result);
+        };
+    }
+    macro_rules! serde_json_err {
+        ($v:tt) => {
+            assert!(serde_json::from_str::<MyStruct>(r#" { "cell": $v } "#).is_err());
+            assert!(serde_json::from_str::<MyStruct>(r#" { "ref_cell": $v } "#).is_err());
+            #[cfg(feature = "std")]
+            assert!(serde_json::from_str::<MyStruct>(r#" { "mutex": $v } "#).is_err());
+            #[cfg(feature = "std")]
+            assert!(serde_json::from_str::<MyStruct>(r#" { "rw_lock": $v } "#).is_err());
+        };
+    }
+    #[test]
+    fn test_deserialize_wrap_option_number_from_string() {
+        serde_qs_eq!("cell=1&ref_cell=1&mutex=1&rw_lock=1", Some(1.0));
+        serde_qs_eq!("cell=-1&ref_cell=-1&mutex=-1&rw_lock=-1", Some(-1.0));
+        serde_qs_eq!("cell=0.1&ref_cell=0.1&mutex=0.1&rw_lock=0.1", Some(0.1));
+        serde_qs_eq!(
+            "cell=-0.1&ref_cell=-0.1&mutex=-0.1&rw_lock=-0.1",
+            Some(-0.1)
+        );
+        serde_qs_eq!("cell=&ref_cell=&mutex=&rw_lock=", None);
+        serde_qs_eq!("cell&ref_cell&mutex&rw_lock", None);
+
+        serde_qs_err!("=true");
+        serde_qs_err!("=a");
+        serde_qs_err!("[a]=");
+        serde_qs_err!("[]=");
+
+        serde_json_eq!(
+            r#" { "cell":"1","ref_cell":"1","mutex":"1","rw_lock":"1" } "#,
+            Some(1.0)
+        );
+        serde_json_eq!(
+            r#" { "cell":"-1","ref_cell":"-1","mutex":"-1","rw_lock":"-1" } "#,
+            Some(-1.0)
+        );
+        serde_json_eq!(
+            r#" { "cell":"0.1","ref_cell":"0.1","mutex":"0.1","rw_lock":"0.1" } "#,
+            Some(0.1)
+        );
+        serde_json_eq!(
+            r#" { "cell":"-0.1","ref_cell":"-0.1","mutex":"-0.1","rw_lock":"-0.1" } "#,
+            Some(-0.1)
+        );
+        serde_json_eq!(
+            r#" { "cell":1,"ref_cell":1,"mutex":1,"rw_lock":1 } "#,
+            Some(1.0)
+        );
+        serde_json_eq!(
+            r#" { "cell":-1,"ref_cell":-1,"mutex":-1,"rw_lock":-1 } "#,
+            Some(-1.0)
+        );
+        serde_json_eq!(
+            r#" { "cell":0.1,"ref_cell":0.1,"mutex":0.1,"rw_lock":0.1 } "#,
+            Some(0.1)
+        );
+        serde_json_eq!(
+            r#" { "cell":-0.1,"ref_cell":-0.1,"mutex":-0.1,"rw_lock":-0.1 } "#,
+            Some(-0.1)
+        );
+        serde_json_eq!(
+            r#" { "cell":"","ref_cell":"","mutex":"","rw_lock":"" } "#,
+            None
+        );
+        serde_json_eq!(
+            r#" { "cell":null,"ref_cell":null,"mutex":null,"rw_lock":null } "#,
+            None
+        );
+
+        serde_json_err!(true);
+        serde_json_err!("a");
+        serde_json_err!({});
+        serde_json_err!([]);
+    }
+
+    #[test]
+    fn test_deserialize_number_from_string_128_bit() {
+        use serde::de::IntoDeserializer;
+
+        #[derive(Debug, serde::Deserialize)]
+        struct Amounts {
+            #[serde(deserialize_with = "deserialize_number_from_string")]
+            unsigned: u128,
+            #[serde(deserialize_with = "deserialize_number_from_string")]
+            signed: i128,
+        }
+
+        let s = r#" { "unsigned": "18446744073709551616", "signed": "-18446744073709551616" } "#;
+        let a: Amounts = serde_json::from_str(s).unwrap();
+        assert_eq!(a.unsigned, u64::MAX as u128 + 1);
+        assert_eq!(a.signed, -(u64::MAX as i128) - 1);
+
+        let s = r#" { "unsigned": "340282366920938463463374607431768211456", "signed": 0 } "#;
+        assert!(serde_json::from_str::<Amounts>(s).is_err());
+
+        let raw: serde::de::value::U128Deserializer<serde::de::value::Error> =
+            (u128::MAX).into_deserializer();
+        assert_eq!(
+            deserialize_number_from_string::<u128, _>(raw).unwrap(),
+            u128::MAX
+        );
+        let raw: serde::de::value::I128Deserializer<serde::de::value::Error> =
+            (i128::MIN).into_deserializer();
+        assert_eq!(
+            deserialize_number_from_string::<i128, _>(raw).unwrap(),
+            i128::MIN
+        );
+    }
+
+    #[test]
+    fn test_non_self_describing_round_trip() {
+        crate::canonical_deserialize_with!(fn number(deserialize_number_from_string) -> u64);
+        crate::canonical_deserialize_with!(fn float(deserialize_number_from_string) -> f32);
+        crate::canonical_deserialize_with!(fn string(deserialize_string_from_number) -> String);
+        crate::canonical_deserialize_with!(fn boolean(deserialize_bool_from_anything) -> bool);
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct MyStruct {
+            #[serde(deserialize_with = "number")]
+            number: u64,
+            #[serde(deserialize_with = "float")]
+            float: f32,
+            #[serde(deserialize_with = "string")]
+            string: String,
+            #[serde(deserialize_with = "boolean")]
+            boolean: bool,
+        }
+
+        let value = MyStruct {
+            number: 42,
+            float: -0.5,
+            string: "foo".to_owned(),
+            boolean: true,
+        };
+        let bytes = bincode::serialize(&value).unwrap();
+        assert_eq!(bincode::deserialize::<MyStruct>(&bytes).unwrap(), value);
+
+        // The helpers themselves need a self-describing format.
+        #[derive(Debug, serde::Deserialize)]
+        struct Plain {
+            #[serde(deserialize_with = "deserialize_number_from_string")]
+            #[allow(dead_code)]
+            number: u64,
+        }
+        let bytes = bincode::serialize(&42u64).unwrap();
+        assert!(bincode::deserialize::<Plain>(&bytes).is_err());
+
+        // The generated functions still parse in the human-readable formats.
+        let s = r#" { "number": "42", "float": "-0.5", "string": 7, "boolean": "1" } "#;
+        let a: MyStruct = serde_json::from_str(s).unwrap();
+        assert_eq!(a.number, 42);
+        assert_eq!(a.string, "7");
+    }
+
+    #[test]
+    fn test_self_describing_binary_formats_parse_strings() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_number_from_string")]
+            number: u64,
+            #[serde(deserialize_with = "deserialize_option_number_from_string")]
+            option_number: Option<i32>,
+            #[serde(deserialize_with = "deserialize_string_from_number")]
+            string: String,
+            #[serde(deserialize_with = "deserialize_option_string_from_number")]
+            option_string: Option<String>,
+            #[serde(deserialize_with = "deserialize_bool_from_anything")]
+            boolean: bool,
+            #[serde(deserialize_with = "deserialize_number_from_si_string")]
+            si: u64,
+            #[serde(deserialize_with = "deserialize_bytes_size_from_string")]
+            size: u64,
+            #[serde(deserialize_with = "deserialize_ratio_from_string")]
+            ratio: f64,
+            #[serde(deserialize_with = "deserialize_number_from_underscored_string")]
+            underscored: u32,
+            #[serde(deserialize_with = "deserialize_vec_from_json_string_or_seq")]
+            list: Vec<u8>,
+            #[serde(deserialize_with = "deserialize_byte_vec_flexible")]
+            bytes: Vec<u8>,
+        }
+
+        let input = serde_json::json!({
+            "number": "123",
+            "option_number": "-4",
+            "string": 5,
+            "option_string": 6,
+            "boolean": "1",
+            "si": "2k",
+            "size": "1 KiB",
+            "ratio": "1/4",
+            "underscored": "1_000",
+            "list": "[1, 2]",
+            "bytes": "ab",
+        });
+        let expected = MyStruct {
+            number: 123,
+            option_number: Some(-4),
+            string: "5".to_owned(),
+            option_string: Some("6".to_owned()),
+            boolean: true,
+            si: 2000,
+            size: 1024,
+            ratio: 0.25,
+            underscored: 1000,
+            list: vec![1, 2],
+            bytes: b"ab".to_vec(),
+        };
+        let bytes = rmp_serde::to_vec_named(&input).unwrap();
+        assert_eq!(rmp_serde::from_slice::<MyStruct>(&bytes).unwrap(), expected);
+        let bytes = rmp_serde::to_vec(&input).unwrap();
+        assert_eq!(rmp_serde::from_slice::<MyStruct>(&bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_error_messages() {
+        #[allow(dead_code)]
+        #[derive(Debug, serde::Deserialize)]
+        struct MyStruct {
+            #[serde(default, deserialize_with = "deserialize_number_from_string")]
+            number: u64,
+            #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+            option_number: Option<u64>,
+            #[serde(default, deserialize_with = "deserialize_string_from_number")]
+            string: String,
+            #[serde(default, deserialize_with = "deserialize_bool_from_anything")]
+            boolean: bool,
+        }
+
+        fn error(s: &str) -> String {
+            let e = serde_json::from_str::<MyStruct>(s).unwrap_err().to_string();
+            e[..e.find(" at line").unwrap()].to_owned()
+        }
+
+        assert_eq!(
+            error(r#"{ "number": true }"#),
+            "invalid type: boolean `true`, expected a number or a numeric string"
+        );
+        assert_eq!(
+            error(r#"{ "number": "foo" }"#),
+            "invalid value: string \"foo\", expected a number or a numeric string: \
+             invalid digit found in string"
+        );
+        assert_eq!(
+            error(r#"{ "option_number": [] }"#),
+            "invalid type: sequence, expected a number, a numeric string or null"
+        );
+        assert_eq!(
+            error(r#"{ "option_number": "-1" }"#),
+            "invalid value: string \"-1\", expected a number or a numeric string: \
+             value -1 out of range for u64 (0..=18446744073709551615)"
+        );
+        assert_eq!(
+            error(r#"{ "string": {} }"#),
+            "invalid type: map, expected a string or a number"
+        );
+        assert_eq!(
+            error(r#"{ "boolean": null }"#),
+            "invalid type: null, expected a boolean, a number or a string"
+        );
+    }
+
+    #[test]
+    fn test_option_deserialize_with() {
+        crate::option_deserialize_with!(
+            fn option_string(deserialize_string_from_number) -> String
+        );
+        crate::option_deserialize_with!(
+            fn option_float(deserialize_number_from_string) -> f64
+        );
+
+        #[derive(Debug, serde::Deserialize)]
+        struct MyStruct {
+            #[serde(default, deserialize_with = "option_string")]
+            string: Option<String>,
+            #[serde(default, deserialize_with = "option_float")]
+            float: Option<f64>,
+        }
+
+        let a: MyStruct = serde_json::from_str(r#"{ "string": 13, "float": "0.5" }"#).unwrap();
+        assert_eq!(a.string.as_deref(), Some("13"));
+        assert_eq!(a.float, Some(0.5));
+
+        let a: MyStruct = serde_json::from_str(r#"{ "string": null, "float": null }"#).unwrap();
+        assert_eq!(a.string, None);
+        assert_eq!(a.float, None);
+
+        let a: MyStruct = serde_json::from_str("{}").unwrap();
+        assert_eq!(a.string, None);
+        assert_eq!(a.float, None);
+
+        assert!(serde_json::from_str::<MyStruct>(r#"{ "float": "a" }"#).is_err());
+        assert!(serde_json::from_str::<MyStruct>(r#"{ "string": [] }"#).is_err());
+    }
+
+    #[test]
+    fn test_vec_deserialize_with() {
+        crate::vec_deserialize_with!(
+            fn vec_bool(deserialize_bool_from_anything) -> bool
+        );
+        crate::vec_deserialize_with!(
+            fn vec_option_float(deserialize_option_number_from_string) -> Option<f32>
+        );
+
+        #[derive(Debug, serde::Deserialize)]
+        struct MyStruct {
+            #[serde(deserialize_with = "vec_bool")]
+            booleans: Vec<bool>,
+            #[serde(deserialize_with = "vec_option_float")]
+            floats: Vec<Option<f32>>,
+        }
+
+        let s = r#"{ "booleans": [1, "0", true, 0.0], "floats": ["0.5", null, "", 2] }"#;
+        let a: MyStruct = serde_json::from_str(s).unwrap();
+        assert_eq!(a.booleans, vec![true, false, true, false]);
+        assert_eq!(a.floats, vec![Some(0.5), None, None, Some(2.0)]);
+
+        let a: MyStruct = serde_json::from_str(r#"{ "booleans": [], "floats": [] }"#).unwrap();
+        assert!(a.booleans.is_empty());
+        assert!(a.floats.is_empty());
+
+        let s = r#"{ "booleans": [2], "floats": [] }"#;
+        assert!(serde_json::from_str::<MyStruct>(s).is_err());
+        let s = r#"{ "booleans": [], "floats": null }"#;
+        assert!(serde_json::from_str::<MyStruct>(s).is_err());
+    }
+
+    #[test]
+    fn test_map_values_deserialize_with() {
+        use alloc::collections::BTreeMap;
+
+        crate::map_values_deserialize_with!(
+            fn map_numbers(deserialize_number_from_string) -> BTreeMap<u8, i32>
+        );
+
+        #[derive(Debug, serde::Deserialize)]
+        struct MyStruct {
+            #[serde(deserialize_with = "map_numbers")]
+            numbers: BTreeMap<u8, i32>,
+        }
+
+        let s = r#"{ "numbers": { "2": "-2", "1": 1 } }"#;
+        let a: MyStruct = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            a.numbers.into_iter().collect::<Vec<_>>(),
+            vec![(1, 1), (2, -2)]
+        );
+
+        let s = r#"{ "numbers": { "1": "one" } }"#;
+        let e = serde_json::from_str::<MyStruct>(s).unwrap_err().to_string();
+        assert!(e.starts_with("invalid value for key 1: invalid value: string \"one\""));
+
+        let s = r#"{ "numbers": { "300": 1 } }"#;
+        assert!(serde_json::from_str::<MyStruct>(s).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_deserialize_datetime_utc_from_milliseconds_before_epoch() {
+        #[derive(Debug, serde::Deserialize)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_datetime_utc_from_milliseconds")]
+            time: chrono::DateTime<chrono::Utc>,
+        }
+
+        let a: MyStruct = serde_json::from_str(r#"{ "time": -1500 }"#).unwrap();
+        assert_eq!(a.time.timestamp(), -2);
+        assert_eq!(a.time.timestamp_subsec_millis(), 500);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_deserialize_datetime_utc_flexible_precision_guess() {
+        use chrono::prelude::*;
+
+        #[derive(Debug, serde::Deserialize)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_datetime_utc_flexible")]
+            time: DateTime<Utc>,
+        }
+
+        let expected = Utc.timestamp_opt(1672628645, 123_456_789).unwrap();
+        let a: MyStruct = serde_json::from_str(r#"{ "time": 1672628645123456789 }"#).unwrap();
+        assert_eq!(a.time, expected);
+        let a: MyStruct = serde_json::from_str(r#"{ "time": 1672628645123456 }"#).unwrap();
+        assert_eq!(a.time, expected.with_nanosecond(123_456_000).unwrap());
+        let a: MyStruct = serde_json::from_str(r#"{ "time": "-86400" }"#).unwrap();
+        assert_eq!(a.time, Utc.with_ymd_and_hms(1969, 12, 31, 0, 0, 0).unwrap());
+
+        let e = serde_json::from_str::<MyStruct>(r#"{ "time": "2023-13-01" }"#).unwrap_err();
+        assert!(e
+            .to_string()
+            .contains("tried RFC 3339, RFC 2822 and an epoch time stamp"));
+        assert!(serde_json::from_str::<MyStruct>(r#"{ "time": true }"#).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_string_lossy_from_message_pack() {
+        use serde::de::value::{BorrowedBytesDeserializer, Error};
+
+        #[derive(Debug, serde::Deserialize)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_string_lossy")]
+            name: String,
+        }
+
+        // {"name": bin8 [0x66, 0xff, 0x6f]}
+        let bytes = b"\x81\xa4name\xc4\x03\x66\xff\x6f";
+        let a: MyStruct = rmp_serde::from_slice(bytes).unwrap();
+        assert_eq!(a.name, "f\u{fffd}o");
+
+        // {"name": str8 "foo"}
+        let bytes = b"\x81\xa4name\xa3foo";
+        let a: MyStruct = rmp_serde::from_slice(bytes).unwrap();
+        assert_eq!(a.name, "foo");
+
+        let deserializer = BorrowedBytesDeserializer::<Error>::new(b"ok\xc3\x28");
+        assert_eq!(
+            deserialize_string_lossy(deserializer).unwrap(),
+            "ok\u{fffd}("
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_deserialize_os_string_from_message_pack() {
+        use std::ffi::OsString;
+
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_os_string_flexible")]
+            name: OsString,
+        }
+
+        // {"name": bin8 [0x66, 0x6f]}
+        let bytes = b"\x81\xa4name\xc4\x02\x66\x6f";
+        let a: MyStruct = rmp_serde::from_slice(bytes).unwrap();
+        assert_eq!(a.name, "fo");
+
+        // {"name": bin8 [0x66, 0xff]}
+        let bytes = b"\x81\xa4name\xc4\x02\x66\xff";
+        let result = rmp_serde::from_slice::<MyStruct>(bytes);
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+            assert_eq!(result.unwrap().name.as_bytes(), b"f\xff");
+        }
+        #[cfg(not(unix))]
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_shift_decimal_point() {
+        use super::shift_decimal_point;
+
+        assert_eq!(shift_decimal_point("1.55", 3).unwrap(), "1550");
+        assert_eq!(shift_decimal_point("1.5555", 3).unwrap(), "1555.5");
+        assert_eq!(shift_decimal_point("-0.0012", 3).unwrap(), "-1.2");
+        assert_eq!(shift_decimal_point("+.5", 6).unwrap(), "500000");
+        assert_eq!(shift_decimal_point("007", 0).unwrap(), "7");
+        assert_eq!(shift_decimal_point("2.000", 3).unwrap(), "2000");
+        assert!(shift_decimal_point("", 3).is_none());
+        assert!(shift_decimal_point(".", 3).is_none());
+        assert!(shift_decimal_point("1e3", 3).is_none());
+        assert!(shift_decimal_point("1.2.3", 3).is_none());
+    }
+
+    #[test]
+    fn test_map_helpers_preserve_index_map_order() {
+        use indexmap::IndexMap;
+
+        crate::map_values_deserialize_with!(
+            fn deserialize_flags(deserialize_bool_from_anything) -> IndexMap<String, bool>
+        );
+
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_map_drop_nulls")]
+            metadata: IndexMap<String, u8>,
+            #[serde(deserialize_with = "deserialize_map_from_kv_string")]
+            weights: IndexMap<String, u8>,
+            #[serde(deserialize_with = "deserialize_flags")]
+            flags: IndexMap<String, bool>,
+        }
+
+        let s = r#" {
+            "metadata": { "z": 1, "a": null, "m": 2, "b": 3 },
+            "weights": "z=1;a=2;m=3",
+            "flags": { "z": 1, "a": "false", "m": true }
+        } "#;
+        let a: MyStruct = serde_json::from_str(s).unwrap();
+        assert_eq!(a.metadata.keys().collect::<Vec<_>>(), vec!["z", "m", "b"]);
+        assert_eq!(a.weights.keys().collect::<Vec<_>>(), vec!["z", "a", "m"]);
+        assert_eq!(a.flags.keys().collect::<Vec<_>>(), vec!["z", "a", "m"]);
+        assert_eq!(a.metadata.values().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    }
+
+    #[test]
+    fn test_deserialize_option_finite_f64_from_message_pack() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_option_finite_f64")]
+            value: Option<f64>,
+        }
+
+        for &(value, expected) in &[
+            (Some(f64::NAN), None),
+            (Some(f64::INFINITY), None),
+            (Some(f64::NEG_INFINITY), None),
+            (Some(1.5), Some(1.5)),
+            (None, None),
+        ] {
+            let bytes = rmp_serde::to_vec(&MyStruct { value }).unwrap();
+            let a: MyStruct = rmp_serde::from_slice(&bytes).unwrap();
+            assert_eq!(a.value, expected);
+        }
+
+        for &(value, expected) in &[
+            ("NaN", None),
+            ("-Infinity", None),
+            ("inf", None),
+            ("", None),
+            ("-1.5", Some(-1.5)),
+        ] {
+            let bytes = rmp_serde::to_vec(&(value,)).unwrap();
+            let a: MyStruct = rmp_serde::from_slice(&bytes).unwrap();
+            assert_eq!(a.value, expected, "{:?}", value);
+        }
+        let bytes = rmp_serde::to_vec(&("hot",)).unwrap();
+        assert!(rmp_serde::from_slice::<MyStruct>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_finite_float_from_message_pack() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_finite_float")]
+            value: f64,
+        }
+
+        let bytes = rmp_serde::to_vec(&MyStruct { value: 1.5 }).unwrap();
+        assert_eq!(
+            rmp_serde::from_slice::<MyStruct>(&bytes).unwrap().value,
+            1.5
+        );
+
+        let bytes = rmp_serde::to_vec(&MyStruct { value: f64::NAN }).unwrap();
+        let e = rmp_serde::from_slice::<MyStruct>(&bytes).unwrap_err();
+        assert!(e
+            .to_string()
+            .contains("invalid value: NaN, expected a finite number"));
+
+        let bytes = rmp_serde::to_vec(&MyStruct {
+            value: f64::INFINITY,
+        })
+        .unwrap();
+        let e = rmp_serde::from_slice::<MyStruct>(&bytes).unwrap_err();
+        assert!(e
+            .to_string()
+            .contains("invalid value: inf, expected a finite number"));
+    }
+
+    #[test]
+    fn test_serialize_float_nan_as_null_round_trip() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(
+                serialize_with = "serialize_option_float_nan_as_null",
+                deserialize_with = "deserialize_option_finite_f64"
+            )]
+            value: Option<f64>,
+        }
+
+        let values = [
+            (Some(1.5), Some(1.5)),
+            (Some(f64::NAN), None),
+            (Some(f64::INFINITY), None),
+            (Some(f64::NEG_INFINITY), None),
+            (None, None),
+        ];
+        for &(value, expected) in &values {
+            let a = MyStruct { value };
+            let s = serde_json::to_string(&a).unwrap();
+            assert_eq!(
+                serde_json::from_str::<MyStruct>(&s).unwrap().value,
+                expected
+            );
+            let bytes = rmp_serde::to_vec(&a).unwrap();
+            assert_eq!(
+                rmp_serde::from_slice::<MyStruct>(&bytes).unwrap().value,
+                expected
+            );
+            let s = serde_json::to_string(&MyStruct { value: expected }).unwrap();
+            assert_eq!(
+                serde_json::from_str::<MyStruct>(&s).unwrap().value,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_remove_digit_separators() {
+        use super::remove_digit_separators;
+
+        assert_eq!(remove_digit_separators("1_000").as_deref(), Some("1000"));
+        assert_eq!(
+            remove_digit_separators("-1_0.0_1").as_deref(),
+            Some("-10.01")
+        );
+        assert_eq!(remove_digit_separators("1e1_0").as_deref(), Some("1e10"));
+        assert_eq!(remove_digit_separators("42").as_deref(), Some("42"));
+        for number in &["_1", "1_", "1__0", "1_.0", "1._0", "-_1", "_", "1_e5"] {
+            assert_eq!(remove_digit_separators(number), None, "{}", number);
+        }
+    }
+
+    #[test]
+    fn test_numeric_strings_with_leading_plus() {
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_number_from_string")]
+            unsigned: u64,
+            #[serde(deserialize_with = "deserialize_option_number_from_string")]
+            signed: Option<i32>,
+            #[serde(deserialize_with = "deserialize_number_from_locale_string")]
+            locale: f64,
+            #[serde(deserialize_with = "deserialize_number_from_si_string")]
+            si: u32,
+            #[serde(deserialize_with = "deserialize_number_from_underscored_string")]
+            underscored: u16,
+        }
+
+        let s = r#" { "unsigned": "+42", "signed": "+7", "locale": "+1,5", "si": "+1.5k", "underscored": "+1_000" } "#;
+        let a: MyStruct = serde_json::from_str(s).unwrap();
+        assert_eq!(a.unsigned, 42);
+        assert_eq!(a.signed, Some(7));
+        assert_eq!(a.locale, 1.5);
+        assert_eq!(a.si, 1500);
+        assert_eq!(a.underscored, 1000);
+
+        let fields = ["unsigned", "signed", "locale", "si", "underscored"];
+        for number in &["++42", "+-42", "-+42", "+"] {
+            for field in &fields {
+                let mut value = serde_json::json!({});
+                for other in &fields {
+                    value[*other] = serde_json::json!("1");
+                }
+                value[*field] = serde_json::json!(number);
+                let result = serde_json::from_value::<MyStruct>(value);
+                assert!(result.is_err(), "{} {}", field, number);
+            }
+        }
+    }
+
+    #[test]
+    fn test_numeric_string_out_of_range_errors() {
+        #[derive(serde::Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct MyStruct {
+            #[serde(default, deserialize_with = "deserialize_number_from_string")]
+            small: u8,
+            #[serde(default, deserialize_with = "deserialize_option_number_from_string")]
+            signed: Option<i16>,
+            #[serde(default, deserialize_with = "deserialize_number_from_si_string")]
+            si: u32,
+            #[serde(default, deserialize_with = "deserialize_number_from_string")]
+            float: f32,
+            #[serde(default, deserialize_with = "deserialize_number_from_string")]
+            wide: i128,
+            #[serde(default, deserialize_with = "deserialize_number_from_string")]
+            wrapped: Wrapped,
+        }
+
+        #[derive(serde::Deserialize, Debug, Default)]
+        #[allow(dead_code)]
+        struct Wrapped(u8);
+
+        impl core::str::FromStr for Wrapped {
+            type Err = core::num::ParseIntError;
+
+            fn from_str(s: &str) -> Result<Wrapped, Self::Err> {
+                s.parse().map(Wrapped)
+            }
+        }
+
+        let error = |s: &str| serde_json::from_str::<MyStruct>(s).unwrap_err().to_string();
+
+        let e = error(r#" { "small": "300" } "#);
+        assert!(
+            e.contains(": value 300 out of range for u8 (0..=255)"),
+            "{}",
+            e
+        );
+        let e = error(r#" { "small": "-1" } "#);
+        assert!(
+            e.contains(": value -1 out of range for u8 (0..=255)"),
+            "{}",
+            e
+        );
+        let e = error(r#" { "small": "99999999999999999999999999999999999999999" } "#);
+        assert!(e.contains("out of range for u8 (0..=255)"), "{}", e);
+        let e = error(r#" { "signed": "-40000" } "#);
+        assert!(
+            e.contains(": value -40000 out of range for i16 (-32768..=32767)"),
+            "{}",
+            e
+        );
+        let e = error(r#" { "si": "5G" } "#);
+        assert!(
+            e.contains(": value 5000000000 out of range for u32 (0..=4294967295)"),
+            "{}",
+            e
+        );
+        let e = error(r#" { "wide": "-170141183460469231731687303715884105729" } "#);
+        assert!(e.contains("out of range for i128 (-1701"), "{}", e);
+
+        for s in &[
+            r#" { "small": "abc" } "#,
+            r#" { "small": "1.5" } "#,
+            r#" { "small": "-" } "#,
+            r#" { "float": "x" } "#,
+            r#" { "wrapped": "300" } "#,
+        ] {
+            let e = error(s);
+            assert!(!e.contains("out of range"), "{}", e);
+        }
     }
-    macro_rules! serde_qs_eq {
-        ($s:literal, $result:expr) => {
-            let a: MyStruct = serde_qs::from_str($s).unwrap();
-            assert_eq!(a.cell, Cell::new($result));
-            assert_eq!(a.ref_cell, RefCell::new($result));
-            assert_eq!(*a.mutex.lock().unwrap(), $result);
-            assert_eq!(*a.rw_lock.read().unwrap(), $result);
+
+    #[test]
+    fn test_deserialize_byte_vec_flexible_from_binary_formats() {
+        #[derive(serde::Serialize)]
+        struct Bytes<'a> {
+            #[serde(with = "serde_bytes_like")]
+            payload: &'a [u8],
+        }
+
+        mod serde_bytes_like {
+            pub fn serialize<S: serde::Serializer>(v: &[u8], s: S) -> Result<S::Ok, S::Error> {
+                s.serialize_bytes(v)
+            }
+        }
+
+        #[derive(serde::Serialize)]
+        struct Numbers {
+            payload: Vec<u16>,
+        }
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_byte_vec_flexible")]
+            payload: Vec<u8>,
+        }
+
+        let bytes = rmp_serde::to_vec(&Bytes {
+            payload: b"\x00\xff",
+        })
+        .unwrap();
+        let a = rmp_serde::from_slice::<MyStruct>(&bytes).unwrap();
+        assert_eq!(a.payload, vec![0, 255]);
+
+        let bytes = rmp_serde::to_vec(&Numbers {
+            payload: vec![1, 2],
+        })
+        .unwrap();
+        let a = rmp_serde::from_slice::<MyStruct>(&bytes).unwrap();
+        assert_eq!(a.payload, vec![1, 2]);
+
+        let bytes = rmp_serde::to_vec(&Numbers {
+            payload: vec![1, 300],
+        })
+        .unwrap();
+        let e = rmp_serde::from_slice::<MyStruct>(&bytes).unwrap_err();
+        assert!(
+            e.to_string().contains("invalid byte 300 at index 1"),
+            "{}",
+            e
+        );
+
+        crate::canonical_deserialize_with!(fn payload(deserialize_byte_vec_flexible) -> Vec<u8>);
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Canonical {
+            #[serde(deserialize_with = "payload")]
+            payload: Vec<u8>,
+        }
+
+        let a = Canonical {
+            payload: vec![7, 8, 9],
         };
+        let bytes = bincode::serialize(&a).unwrap();
+        assert_eq!(bincode::deserialize::<Canonical>(&bytes).unwrap(), a);
     }
-    macro_rules! serde_qs_err {
-        ($rest:literal) => {
-            assert!(serde_qs::from_str::<MyStruct>(concat!("cell", $rest)).is_err());
-            assert!(serde_qs::from_str::<MyStruct>(concat!("ref_cell", $rest)).is_err());
-            assert!(serde_qs::from_str::<MyStruct>(concat!("mutex", $rest)).is_err());
-            assert!(serde_qs::from_str::<MyStruct>(concat!("rw_lock", $rest)).is_err());
-        };
+
+    #[test]
+    fn test_derive_variants_signatures() {
+        crate::derive_variants!(
+            deserialize_bool_from_anything: bool => option as option_bool, vec as vec_bool,
+        );
+        crate::derive_variants!(
+            crate::field_attributes::deserialize_string_from_number: String
+                => vec as vec_string, option as option_string
+        );
+
+        let _: fn(serde_json::Value) -> Result<Option<bool>, serde_json::Error> = option_bool;
+        let _: fn(serde_json::Value) -> Result<Vec<bool>, serde_json::Error> = vec_bool;
+        let _: fn(serde_json::Value) -> Result<Option<String>, serde_json::Error> = option_string;
+        let _: fn(serde_json::Value) -> Result<Vec<String>, serde_json::Error> = vec_string;
+
+        let value = serde_json::json!([1, "false", true]);
+        assert_eq!(vec_bool(value).unwrap(), vec![true, false, true]);
+        assert_eq!(option_bool(serde_json::Value::Null).unwrap(), None);
+        assert_eq!(
+            option_string(serde_json::json!(7)).unwrap().as_deref(),
+            Some("7")
+        );
+        assert_eq!(
+            vec_string(serde_json::json!([1, "a"])).unwrap(),
+            vec!["1", "a"]
+        );
     }
-    macro_rules! serde_json_eq {
-        ($s:literal, $result:expr) => {
-            let a: MyStruct = serde_json::from_str($s).unwrap();
-            assert_eq!(a.cell, Cell::new($result));
-            assert_eq!(a.ref_cell, RefCell::new($result));
-            assert_eq!(*a.mutex.lock().unwrap(), $result);
-            assert_eq!(*a.rw_lock.read().unwrap(), $result);
-        };
+
+    fn none_if<'a>(builder: NoneIf<'a>, json: &str) -> Result<Option<i32>, serde_json::Error> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        builder.into_deserializer()(&mut deserializer)
     }
-    macro_rules! serde_json_err {
-        ($v:tt) => {
-            assert!(serde_json::from_str::<MyStruct>(r#" { "cell": $v } "#).is_err());
-            assert!(serde_json::from_str::<MyStruct>(r#" { "ref_cell": $v } "#).is_err());
-            assert!(serde_json::from_str::<MyStruct>(r#" { "mutex": $v } "#).is_err());
-            assert!(serde_json::from_str::<MyStruct>(r#" { "rw_lock": $v } "#).is_err());
-        };
+
+    #[test]
+    fn test_none_if_null_only() {
+        assert_eq!(none_if(NoneIf::new(), "null").unwrap(), None);
+        assert_eq!(none_if(NoneIf::new(), "0").unwrap(), Some(0));
+        assert_eq!(none_if(NoneIf::new(), r#""7""#).unwrap(), Some(7));
+        assert!(none_if(NoneIf::new(), r#""""#).is_err());
     }
+
     #[test]
-    fn test_deserialize_wrap_option_number_from_string() {
-        serde_qs_eq!("cell=1&ref_cell=1&mutex=1&rw_lock=1", Some(1.0));
-        serde_qs_eq!("cell=-1&ref_cell=-1&mutex=-1&rw_lock=-1", Some(-1.0));
-        serde_qs_eq!("cell=0.1&ref_cell=0.1&mutex=0.1&rw_lock=0.1", Some(0.1));
-        serde_qs_eq!(
-            "cell=-0.1&ref_cell=-0.1&mutex=-0.1&rw_lock=-0.1",
-            Some(-0.1)
+    fn test_none_if_empty_string() {
+        assert_eq!(none_if(NoneIf::empty_string(), r#""""#).unwrap(), None);
+        assert_eq!(none_if(NoneIf::empty_string(), r#"" \t""#).unwrap(), None);
+        assert_eq!(none_if(NoneIf::empty_string(), "0").unwrap(), Some(0));
+        assert!(none_if(NoneIf::empty_string(), r#""N/A""#).is_err());
+    }
+
+    #[test]
+    fn test_none_if_zero() {
+        assert_eq!(none_if(NoneIf::zero(), "0").unwrap(), None);
+        assert_eq!(none_if(NoneIf::zero(), r#""0""#).unwrap(), None);
+        assert_eq!(none_if(NoneIf::zero(), r#"" -0.00 ""#).unwrap(), None);
+        assert_eq!(none_if(NoneIf::zero(), "-1").unwrap(), Some(-1));
+        assert!(none_if(NoneIf::zero(), r#""""#).is_err());
+
+        let mut deserializer = serde_json::Deserializer::from_str("0.0");
+        let value: Option<f64> = NoneIf::zero().into_deserializer()(&mut deserializer).unwrap();
+        assert_eq!(value, None);
+        let mut deserializer = serde_json::Deserializer::from_str(r#""0e10""#);
+        let value: Option<f64> = NoneIf::zero().into_deserializer()(&mut deserializer).unwrap();
+        assert_eq!(value, None);
+
+        assert!(none_if(NoneIf::zero(), r#""1e-400""#).is_err());
+        let mut deserializer = serde_json::Deserializer::from_str(r#""1e-400""#);
+        let value: Option<f64> = NoneIf::zero().into_deserializer()(&mut deserializer).unwrap();
+        assert_eq!(value, Some(0.0));
+    }
+
+    #[test]
+    fn test_none_if_token() {
+        let builder = NoneIf::token("N/A").or_token("none");
+        assert_eq!(none_if(builder.clone(), r#""N/A""#).unwrap(), None);
+        assert_eq!(none_if(builder.clone(), r#"" n/a ""#).unwrap(), None);
+        assert_eq!(none_if(builder.clone(), r#""NONE""#).unwrap(), None);
+        assert_eq!(none_if(builder.clone(), "0").unwrap(), Some(0));
+        assert!(none_if(builder, r#""NA""#).is_err());
+    }
+
+    #[test]
+    fn test_coerce_stages() {
+        fn coerce<T>(builder: Coerce<T>, json: &str) -> Result<T, serde_json::Error>
+        where
+            T: std::str::FromStr + serde::de::DeserializeOwned + Default + PartialOrd + Clone,
+            <T as std::str::FromStr>::Err: std::fmt::Display,
+        {
+            let mut deserializer = serde_json::Deserializer::from_str(json);
+            let value = builder.into_deserializer()(&mut deserializer);
+            value
+        }
+
+        assert!(coerce(Coerce::<u32>::new(), r#""7""#).is_err());
+        assert_eq!(coerce(Coerce::<u32>::new(), "7").unwrap(), 7);
+        assert_eq!(
+            coerce(Coerce::<u32>::new().from_string_or_number(), r#""7""#).unwrap(),
+            7
         );
-        serde_qs_eq!("cell=&ref_cell=&mutex=&rw_lock=", None);
-        serde_qs_eq!("cell&ref_cell&mutex&rw_lock", None);
+        assert!(coerce(Coerce::<u32>::new().from_string_or_number(), r#"" 7""#).is_err());
+        assert_eq!(
+            coerce(
+                Coerce::<u32>::new().trim().from_string_or_number(),
+                r#"" 7""#
+            )
+            .unwrap(),
+            7
+        );
+        assert!(coerce(Coerce::<u32>::new().from_string_or_number(), r#""""#).is_err());
+        assert_eq!(
+            coerce(Coerce::<u32>::new().empty_as_default(), r#""""#).unwrap(),
+            0
+        );
+        assert_eq!(
+            coerce(Coerce::<String>::new().trim(), r#"" x ""#).unwrap(),
+            "x"
+        );
+        assert_eq!(
+            coerce(Coerce::new().clamp(-1.0, 1.0), "-2.5").unwrap(),
+            -1.0
+        );
+        assert_eq!(coerce(Coerce::new().clamp(-1.0, 1.0), "0.5").unwrap(), 0.5);
+    }
 
-        serde_qs_err!("=true");
-        serde_qs_err!("=a");
-        serde_qs_err!("[a]=");
-        serde_qs_err!("[]=");
+    #[test]
+    fn test_option_vec_elements_as_strings() {
+        crate::option_deserialize_with!(
+            fn deserialize_option_ids(deserialize_vec_elements_from_strings) -> Vec<u64>
+        );
 
-        serde_json_eq!(
-            r#" { "cell":"1","ref_cell":"1","mutex":"1","rw_lock":"1" } "#,
-            Some(1.0)
+        fn serialize_option_ids<S>(ids: &Option<Vec<u64>>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            match ids {
+                Some(ids) => serializer.serialize_some(&AsStrings(ids)),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct MyStruct {
+            #[serde(
+                default,
+                deserialize_with = "deserialize_option_ids",
+                serialize_with = "serialize_option_ids"
+            )]
+            ids: Option<Vec<u64>>,
+        }
+
+        for a in &[
+            MyStruct {
+                ids: Some(vec![9_007_199_254_740_993, 0]),
+            },
+            MyStruct { ids: None },
+        ] {
+            let s = serde_json::to_string(a).unwrap();
+            assert_eq!(&serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+        }
+        assert_eq!(
+            serde_json::to_string(&MyStruct {
+                ids: Some(vec![9_007_199_254_740_993])
+            })
+            .unwrap(),
+            r#"{"ids":["9007199254740993"]}"#
         );
-        serde_json_eq!(
-            r#" { "cell":"-1","ref_cell":"-1","mutex":"-1","rw_lock":"-1" } "#,
-            Some(-1.0)
+        assert_eq!(
+            serde_json::from_str::<MyStruct>("{}").unwrap(),
+            MyStruct { ids: None }
         );
-        serde_json_eq!(
-            r#" { "cell":"0.1","ref_cell":"0.1","mutex":"0.1","rw_lock":"0.1" } "#,
-            Some(0.1)
+    }
+
+    struct AsStrings<'a>(&'a [u64]);
+
+    impl<'a> serde::Serialize for AsStrings<'a> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serialize_vec_elements_as_strings(self.0, serializer)
+        }
+    }
+
+    #[test]
+    fn test_normalize_decimal_huge_exponents() {
+        use super::{is_decimal_zero, normalize_decimal};
+
+        assert_eq!(normalize_decimal("1e9223372036854775807"), None);
+        assert_eq!(normalize_decimal("0.01e-9223372036854775808"), None);
+        assert_eq!(
+            normalize_decimal("1e-9223372036854775808"),
+            Some((false, "1".to_owned(), i64::MIN + 1))
         );
-        serde_json_eq!(
-            r#" { "cell":"-0.1","ref_cell":"-0.1","mutex":"-0.1","rw_lock":"-0.1" } "#,
-            Some(-0.1)
+        assert_eq!(normalize_decimal("1e99999999999999999999"), None);
+        assert_eq!(normalize_decimal("1e"), None);
+        assert_eq!(
+            normalize_decimal("0e99999999999999999999"),
+            Some((false, String::new(), 0))
         );
-        serde_json_eq!(
-            r#" { "cell":1,"ref_cell":1,"mutex":1,"rw_lock":1 } "#,
-            Some(1.0)
+
+        assert!(!is_decimal_zero("1e9223372036854775807"));
+        assert!(!is_decimal_zero("1e-9223372036854775808"));
+        assert!(is_decimal_zero("0e99999999999999999999"));
+
+        let mut deserializer = serde_json::Deserializer::from_str(r#""0e99999999999999999999""#);
+        let value: Option<f64> = NoneIf::zero().into_deserializer()(&mut deserializer).unwrap();
+        assert_eq!(value, None);
+
+        #[derive(serde::Deserialize, Debug)]
+        struct Strict {
+            #[serde(deserialize_with = "deserialize_integer_strict")]
+            #[allow(dead_code)]
+            quantity: i64,
+        }
+        for s in &[
+            "1e9223372036854775807",
+            "1e-9223372036854775808",
+            "0e99999999999999999999",
+        ] {
+            let json = format!(r#"{{ "quantity": "{}" }}"#, s);
+            assert!(serde_json::from_str::<Strict>(&json).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_is_exact_f64_around_2_pow_53() {
+        use super::is_exact_f64;
+
+        for &number in &[
+            "9007199254740991",
+            "9007199254740992",
+            "9007199254740994",
+            "9007199254740992.0",
+            "-9007199254740992",
+            "0.1",
+            "1.50",
+            "1e3",
+            "-0.0",
+            "123.456e-2",
+        ] {
+            assert!(
+                is_exact_f64(number, number.parse().unwrap()),
+                "{} must be exact",
+                number
+            );
+        }
+        for &number in &[
+            "9007199254740993",
+            "9007199254740993.0",
+            "-9007199254740995",
+            "18446744073709551617.5",
+            "0.10000000000000000001",
+        ] {
+            assert!(
+                !is_exact_f64(number, number.parse().unwrap()),
+                "{} must be lossy",
+                number
+            );
+        }
+    }
+
+    #[test]
+    fn test_serialize_string_as_number_rejects_lossy_floats() {
+        #[derive(serde::Serialize)]
+        struct MyStruct<'a> {
+            #[serde(serialize_with = "serialize_string_as_number")]
+            value: &'a str,
+        }
+
+        let json = |value| serde_json::to_string(&MyStruct { value });
+        assert_eq!(
+            json("9007199254740993").unwrap(),
+            r#"{"value":9007199254740993}"#
         );
-        serde_json_eq!(
-            r#" { "cell":-1,"ref_cell":-1,"mutex":-1,"rw_lock":-1 } "#,
-            Some(-1.0)
+        assert_eq!(
+            json("9007199254740992.0").unwrap(),
+            r#"{"value":9007199254740992.0}"#
         );
-        serde_json_eq!(
-            r#" { "cell":0.1,"ref_cell":0.1,"mutex":0.1,"rw_lock":0.1 } "#,
-            Some(0.1)
+        let e = json("9007199254740993.0").unwrap_err();
+        assert!(e.to_string().contains("losing the precision"), "{}", e);
+        assert!(json("1e400").is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_option_datetime_milliseconds_null_and_zero() {
+        use chrono::prelude::*;
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Nulls {
+            #[serde(with = "crate::with::option_datetime_milliseconds_null")]
+            time: Option<DateTime<Utc>>,
+        }
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Zeros {
+            #[serde(with = "crate::with::option_datetime_milliseconds_zero")]
+            time: Option<DateTime<Utc>>,
+        }
+
+        let epoch = Some(Utc.timestamp_opt(0, 0).unwrap());
+        let time = Some(Utc.timestamp_opt(1519927261, 900_000_000).unwrap());
+        let early = Some(Utc.timestamp_opt(5, 0).unwrap());
+
+        crate::test_utils::assert_roundtrip(&Nulls { time: None }, r#"{"time":null}"#);
+        crate::test_utils::assert_roundtrip(&Nulls { time: epoch }, r#"{"time":0}"#);
+        crate::test_utils::assert_roundtrip(&Nulls { time }, r#"{"time":1519927261900}"#);
+        crate::test_utils::assert_roundtrip(&Nulls { time: early }, r#"{"time":5000}"#);
+
+        crate::test_utils::assert_roundtrip(&Zeros { time: None }, r#"{"time":0}"#);
+        // The epoch is the sentinel itself, so it can't survive the round trip.
+        assert_eq!(
+            serde_json::to_string(&Zeros { time: epoch }).unwrap(),
+            r#"{"time":0}"#
+        );
+        crate::test_utils::assert_de(r#"{"time":0}"#, &Zeros { time: None });
+        crate::test_utils::assert_roundtrip(&Zeros { time }, r#"{"time":1519927261900}"#);
+        crate::test_utils::assert_roundtrip(&Zeros { time: early }, r#"{"time":5000}"#);
+        crate::test_utils::assert_de(r#"{"time":null}"#, &Zeros { time: None });
+    }
+
+    #[test]
+    fn test_missing_null_and_value_across_formats() {
+        crate::option_deserialize_with!(
+            fn deserialize_option_u64(deserialize_number_from_string) -> u64
+        );
+        crate::default_deserialize_with!(
+            fn deserialize_u64_or_default(deserialize_number_from_string) -> u64
+        );
+
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct WithDefault {
+            #[serde(default, deserialize_with = "deserialize_option_u64")]
+            option: Option<u64>,
+            #[serde(default, deserialize_with = "deserialize_u64_or_default")]
+            number: u64,
+        }
+
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct WithoutDefault {
+            #[serde(deserialize_with = "deserialize_option_u64")]
+            option: Option<u64>,
+        }
+
+        fn json<T: serde::de::DeserializeOwned>(value: &serde_json::Value) -> Result<T, String> {
+            serde_json::from_value(value.clone()).map_err(|e| e.to_string())
+        }
+
+        fn msgpack<T: serde::de::DeserializeOwned>(value: &serde_json::Value) -> Result<T, String> {
+            let bytes = rmp_serde::to_vec_named(value).unwrap();
+            rmp_serde::from_slice(&bytes).map_err(|e| e.to_string())
+        }
+
+        type Format<T> = fn(&serde_json::Value) -> Result<T, String>;
+
+        let formats: &[(&str, Format<WithDefault>)] = &[("json", json), ("msgpack", msgpack)];
+        for (format, deserialize) in formats {
+            for (input, expected) in &[
+                (serde_json::json!({}), (None, 0)),
+                (
+                    serde_json::json!({ "option": null, "number": null }),
+                    (None, 0),
+                ),
+                (
+                    serde_json::json!({ "option": 7, "number": 8 }),
+                    (Some(7), 8),
+                ),
+                (
+                    serde_json::json!({ "option": "7", "number": "8" }),
+                    (Some(7), 8),
+                ),
+            ] {
+                let a = deserialize(input).unwrap();
+                assert_eq!((a.option, a.number), *expected, "{} {}", format, input);
+            }
+            assert!(deserialize(&serde_json::json!({ "option": [] })).is_err());
+        }
+
+        let formats: &[(&str, Format<WithoutDefault>)] = &[("json", json), ("msgpack", msgpack)];
+        for (format, deserialize) in formats {
+            let e = deserialize(&serde_json::json!({})).unwrap_err();
+            assert!(e.contains("missing field `option`"), "{} {}", format, e);
+            let a = deserialize(&serde_json::json!({ "option": null })).unwrap();
+            assert_eq!(a.option, None, "{}", format);
+        }
+    }
+
+    #[test]
+    fn test_serialize_map_sorted() {
+        #[derive(serde::Serialize)]
+        struct MyStruct {
+            #[serde(serialize_with = "serialize_map_sorted")]
+            ordered: indexmap::IndexMap<u8, &'static str>,
+            #[serde(serialize_with = "serialize_map_sorted")]
+            empty: std::collections::HashMap<String, u8>,
+        }
+
+        let ordered = vec![(3, "c"), (1, "a"), (2, "b")].into_iter().collect();
+        let a = MyStruct {
+            ordered,
+            empty: Default::default(),
+        };
+        assert_eq!(
+            serde_json::to_string(&a).unwrap(),
+            r#"{"ordered":{"1":"a","2":"b","3":"c"},"empty":{}}"#
+        );
+    }
+
+    #[test]
+    fn test_string_max_bytes() {
+        fn truncate(max: usize, s: &str) -> String {
+            let json = serde_json::to_string(s).unwrap();
+            let mut de = serde_json::Deserializer::from_str(&json);
+            let value = StringMaxBytes::new(max).truncate(true).into_deserializer()(&mut de);
+            value.unwrap()
+        }
+
+        // "é" is 2 bytes, "日" and "本" are 3 bytes, "🦀" is 4 bytes.
+        assert_eq!(truncate(1, "héllo"), "h");
+        assert_eq!(truncate(2, "héllo"), "h");
+        assert_eq!(truncate(3, "héllo"), "hé");
+        assert_eq!(truncate(4, "日本"), "日");
+        assert_eq!(truncate(5, "日本"), "日");
+        assert_eq!(truncate(6, "日本"), "日本");
+        assert_eq!(truncate(3, "🦀"), "");
+        assert_eq!(truncate(0, "abc"), "");
+
+        fn strict(max: usize, s: &str) -> Result<String, serde_json::Error> {
+            let json = serde_json::to_string(s).unwrap();
+            let mut de = serde_json::Deserializer::from_str(&json);
+            let value = StringMaxBytes::new(max).into_deserializer()(&mut de);
+            value
+        }
+
+        assert_eq!(strict(6, "日本").unwrap(), "日本");
+        assert_eq!(strict(6, "").unwrap(), "");
+        let e = strict(5, "日本").unwrap_err();
+        assert!(e.to_string().contains("has 6 bytes, the maximum is 5"));
+    }
+
+    #[cfg(feature = "querystring")]
+    #[test]
+    fn test_deserialize_from_query_string() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        #[serde(rename_all = "lowercase")]
+        enum Order {
+            Asc,
+            Desc,
+        }
+
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Filter {
+            #[serde(rename = "sort order")]
+            order: Order,
+            archived: bool,
+            page: Option<u8>,
+            ids: Vec<u64>,
+            #[serde(default)]
+            note: String,
+        }
+
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_from_query_string")]
+            filter: Filter,
+            #[serde(default, deserialize_with = "deserialize_from_query_string")]
+            extra: std::collections::BTreeMap<String, String>,
+        }
+
+        let s = r#" { "filter": "?sort+order=desc&archived=true&ids=7&x=1&x=2&note=",
+                      "extra": "a=1&b=%C3%A9" } "#;
+        let a: MyStruct = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            a.filter,
+            Filter {
+                order: Order::Desc,
+                archived: true,
+                page: None,
+                ids: vec![7],
+                note: String::new(),
+            }
+        );
+        assert_eq!(a.extra["b"], "é");
+
+        for &(query, error) in &[
+            ("sort+order=up&archived=true&ids=1", "unknown variant `up`"),
+            (
+                "sort+order=asc&archived=yes&ids=1",
+                r#"invalid value "yes" for key "archived""#,
+            ),
+            ("sort+order=asc&archived=true&ids=-1", r#"for key "ids""#),
+            (
+                "sort+order=asc&archived=true&ids=1&page=1&page=2",
+                r#"duplicate key "page""#,
+            ),
+            ("sort+order=asc&ids=1", "missing field `archived`"),
+            ("sort+order=asc&archived=true&ids=%FF", "invalid UTF-8"),
+        ] {
+            let json = format!(r#"{{ "filter": "{}" }}"#, query);
+            let e = serde_json::from_str::<MyStruct>(&json).unwrap_err();
+            assert!(e.to_string().contains(error), "{}: {}", query, e);
+        }
+    }
+
+    #[cfg(feature = "ranges")]
+    #[test]
+    fn test_expand_ranges() {
+        fn expand<T: core::convert::TryFrom<u64> + serde::de::DeserializeOwned>(
+            max_len: usize,
+            s: &str,
+        ) -> Result<Vec<T>, String> {
+            ExpandRanges::new().max_len(max_len).expand(s)
+        }
+
+        assert_eq!(expand::<u64>(10, " ").unwrap(), Vec::<u64>::new());
+        assert_eq!(expand::<u64>(10, "3-3, 7 - 8").unwrap(), vec![3, 7, 8]);
+        assert_eq!(expand::<u64>(3, "1-3").unwrap(), vec![1, 2, 3]);
+        assert!(expand::<u64>(3, "1-3,4")
+            .unwrap_err()
+            .contains("more than 3 numbers"));
+        assert!(expand::<u64>(0, "1")
+            .unwrap_err()
+            .contains("more than 0 numbers"));
+        assert!(expand::<u64>(usize::MAX, "0-18446744073709551615")
+            .unwrap_err()
+            .contains("more than"));
+        assert!(expand::<u8>(1000, "250-260")
+            .unwrap_err()
+            .contains("256 in \"250-260\" is out of range for u8"));
+        assert!(expand::<u64>(10, "1,,2")
+            .unwrap_err()
+            .contains("invalid number \"\""));
+        assert!(expand::<u64>(10, "-1")
+            .unwrap_err()
+            .contains("invalid number \"\""));
+        assert!(expand::<u64>(10, "1-2-3")
+            .unwrap_err()
+            .contains("invalid number \"2-3\""));
+    }
+
+    #[test]
+    fn test_padded_number_string() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct MyStruct {
+            #[serde(serialize_with = "pad", deserialize_with = "unpad")]
+            value: i32,
+        }
+
+        fn pad<S: serde::Serializer>(value: &i32, serializer: S) -> Result<S::Ok, S::Error> {
+            PaddedNumberString::new(4).into_serializer()(value, serializer)
+        }
+
+        fn unpad<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+            PaddedNumberString::new(4).into_deserializer()(deserializer)
+        }
+
+        for &(value, s) in &[
+            (7, "0007"),
+            (0, "0000"),
+            (-7, "-007"),
+            (1234, "1234"),
+            // Longer than the width: written unpadded, never truncated.
+            (12345, "12345"),
+            (-1234, "-1234"),
+        ] {
+            let a = MyStruct { value };
+            let json = serde_json::to_string(&a).unwrap();
+            assert_eq!(json, format!(r#"{{"value":"{}"}}"#, s));
+            assert_eq!(serde_json::from_str::<MyStruct>(&json).unwrap(), a);
+            let bytes = rmp_serde::to_vec(&a).unwrap();
+            assert_eq!(rmp_serde::from_slice::<MyStruct>(&bytes).unwrap(), a);
+        }
+
+        let a: MyStruct = serde_json::from_str(r#"{"value":"000000000042"}"#).unwrap();
+        assert_eq!(a.value, 42);
+        let a: MyStruct = serde_json::from_str(r#"{"value":-3}"#).unwrap();
+        assert_eq!(a.value, -3);
+        assert!(serde_json::from_str::<MyStruct>(r#"{"value":""}"#).is_err());
+        assert!(serde_json::from_str::<MyStruct>(r#"{"value":"00x1"}"#).is_err());
+    }
+
+    #[test]
+    fn test_option_none_if_empty_object_msgpack() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Profile {
+            name: String,
+        }
+
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_option_none_if_empty_object")]
+            profile: Option<Profile>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Input<T> {
+            profile: T,
+        }
+
+        fn from_msgpack<T: serde::Serialize>(
+            profile: T,
+        ) -> Result<MyStruct, rmp_serde::decode::Error> {
+            let bytes = rmp_serde::to_vec_named(&Input { profile }).unwrap();
+            rmp_serde::from_slice(&bytes)
+        }
+
+        let empty = std::collections::BTreeMap::<String, u8>::new();
+        assert_eq!(from_msgpack(empty).unwrap().profile, None);
+        assert_eq!(from_msgpack(()).unwrap().profile, None);
+        let profile = Profile {
+            name: "Ann".to_owned(),
+        };
+        let a = from_msgpack(&profile).unwrap();
+        assert_eq!(a.profile, Some(profile));
+        assert!(from_msgpack(1).is_err());
+    }
+
+    #[test]
+    fn test_for_each_in_deserialize_with() {
+        use serde::de::DeserializeSeed;
+
+        fn count_large<'de, D: serde::Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<usize, D::Error> {
+            let mut large = 0;
+            ForEach::new(|n: u64| {
+                if n > 100 {
+                    large += 1;
+                }
+            })
+            .deserialize(deserializer)?;
+            Ok(large)
+        }
+
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(rename = "values", deserialize_with = "count_large")]
+            large_values: usize,
+            after: bool,
+        }
+
+        let s = r#" { "values": [1, 200, 3, 400], "after": true } "#;
+        let a: MyStruct = serde_json::from_str(s).unwrap();
+        assert_eq!(a.large_values, 2);
+        assert!(a.after);
+
+        let s = r#" { "values": [1, "x"], "after": true } "#;
+        assert!(serde_json::from_str::<MyStruct>(s).is_err());
+
+        // Stopping skips the rest, even the elements which are not a `T`.
+        let mut deserializer = serde_json::Deserializer::from_str(r#"[1, "x", {"y": [2]}] "#);
+        let count = ForEach::new(|_: u8| Ok::<_, String>(ForEachControl::Stop))
+            .deserialize(&mut deserializer)
+            .unwrap();
+        deserializer.end().unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn test_unit_number_errors() {
+        fn deserialize(units: UnitNumber, json: &str) -> Result<f64, String> {
+            let mut de = serde_json::Deserializer::from_str(json);
+            let value = units.into_deserializer()(&mut de);
+            value.map_err(|e| e.to_string())
+        }
+
+        let meters = || UnitNumber::new().unit("m", 1.0).unit("km", 1000.0);
+        assert_eq!(deserialize(meters(), r#" "-1.5e1 m" "#), Ok(-15.0));
+        assert_eq!(
+            deserialize(meters(), r#" { "unit": "km", "value": 0.5 } "#),
+            Ok(500.0)
         );
-        serde_json_eq!(
-            r#" { "cell":-0.1,"ref_cell":-0.1,"mutex":-0.1,"rw_lock":-0.1 } "#,
-            Some(-0.1)
+        for &(json, error) in &[
+            (r#" "5 KM" "#, r#"unknown unit "KM""#),
+            (r#" "km" "#, "expected a number with a unit"),
+            (
+                r#" { "value": 5 } "#,
+                r#"missing unit in { value: 5 }, expected one of ["m", "km"]"#,
+            ),
+            (r#" { "unit": "m" } "#, "missing field `value`"),
+            (
+                r#" { "value": 1, "unit": "m", "scale": 2 } "#,
+                "unknown field `scale`",
+            ),
+            (r#" { "value": 1, "value": 2 } "#, "duplicate field `value`"),
+            (r#" { "value": "x", "unit": "m" } "#, "x"),
+            ("true", "invalid type: boolean"),
+        ] {
+            let e = deserialize(meters(), json).unwrap_err();
+            assert!(e.contains(error), "{}: {}", json, e);
+        }
+
+        // The default unit is looked up among the registered ones.
+        let e = deserialize(meters().default_unit("mi"), "5").unwrap_err();
+        assert!(e.contains(r#"unknown unit "mi" in 5"#), "{}", e);
+        assert_eq!(
+            deserialize(meters().default_unit("km"), r#" "5" "#),
+            Ok(5000.0)
         );
-        serde_json_eq!(
-            r#" { "cell":"","ref_cell":"","mutex":"","rw_lock":"" } "#,
-            None
+    }
+
+    #[test]
+    fn test_option_string_literal_null_roundtrip() {
+        use crate::test_utils::{assert_de, assert_roundtrip};
+
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct MyStruct {
+            #[serde(with = "crate::with::option_string_literal_null")]
+            comment: Option<String>,
+        }
+
+        assert_roundtrip(&MyStruct { comment: None }, r#"{"comment":"null"}"#);
+        assert_roundtrip(
+            &MyStruct {
+                comment: Some("x".to_owned()),
+            },
+            r#"{"comment":"x"}"#,
         );
-        serde_json_eq!(
-            r#" { "cell":null,"ref_cell":null,"mutex":null,"rw_lock":null } "#,
-            None
+        assert_roundtrip(
+            &MyStruct {
+                comment: Some(String::new()),
+            },
+            r#"{"comment":""}"#,
         );
 
-        serde_json_err!(true);
-        serde_json_err!("a");
-        serde_json_err!({});
-        serde_json_err!([]);
+        // The documented ambiguity: `Some("null")` is written as the literal and read as `None`.
+        let ambiguous = MyStruct {
+            comment: Some("null".to_owned()),
+        };
+        let json = serde_json::to_string(&ambiguous).unwrap();
+        assert_eq!(json, r#"{"comment":"null"}"#);
+        assert_de(&json, &MyStruct { comment: None });
+
+        assert_de(r#"{"comment":null}"#, &MyStruct { comment: None });
+        assert_de(
+            r#"{"comment":"NULL"}"#,
+            &MyStruct {
+                comment: Some("NULL".to_owned()),
+            },
+        );
     }
-}
 
-/// Deserializes boolean from anything (string, number, boolean). If input is a string,
-/// it is expected, that it is possible to convert it to a number. The return boolean is
-/// `true` if the number was either `1` or `1.0` after parsing.
-///
-/// # Example
-///
-/// ```rust
-/// use serde_aux::prelude::*;
-///
-/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
-/// struct MyStruct {
-///     #[serde(deserialize_with = "deserialize_bool_from_anything")]
-///     boolean: bool,
-/// }
-/// fn main() {
-///     let s = r#"{ "boolean": 1.0 }"#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert!(a.boolean);
-///
-///     let s = r#"{ "boolean": 0.0 }"#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert!(!a.boolean);
-///
-///     let s = r#"{ "boolean": 2.3 }"#;
-///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
-///
-///     let s = r#"{ "boolean": 1 }"#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert!(a.boolean);
-///
-///     let s = r#"{ "boolean": 0 }"#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert!(!a.boolean);
-///
-///     let s = r#"{ "boolean": 2 }"#;
-///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
-///
-///     let s = r#"{ "boolean": "1.0" }"#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert!(a.boolean);
-///
-///     let s = r#"{ "boolean": "0.0" }"#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert!(!a.boolean);
-///
-///     let s = r#"{ "boolean": "2.3" }"#;
-///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
-///
-///     let s = r#"{ "boolean": "1" }"#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert!(a.boolean);
-///
-///     let s = r#"{ "boolean": "0" }"#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert!(!a.boolean);
-///
-///     let s = r#"{ "boolean": "2" }"#;
-///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
-///
-///     let s = r#"{ "boolean": "foo" }"#;
-///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
-/// }
-/// ```
-pub fn deserialize_bool_from_anything<'de, D>(deserializer: D) -> Result<bool, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    use std::f64::EPSILON;
+    #[test]
+    fn test_lat_lng_flexible_same_across_formats() {
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_lat_lng_flexible")]
+            location: (f64, f64),
+        }
 
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum AnythingOrBool {
-        String(String),
-        Int(i64),
-        Float(f64),
-        Boolean(bool),
-    }
+        for input in &[
+            serde_json::json!({ "location": [13.4, 52.52] }),
+            serde_json::json!({ "location": { "lat": 52.52, "lon": 13.4 } }),
+            serde_json::json!({ "location": "52.52,13.4" }),
+        ] {
+            let json = serde_json::to_string(input).unwrap();
+            let a: MyStruct = serde_json::from_str(&json).unwrap();
+            assert_eq!(a.location, (52.52, 13.4), "{}", json);
 
-    match AnythingOrBool::deserialize(deserializer)? {
-        AnythingOrBool::Boolean(b) => Ok(b),
-        AnythingOrBool::Int(i) => match i {
-            1 => Ok(true),
-            0 => Ok(false),
-            _ => Err(serde::de::Error::custom("The number is neither 1 nor 0")),
-        },
-        AnythingOrBool::Float(f) => {
-            if (f - 1.0f64).abs() < EPSILON {
-                Ok(true)
-            } else if f == 0.0f64 {
-                Ok(false)
-            } else {
-                Err(serde::de::Error::custom(
-                    "The number is neither 1.0 nor 0.0",
-                ))
-            }
+            let bytes = rmp_serde::to_vec_named(input).unwrap();
+            let a: MyStruct = rmp_serde::from_slice(&bytes).unwrap();
+            assert_eq!(a.location, (52.52, 13.4), "{}", json);
         }
-        AnythingOrBool::String(string) => {
-            if let Ok(b) = string.parse::<bool>() {
-                Ok(b)
-            } else if let Ok(i) = string.parse::<i64>() {
-                match i {
-                    1 => Ok(true),
-                    0 => Ok(false),
-                    _ => Err(serde::de::Error::custom("The number is neither 1 nor 0")),
-                }
-            } else if let Ok(f) = string.parse::<f64>() {
-                if (f - 1.0f64).abs() < EPSILON {
-                    Ok(true)
-                } else if f == 0.0f64 {
-                    Ok(false)
-                } else {
-                    Err(serde::de::Error::custom(
-                        "The number is neither 1.0 nor 0.0",
-                    ))
-                }
-            } else {
-                Err(serde::de::Error::custom(format!(
-                    "Could not parse boolean from a string: {}",
-                    string
-                )))
-            }
+
+        let bytes =
+            rmp_serde::to_vec_named(&serde_json::json!({ "location": [52.52, 113.4] })).unwrap();
+        let e = rmp_serde::from_slice::<MyStruct>(&bytes).unwrap_err();
+        assert!(e.to_string().contains("latitude 113.4 is out of range"));
+    }
+
+    #[test]
+    fn test_vec_null_elements_as_default_msgpack() {
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_vec_null_elements_as_default")]
+            samples: Vec<u64>,
         }
+
+        let input = serde_json::json!({ "samples": [1, null, 3] });
+        let bytes = rmp_serde::to_vec_named(&input).unwrap();
+        let a: MyStruct = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(a.samples, vec![1, 0, 3]);
+
+        let bytes = rmp_serde::to_vec(&(vec![1u64, 2],)).unwrap();
+        let a: MyStruct = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(a.samples, vec![1, 2]);
     }
-}
 
-/// Deserializes string from a number. If the original value is a number value,
-/// it will be converted to a string.
-///
-/// # Example:
-///
-/// ```rust
-/// use serde_aux::prelude::*;
-///
-/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
-/// struct MyStruct {
-///     #[serde(deserialize_with = "deserialize_string_from_number")]
-///     number_as_string: String,
-/// }
-/// fn main() {
-///     let s = r#" { "number_as_string": "foo" } "#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.number_as_string, "foo");
-///
-///     let s = r#" { "number_as_string": -13 } "#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.number_as_string, "-13");
-///
-///     let s = r#" { "number_as_string": 24.0034 } "#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.number_as_string, "24.0034");
-/// }
-/// ```
-pub fn deserialize_string_from_number<'de, D>(deserializer: D) -> Result<String, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum StringOrNumber {
-        String(String),
-        Number(i64),
-        Float(f64),
+    #[test]
+    fn test_enum_normalized_msgpack() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        #[serde(rename_all = "snake_case")]
+        enum Status {
+            Active,
+            Custom(u8),
+        }
+
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_enum_normalized")]
+            status: Status,
+        }
+
+        let bytes = rmp_serde::to_vec_named(&serde_json::json!({ "status": " ACTIVE " })).unwrap();
+        let a: MyStruct = rmp_serde::from_slice(&bytes).unwrap();
+        assert_eq!(a.status, Status::Active);
+
+        for status in &[Status::Active, Status::Custom(7)] {
+            let bytes = rmp_serde::to_vec(&(status,)).unwrap();
+            let a: MyStruct = rmp_serde::from_slice(&bytes).unwrap();
+            assert_eq!(&a.status, status);
+        }
     }
 
-    match StringOrNumber::deserialize(deserializer)? {
-        StringOrNumber::String(s) => Ok(s),
-        StringOrNumber::Number(i) => Ok(i.to_string()),
-        StringOrNumber::Float(f) => Ok(f.to_string()),
+    #[test]
+    fn test_option_none_if_empty_object_forwards_to_t() {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Borrowed<'a> {
+            name: &'a str,
+            #[serde(default)]
+            age: u8,
+        }
+
+        #[derive(serde::Deserialize, Debug, PartialEq, Default)]
+        struct Defaults {
+            #[serde(default)]
+            age: u8,
+        }
+
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct<'a> {
+            #[serde(
+                borrow,
+                default,
+                deserialize_with = "deserialize_option_none_if_empty_object"
+            )]
+            borrowed: Option<Borrowed<'a>>,
+            #[serde(default, deserialize_with = "deserialize_option_none_if_empty_object")]
+            defaults: Option<Defaults>,
+            #[serde(default, deserialize_with = "deserialize_option_none_if_empty_object")]
+            number: Option<u8>,
+            #[serde(default, deserialize_with = "deserialize_option_none_if_empty_object")]
+            by_id: Option<std::collections::BTreeMap<u32, String>>,
+            #[serde(default, deserialize_with = "deserialize_option_none_if_empty_object")]
+            list: Option<Vec<u8>>,
+        }
+
+        let s = r#" { "borrowed": { "name": "Ann" }, "defaults": { "age": 3 }, "number": 7,
+            "by_id": { "1": "a" }, "list": [1, 2] } "#;
+        let a: MyStruct = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            a.borrowed,
+            Some(Borrowed {
+                name: "Ann",
+                age: 0
+            })
+        );
+        assert_eq!(a.defaults, Some(Defaults { age: 3 }));
+        assert_eq!(a.number, Some(7));
+        assert_eq!(a.by_id.unwrap()[&1], "a");
+        assert_eq!(a.list, Some(vec![1, 2]));
+
+        let s = r#" { "borrowed": {}, "defaults": {}, "number": {}, "by_id": {}, "list": {} } "#;
+        let a: MyStruct = serde_json::from_str(s).unwrap();
+        assert_eq!(a.borrowed, None);
+        assert_eq!(a.defaults, None);
+        assert_eq!(a.number, None);
+        assert_eq!(a.by_id, None);
+        assert_eq!(a.list, None);
+
+        let e = serde_json::from_str::<MyStruct>(r#" { "number": { "a": 1 } } "#).unwrap_err();
+        assert!(e.to_string().starts_with("invalid type: map"), "{}", e);
+        let e = serde_json::from_str::<MyStruct>(r#" { "number": "7" } "#).unwrap_err();
+        assert!(e.to_string().starts_with("invalid type: string"), "{}", e);
     }
-}
 
-/// Deserializes default value from nullable value. If the original value is `null`,
-/// `Default::default()` is used.
-///
-/// # Example:
-///
-/// ```rust
-/// use serde_aux::prelude::*;
-///
-/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
-/// struct MyStruct {
-///     #[serde(deserialize_with = "deserialize_default_from_null")]
-///     null_as_default: u64,
-/// }
-///
-/// fn main() {
-///     let s = r#" { "null_as_default": 42 } "#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.null_as_default, 42);
-///
-///     let s = r#" { "null_as_default": null } "#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.null_as_default, 0);
-///
-///     let s = r#" { "null_as_default": "wrong_type" } "#;
-///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
-/// }
-/// ```
-pub fn deserialize_default_from_null<'de, D, T>(deserializer: D) -> Result<T, D::Error>
-where
-    D: Deserializer<'de>,
-    T: Deserialize<'de> + Default,
-{
-    Ok(Option::deserialize(deserializer)?.unwrap_or_default())
-}
+    #[test]
+    fn test_array_from_string_or_seq_longer_than_32() {
+        #[derive(serde::Deserialize, Debug)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_array_from_string_or_seq")]
+            samples: [u8; 40],
+            #[serde(deserialize_with = "crate::rced::deserialize_array_from_string_or_seq")]
+            shared: std::rc::Rc<[u64; 33]>,
+        }
 
-/// Deserializes default value from nullable value or empty object. If the original value is `null` or `{}`,
-/// `Default::default()` is used.
-///
-/// # Example:
-///
-/// ```rust
-/// use serde_aux::prelude::*;
-///
-/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
-/// struct MyStruct {
-///     #[serde(deserialize_with = "deserialize_default_from_empty_object")]
-///     empty_as_default: Option<MyInnerStruct>,
-/// }
-///
-/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
-/// struct MyInnerStruct {
-///     mandatory: u64,
-/// }
-///
-/// fn main() {
-///     let s = r#" { "empty_as_default": { "mandatory": 42 } } "#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert_eq!(a.empty_as_default.unwrap().mandatory, 42);
-///
-///     let s = r#" { "empty_as_default": null } "#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert!(a.empty_as_default.is_none());
-///
-///     let s = r#" { "empty_as_default": {} } "#;
-///     let a: MyStruct = serde_json::from_str(s).unwrap();
-///     assert!(a.empty_as_default.is_none());
-///
-///     let s = r#" { "empty_as_default": { "unknown": 42 } } "#;
-///     assert!(serde_json::from_str::<MyStruct>(s).is_err());
-/// }
-/// ```
-pub fn deserialize_default_from_empty_object<'de, D, T>(deserializer: D) -> Result<T, D::Error>
-where
-    D: Deserializer<'de>,
-    T: Deserialize<'de> + Default,
-{
-    #[derive(Debug, Deserialize)]
-    #[serde(deny_unknown_fields)]
-    struct EmptyObject {}
+        let samples = (0..40).map(|i| i.to_string()).collect::<Vec<_>>().join(",");
+        let shared = (0..33).collect::<Vec<u64>>();
+        let json = serde_json::json!({ "samples": samples, "shared": shared });
+        let a: MyStruct = serde_json::from_value(json).unwrap();
+        assert_eq!(a.samples[39], 39);
+        assert_eq!(a.shared[32], 32);
 
-    #[derive(Debug, Deserialize)]
-    #[serde(untagged)]
-    enum EmptyOrNot<Y> {
-        NonEmpty(Y),
-        Empty(EmptyObject),
+        let json = serde_json::json!({ "samples": "1,2", "shared": shared });
+        let e = serde_json::from_value::<MyStruct>(json).unwrap_err();
+        assert!(
+            e.to_string().starts_with("expected 40 elements, found 2"),
+            "{}",
+            e
+        );
     }
 
-    let empty_or_not: EmptyOrNot<T> = EmptyOrNot::deserialize(deserializer)?;
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_os_string_flexible_huge_length_header() {
+        #[derive(serde::Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct MyStruct {
+            #[serde(deserialize_with = "deserialize_os_string_flexible")]
+            path: std::ffi::OsString,
+        }
 
-    match empty_or_not {
-        EmptyOrNot::NonEmpty(e) => Ok(e),
-        _ => Ok(T::default()),
+        // A positional struct holding an array32 claiming 2^32 - 1 elements and holding none.
+        let bytes = [0x91, 0xdd, 0xff, 0xff, 0xff, 0xff];
+        assert!(rmp_serde::from_slice::<MyStruct>(&bytes).is_err());
     }
 }
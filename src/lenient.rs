@@ -0,0 +1,223 @@
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+
+/// What a lenient helper did with a value instead of failing, see [`LenientWarning`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LenientAction {
+    /// The value was changed to fit: the invalid characters were replaced or removed, the
+    /// trailing text was dropped.
+    Coerced,
+    /// The value was replaced with the default one.
+    Defaulted,
+    /// The value went to the catch-all variant of an enum.
+    Fallback,
+}
+
+/// A value a lenient helper of this crate has accepted only by changing it, as recorded by
+/// [`capture`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LenientWarning {
+    /// The name of the helper, like `"deserialize_enum_with_fallback"`.
+    pub helper: &'static str,
+    /// The value as it was received, the bytes and the other non-strings are escaped.
+    pub original: String,
+    /// What was done with the value.
+    pub action: LenientAction,
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static WARNINGS: core::cell::RefCell<Option<Vec<LenientWarning>>> =
+        const { core::cell::RefCell::new(None) };
+}
+
+/// Runs `f` collecting the warnings of the lenient helpers called by it on the current thread,
+/// so the values which were coerced, defaulted or taken as a fallback can be counted and alerted
+/// on instead of being lost silently. Without `capture` the helpers don't record anything and
+/// behave exactly the same. Requires the `std` feature.
+///
+/// The warnings come in the order the values are deserialized. The helpers don't know where
+/// their values are in the document, so the warnings don't tell the field; a struct deserialized
+/// per record (per line of a log, for example) gives the warnings per record. A nested `capture`
+/// takes the warnings of its `f` only.
+///
+/// The warnings are recorded by `deserialize_enum_with_fallback`, `deserialize_string_lossy`,
+/// `deserialize_vec_null_elements_as_default`, `deserialize_number_from_prefix_of_string`,
+/// `StringMatching` with `strip_invalid(true)` and `StringMaxBytes` with `truncate(true)`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::lenient::{capture, LenientAction};
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_vec_null_elements_as_default")]
+///     samples: Vec<u32>,
+///     #[serde(deserialize_with = "deserialize_number_from_prefix_of_string")]
+///     count: u32,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "samples": [1, null, 3], "count": "12 items" } "#;
+///     let (a, warnings) = capture(|| serde_json::from_str::<MyStruct>(s));
+///     let a = a.unwrap();
+///     assert_eq!(a.samples, vec![1, 0, 3]);
+///     assert_eq!(a.count, 12);
+///
+///     assert_eq!(warnings.len(), 2);
+///     assert_eq!(warnings[0].helper, "deserialize_vec_null_elements_as_default");
+///     assert_eq!(warnings[0].action, LenientAction::Defaulted);
+///     assert_eq!(warnings[1].original, "12 items");
+///     assert_eq!(warnings[1].action, LenientAction::Coerced);
+///
+///     let (_, warnings) = capture(|| serde_json::from_str::<MyStruct>(r#" { "samples": [], "count": 1 } "#));
+///     assert!(warnings.is_empty());
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn capture<R, F>(f: F) -> (R, Vec<LenientWarning>)
+where
+    F: FnOnce() -> R,
+{
+    /// Puts the warnings of the outer `capture` back, even if `f` panics.
+    struct Restore(Option<Vec<LenientWarning>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let outer = self.0.take();
+            let _ = WARNINGS.try_with(|warnings| *warnings.borrow_mut() = outer);
+        }
+    }
+
+    let outer = WARNINGS.with(|warnings| warnings.borrow_mut().replace(Vec::new()));
+    let restore = Restore(outer);
+    let result = f();
+    let warnings = WARNINGS.with(|warnings| warnings.borrow_mut().take().unwrap_or_default());
+    drop(restore);
+    (result, warnings)
+}
+
+/// Records a warning if a [`capture`] is active on the current thread. The original value is
+/// formatted only then.
+pub(crate) fn report<F>(helper: &'static str, action: LenientAction, original: F)
+where
+    F: FnOnce() -> String,
+{
+    #[cfg(feature = "std")]
+    let _ = WARNINGS.try_with(|warnings| {
+        if let Some(warnings) = warnings.borrow_mut().as_mut() {
+            warnings.push(LenientWarning {
+                helper,
+                original: original(),
+                action,
+            });
+        }
+    });
+    #[cfg(not(feature = "std"))]
+    let _ = (helper, action, original);
+}
+
+/// Escapes the bytes which are not printable ASCII, like `b"caf\xe9"` gives `caf\xe9`.
+pub(crate) fn escape_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|&b| core::ascii::escape_default(b))
+        .map(char::from)
+        .collect()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Color {
+        Red,
+        Other(String),
+    }
+
+    impl EnumWithFallback for Color {
+        fn from_unknown(name: String) -> Self {
+            Color::Other(name)
+        }
+
+        fn as_unknown(&self) -> Option<&str> {
+            match self {
+                Color::Other(name) => Some(name),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(serde::Deserialize, Debug)]
+    struct MyStruct {
+        #[serde(deserialize_with = "deserialize_enum_with_fallback")]
+        color: Color,
+        #[serde(deserialize_with = "deserialize_string_lossy")]
+        name: String,
+    }
+
+    #[test]
+    fn test_capture() {
+        let (a, warnings) = capture(|| {
+            let deserializer =
+                serde::de::value::BytesDeserializer::<serde::de::value::Error>::new(b"caf\xe9");
+            deserialize_string_lossy(deserializer)
+        });
+        assert_eq!(a.unwrap(), "caf\u{fffd}");
+        assert_eq!(
+            warnings,
+            vec![LenientWarning {
+                helper: "deserialize_string_lossy",
+                original: "caf\\xe9".to_owned(),
+                action: LenientAction::Coerced,
+            }]
+        );
+
+        let s = r#" { "color": "teal", "name": "x" } "#;
+        let (a, warnings) = capture(|| serde_json::from_str::<MyStruct>(s).unwrap());
+        assert_eq!(a.color, Color::Other("teal".to_owned()));
+        assert_eq!(a.name, "x");
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].original, "teal");
+        assert_eq!(warnings[0].action, LenientAction::Fallback);
+
+        let s = r#" { "color": "red", "name": "x" } "#;
+        let (_, warnings) = capture(|| serde_json::from_str::<MyStruct>(s).unwrap());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_capture_nested_and_inactive() {
+        let s = r#" { "color": "teal", "name": "x" } "#;
+        assert!(serde_json::from_str::<MyStruct>(s).is_ok());
+
+        let ((_, inner), outer) = capture(|| {
+            serde_json::from_str::<MyStruct>(s).unwrap();
+            let inner = capture(|| serde_json::from_str::<MyStruct>(s).unwrap());
+            serde_json::from_str::<MyStruct>(s).unwrap();
+            inner
+        });
+        assert_eq!(inner.len(), 1);
+        assert_eq!(outer.len(), 2);
+    }
+
+    #[test]
+    fn test_capture_restores_after_panic() {
+        let s = r#" { "color": "teal", "name": "x" } "#;
+        let (_, outer) = capture(|| {
+            let result = std::panic::catch_unwind(|| {
+                capture(|| -> () { panic!("inner") });
+            });
+            assert!(result.is_err());
+            serde_json::from_str::<MyStruct>(s).unwrap();
+        });
+        assert_eq!(outer.len(), 1);
+    }
+}
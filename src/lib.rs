@@ -1,17 +1,192 @@
 //! # serde-aux
 //!
 //! A serde auxiliary library.
+//!
+//! The library is `no_std` (it needs `alloc`) when the default `std` feature is disabled. The
+//! helpers for the types of `std` (`OsString`, `PathBuf`, `SystemTime`, `HashSet`, `Mutex`, ...)
+//! are not available then, and neither are the `chrono`, `unicode` and `regex` features.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(missing_docs)]
 #![deny(warnings)]
 
+extern crate alloc;
+// The tests need the standard library even when the crate itself is `no_std`.
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
+#[cfg(all(
+    not(feature = "std"),
+    any(feature = "chrono", feature = "unicode", feature = "regex")
+))]
+compile_error!("the `chrono`, `unicode` and `regex` features require the `std` feature");
+
+#[macro_use]
+mod smart_pointers;
+
 /// Contains helpers for the containers.
 pub mod container_attributes;
 /// Contains helpers for the fields.
 pub mod field_attributes;
+/// Contains the collector of the warnings of the lenient helpers.
+pub mod lenient;
+/// Contains the predicates for `#[serde(skip_serializing_if)]`.
+pub mod predicates;
+/// Contains helpers for the introspection of the types as serde sees them.
+pub mod serde_introspection;
+/// Contains the assertions for testing the serde helpers and the types using them. Requires the
+/// `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+pub mod test_utils;
+/// Contains the serializer and deserializer pairs for the fields which need both directions,
+/// usable as `#[serde(with = "serde_aux::with::number_as_string")]`.
+pub mod with;
+
+/// Contains the helpers of this crate returning a `Box`, for the boxed fields:
+/// `#[serde(deserialize_with = "serde_aux::boxed::deserialize_number_from_string")]`. See
+/// [`arced`] and [`rced`] for the other pointers.
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "serde_aux::boxed::deserialize_number_from_string")]
+///     number: Box<u64>,
+///     #[serde(deserialize_with = "serde_aux::boxed::deserialize_bool_from_anything")]
+///     boolean: Box<bool>,
+/// }
+///
+/// fn main() {
+///     let s = r#" { "number": "123", "boolean": 1 } "#;
+///     let a: MyStruct = serde_json::from_str(s).unwrap();
+///     assert_eq!(*a.number, 123);
+///     assert!(*a.boolean);
+/// }
+/// ```
+pub mod boxed {
+    use alloc::boxed::Box;
+
+    smart_pointer_helpers!(Box);
+}
+
+/// Contains the helpers of this crate returning an `Arc`, the same as [`boxed`].
+///
+/// # Example:
+///
+/// ```rust
+/// use std::sync::Arc;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "serde_aux::arced::deserialize_string_from_number")]
+///     name: Arc<String>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "name": 42 } "#).unwrap();
+///     assert_eq!(*a.name, "42");
+/// }
+/// ```
+pub mod arced {
+    use alloc::sync::Arc;
+
+    smart_pointer_helpers!(Arc);
+}
+
+/// Contains the helpers of this crate returning an `Rc`, the same as [`boxed`].
+///
+/// # Example:
+///
+/// ```rust
+/// use std::rc::Rc;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "serde_aux::rced::deserialize_option_number_from_string")]
+///     number: Rc<Option<f64>>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "number": "1.5" } "#).unwrap();
+///     assert_eq!(*a.number, Some(1.5));
+///     let a: MyStruct = serde_json::from_str(r#" { "number": null } "#).unwrap();
+///     assert_eq!(*a.number, None);
+/// }
+/// ```
+pub mod rced {
+    use alloc::rc::Rc;
+
+    smart_pointer_helpers!(Rc);
+}
+
+/// Contains the helpers of this crate returning a `Cell`, the same as [`boxed`], and the
+/// serializers of this crate reading the value of a `Cell`, for the round trips.
+///
+/// # Example:
+///
+/// ```rust
+/// use std::cell::Cell;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(
+///         deserialize_with = "serde_aux::cell::deserialize_number_from_string",
+///         serialize_with = "serde_aux::cell::serialize_number_as_string"
+///     )]
+///     counter: Cell<u64>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "counter": "41" } "#).unwrap();
+///     a.counter.set(a.counter.get() + 1);
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"counter":"42"}"#);
+/// }
+/// ```
+pub mod cell {
+    use core::cell::Cell;
+
+    smart_pointer_helpers!(Cell);
+    cell_serializer_helpers!(Cell);
+}
+
+/// Contains the helpers of this crate returning a `RefCell`, the same as [`boxed`], and the
+/// serializers of this crate reading the value of a `RefCell`, for the round trips.
+///
+/// # Example:
+///
+/// ```rust
+/// use std::cell::RefCell;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "serde_aux::ref_cell::deserialize_string_from_number")]
+///     name: RefCell<String>,
+///     #[serde(
+///         deserialize_with = "serde_aux::ref_cell::deserialize_option_number_from_string",
+///         serialize_with = "serde_aux::ref_cell::serialize_option_number_as_string"
+///     )]
+///     limit: RefCell<Option<u32>>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#" { "name": 42, "limit": "10" } "#).unwrap();
+///     a.name.borrow_mut().push('!');
+///     *a.limit.borrow_mut() = None;
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"name":"42!","limit":null}"#);
+/// }
+/// ```
+pub mod ref_cell {
+    use core::cell::RefCell;
+
+    smart_pointer_helpers!(RefCell);
+    cell_serializer_helpers!(RefCell);
+}
 
 /// Prelude module, contains the most needed helpers from this library.
 pub mod prelude {
     pub use crate::container_attributes::*;
     pub use crate::field_attributes::*;
+    pub use crate::predicates::*;
+    pub use crate::serde_introspection::*;
 }
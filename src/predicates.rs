@@ -0,0 +1,279 @@
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+/// Tells whether the value is the default one of its type. Any type implementing `Default` and
+/// `PartialEq` works, the type is inferred from the field.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug, Default, PartialEq)]
+/// struct Paging {
+///     offset: u32,
+///     limit: u32,
+/// }
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(skip_serializing_if = "is_default")]
+///     paging: Paging,
+///     #[serde(skip_serializing_if = "is_default")]
+///     name: String,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { paging: Paging::default(), name: "a".to_owned() };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"name":"a"}"#);
+///
+///     let a = MyStruct { paging: Paging { offset: 0, limit: 10 }, name: String::new() };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"paging":{"offset":0,"limit":10}}"#);
+/// }
+/// ```
+pub fn is_default<T>(value: &T) -> bool
+where
+    T: Default + PartialEq,
+{
+    *value == T::default()
+}
+
+/// A number which can be checked for being zero, see [`is_zero`].
+pub trait IsZero {
+    /// Returns `true` if the number is zero (either of the float zeros for the floats).
+    fn is_zero(&self) -> bool;
+}
+
+macro_rules! impl_is_zero {
+    ($zero:expr => $($ty:ty)*) => {
+        $(
+            impl IsZero for $ty {
+                fn is_zero(&self) -> bool {
+                    *self == $zero
+                }
+            }
+        )*
+    };
+}
+
+impl_is_zero!(0 => i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+impl_is_zero!(0.0 => f32 f64);
+
+impl IsZero for core::time::Duration {
+    fn is_zero(&self) -> bool {
+        *self == core::time::Duration::from_secs(0)
+    }
+}
+
+/// Tells whether the number (or a `Duration`) is zero.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(skip_serializing_if = "is_zero")]
+///     retries: u8,
+///     #[serde(skip_serializing_if = "is_zero")]
+///     discount: f64,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { retries: 0, discount: -0.0 };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), "{}");
+///
+///     let a = MyStruct { retries: 3, discount: 0.5 };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"retries":3,"discount":0.5}"#);
+/// }
+/// ```
+pub fn is_zero<T>(value: &T) -> bool
+where
+    T: IsZero,
+{
+    value.is_zero()
+}
+
+/// Tells whether the string is empty.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(skip_serializing_if = "is_empty_string")]
+///     comment: String,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { comment: String::new() };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), "{}");
+///
+///     let a = MyStruct { comment: " ".to_owned() };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"comment":" "}"#);
+/// }
+/// ```
+pub fn is_empty_string(value: &str) -> bool {
+    value.is_empty()
+}
+
+/// Tells whether the vector (or a slice) is empty.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(skip_serializing_if = "is_empty_vec")]
+///     tags: Vec<String>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { tags: vec![] };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), "{}");
+///
+///     let a = MyStruct { tags: vec!["a".to_owned()] };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"tags":["a"]}"#);
+/// }
+/// ```
+pub fn is_empty_vec<T>(value: &[T]) -> bool {
+    value.is_empty()
+}
+
+/// Tells whether the boolean is `false`.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(skip_serializing_if = "is_false")]
+///     archived: bool,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { archived: false };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), "{}");
+///
+///     let a = MyStruct { archived: true };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"archived":true}"#);
+/// }
+/// ```
+pub fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// A string or a collection which can be checked for being empty, see [`is_none_or_empty`].
+pub trait IsEmpty {
+    /// Returns `true` if there is nothing inside.
+    fn is_empty(&self) -> bool;
+}
+
+impl IsEmpty for str {
+    fn is_empty(&self) -> bool {
+        str::is_empty(self)
+    }
+}
+
+impl IsEmpty for String {
+    fn is_empty(&self) -> bool {
+        String::is_empty(self)
+    }
+}
+
+impl<T> IsEmpty for [T] {
+    fn is_empty(&self) -> bool {
+        <[T]>::is_empty(self)
+    }
+}
+
+impl<T> IsEmpty for Vec<T> {
+    fn is_empty(&self) -> bool {
+        Vec::is_empty(self)
+    }
+}
+
+impl<T> IsEmpty for VecDeque<T> {
+    fn is_empty(&self) -> bool {
+        VecDeque::is_empty(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> IsEmpty for HashMap<K, V, S> {
+    fn is_empty(&self) -> bool {
+        HashMap::is_empty(self)
+    }
+}
+
+impl<K, V> IsEmpty for BTreeMap<K, V> {
+    fn is_empty(&self) -> bool {
+        BTreeMap::is_empty(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> IsEmpty for HashSet<T, S> {
+    fn is_empty(&self) -> bool {
+        HashSet::is_empty(self)
+    }
+}
+
+impl<T> IsEmpty for BTreeSet<T> {
+    fn is_empty(&self) -> bool {
+        BTreeSet::is_empty(self)
+    }
+}
+
+impl<T> IsEmpty for &T
+where
+    T: IsEmpty + ?Sized,
+{
+    fn is_empty(&self) -> bool {
+        T::is_empty(self)
+    }
+}
+
+/// Tells whether the `Option` is `None` or holds an empty string or collection (see
+/// [`IsEmpty`]).
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct MyStruct {
+///     #[serde(skip_serializing_if = "is_none_or_empty")]
+///     nickname: Option<String>,
+///     #[serde(skip_serializing_if = "is_none_or_empty")]
+///     ids: Option<Vec<u32>>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { nickname: None, ids: Some(vec![]) };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), "{}");
+///
+///     let a = MyStruct { nickname: Some("a".to_owned()), ids: Some(vec![1]) };
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"nickname":"a","ids":[1]}"#);
+/// }
+/// ```
+pub fn is_none_or_empty<T>(value: &Option<T>) -> bool
+where
+    T: IsEmpty,
+{
+    match value {
+        Some(value) => value.is_empty(),
+        None => true,
+    }
+}
@@ -0,0 +1,550 @@
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::fmt;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, MapAccess, Visitor};
+use serde::ser::{self, Serialize, Serializer};
+
+/// Gets the serialization names for structs and enums. The aliases (`#[serde(alias)]`) are
+/// listed too, right after the names they belong to, since serde's derive reports them so.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct AnotherStruct {
+///     #[serde(rename = "a3")]
+///     aaa: Option<i64>,
+///     #[serde(rename = "b3")]
+///     bbb: i128,
+///     #[serde(rename = "c3")]
+///     ccc: u128,
+/// }
+/// let fields = serde_introspect::<AnotherStruct>();
+/// assert_eq!(fields[0], "a3");
+/// assert_eq!(fields[1], "b3");
+/// assert_eq!(fields[2], "c3");
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// enum SomeEnum {
+///     #[serde(rename = "a")]
+///     EnumA,
+///     #[serde(rename = "b")]
+///     EnumB,
+/// }
+/// let variants = serde_introspect::<SomeEnum>();
+/// assert_eq!(variants[0], "a");
+/// assert_eq!(variants[1], "b");
+/// ```
+pub fn serde_introspect<'de, T>() -> &'static [&'static str]
+where
+    T: Deserialize<'de>,
+{
+    let mut info = None;
+    let _ = T::deserialize(NameDeserializer { info: &mut info });
+    info.map_or(&[], |(_, names)| names)
+}
+
+/// The information about a struct as serde sees it, see [`serde_struct_info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerdeStructInfo {
+    /// The name of the struct, after `#[serde(rename)]`.
+    pub name: &'static str,
+    /// The fields in the order of their declaration.
+    pub fields: Vec<SerdeFieldInfo>,
+}
+
+impl SerdeStructInfo {
+    /// Returns the number of the fields.
+    pub fn field_count(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns the names of the fields which are not `Option`s.
+    pub fn required_field_names(&self) -> Vec<&'static str> {
+        self.fields
+            .iter()
+            .filter(|f| !f.is_option)
+            .map(|f| f.name)
+            .collect()
+    }
+}
+
+/// The information about a single field of a struct, see [`serde_struct_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerdeFieldInfo {
+    /// The name of the field, after `#[serde(rename)]`.
+    pub name: &'static str,
+    /// Whether the field is an `Option`, see the limitations in [`serde_struct_info`].
+    pub is_option: bool,
+}
+
+/// Gets the name of a struct, its fields and whether each of the fields is an `Option`, as serde
+/// sees them. Returns `None` if `T` is not deserialized from a struct.
+///
+/// # Limitations
+///
+/// The `Option` detection is best-effort: every field is fed to the struct alone and it is
+/// recorded whether the field's `Deserialize` asked for an optional value. Thus:
+///
+/// - The fields with `#[serde(deserialize_with)]` are reported as `Option`s only when the
+///   function deserializes an `Option` itself (not, for example, when it calls `deserialize_any`).
+/// - A field with `#[serde(default)]` is not an `Option`, even though it may be missing.
+/// - The aliases (`#[serde(alias)]`) are listed as the separate fields, see [`serde_introspect`].
+/// - The structs with `#[serde(flatten)]` fields are not deserialized as structs, so `None` is
+///   returned for them.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// #[serde(rename = "user")]
+/// struct User {
+///     id: u64,
+///     #[serde(rename = "fullName")]
+///     name: String,
+///     email: Option<String>,
+/// }
+///
+/// let info = serde_struct_info::<User>().unwrap();
+/// assert_eq!(info.name, "user");
+/// assert_eq!(info.field_count(), 3);
+/// assert_eq!(info.fields[1].name, "fullName");
+/// assert!(!info.fields[1].is_option);
+/// assert!(info.fields[2].is_option);
+/// assert_eq!(info.required_field_names(), vec!["id", "fullName"]);
+///
+/// assert!(serde_struct_info::<u64>().is_none());
+/// ```
+pub fn serde_struct_info<'de, T>() -> Option<SerdeStructInfo>
+where
+    T: Deserialize<'de>,
+{
+    let mut info = None;
+    let _ = T::deserialize(NameDeserializer { info: &mut info });
+    let (name, fields) = info?;
+    let fields = fields
+        .iter()
+        .map(|&field| {
+            let is_option = Cell::new(false);
+            let _ = T::deserialize(FieldProbeDeserializer {
+                field,
+                is_option: &is_option,
+            });
+            SerdeFieldInfo {
+                name: field,
+                is_option: is_option.get(),
+            }
+        })
+        .collect();
+    Some(SerdeStructInfo { name, fields })
+}
+
+/// Gets the names of the fields of a struct in the order serde serializes them for the given
+/// value, for generating the headers of a CSV file for example. Unlike [`serde_introspect`] this
+/// looks at the serialization, so the renames for serialization are applied and the fields
+/// skipped by `#[serde(skip_serializing)]` or by `#[serde(skip_serializing_if)]` *for this value*
+/// are left out. The values of the fields are not serialized. Returns an empty vector if `T` is
+/// not serialized as a struct.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, Debug)]
+/// struct Row {
+///     id: u64,
+///     #[serde(rename = "fullName")]
+///     name: String,
+///     #[serde(skip_serializing_if = "Option::is_none")]
+///     email: Option<String>,
+///     #[serde(skip_serializing)]
+///     password: String,
+/// }
+///
+/// let row = Row { id: 1, name: "Ann".to_owned(), email: None, password: "x".to_owned() };
+/// assert_eq!(serde_serialize_field_names(&row), vec!["id", "fullName"]);
+///
+/// let row = Row { email: Some("ann@example.com".to_owned()), ..row };
+/// assert_eq!(serde_serialize_field_names(&row), vec!["id", "fullName", "email"]);
+///
+/// assert!(serde_serialize_field_names(&42).is_empty());
+/// ```
+pub fn serde_serialize_field_names<T>(value: &T) -> Vec<&'static str>
+where
+    T: Serialize + ?Sized,
+{
+    let mut names = Vec::new();
+    let _ = value.serialize(FieldNamesSerializer { names: &mut names });
+    names
+}
+
+/// The error of the introspection deserializers and serializers, it is never shown to the users.
+#[derive(Debug)]
+struct IntrospectionError;
+
+impl fmt::Display for IntrospectionError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("introspection")
+    }
+}
+
+impl de::StdError for IntrospectionError {}
+
+impl de::Error for IntrospectionError {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        IntrospectionError
+    }
+}
+
+impl ser::Error for IntrospectionError {
+    fn custom<T: fmt::Display>(_msg: T) -> Self {
+        IntrospectionError
+    }
+}
+
+/// Records the name and the fields (or the variants) of the type and stops the deserialization.
+struct NameDeserializer<'a> {
+    info: &'a mut Option<(&'static str, &'static [&'static str])>,
+}
+
+impl<'de, 'a> Deserializer<'de> for NameDeserializer<'a> {
+    type Error = IntrospectionError;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(IntrospectionError)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        *self.info = Some((name, fields));
+        Err(IntrospectionError)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        *self.info = Some((name, variants));
+        Err(IntrospectionError)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map identifier ignored_any
+    }
+}
+
+/// Feeds a struct with a map containing the single `field` and records whether the value of the
+/// field is deserialized as an `Option`.
+struct FieldProbeDeserializer<'a> {
+    field: &'static str,
+    is_option: &'a Cell<bool>,
+}
+
+impl<'de, 'a> Deserializer<'de> for FieldProbeDeserializer<'a> {
+    type Error = IntrospectionError;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(IntrospectionError)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(FieldProbeMap {
+            field: Some(self.field),
+            is_option: self.is_option,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct FieldProbeMap<'a> {
+    field: Option<&'static str>,
+    is_option: &'a Cell<bool>,
+}
+
+impl<'de, 'a> MapAccess<'de> for FieldProbeMap<'a> {
+    type Error = IntrospectionError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.field.take() {
+            Some(field) => seed
+                .deserialize(de::value::BorrowedStrDeserializer::new(field))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(OptionProbeDeserializer {
+            is_option: self.is_option,
+        })
+    }
+}
+
+/// Records whether an `Option` is asked for and gives `None` then; fails otherwise.
+struct OptionProbeDeserializer<'a> {
+    is_option: &'a Cell<bool>,
+}
+
+impl<'de, 'a> Deserializer<'de> for OptionProbeDeserializer<'a> {
+    type Error = IntrospectionError;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(IntrospectionError)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.is_option.set(true);
+        visitor.visit_none()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Records the names of the fields of a struct without serializing their values, fails for
+/// everything else.
+struct FieldNamesSerializer<'a> {
+    names: &'a mut Vec<&'static str>,
+}
+
+impl<'a> ser::SerializeStruct for FieldNamesSerializer<'a> {
+    type Ok = ();
+    type Error = IntrospectionError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, _value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.names.push(key);
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<'a> Serializer for FieldNamesSerializer<'a> {
+    type Ok = ();
+    type Error = IntrospectionError;
+    type SerializeSeq = ser::Impossible<(), IntrospectionError>;
+    type SerializeTuple = ser::Impossible<(), IntrospectionError>;
+    type SerializeTupleStruct = ser::Impossible<(), IntrospectionError>;
+    type SerializeTupleVariant = ser::Impossible<(), IntrospectionError>;
+    type SerializeMap = ser::Impossible<(), IntrospectionError>;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = ser::Impossible<(), IntrospectionError>;
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(self)
+    }
+
+    fn serialize_bool(self, _v: bool) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_char(self, _v: char) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_none(self) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_unit(self) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(IntrospectionError)
+    }
+
+    fn collect_str<T>(self, _value: &T) -> Result<(), Self::Error>
+    where
+        T: fmt::Display + ?Sized,
+    {
+        Err(IntrospectionError)
+    }
+}
@@ -0,0 +1,224 @@
+/// Defines the helpers of this crate returning a single value wrapped into `$pointer`, which
+/// has to be in scope and have a `new` function. Add the new helpers to the list below.
+macro_rules! smart_pointer_helpers {
+    ($pointer:ident) => {
+        smart_pointer_helpers! {
+            @helpers $pointer;
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_datetime_utc_from_seconds<>
+                -> chrono::DateTime<chrono::Utc> [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_datetime_utc_from_milliseconds<>
+                -> chrono::DateTime<chrono::Utc> [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_datetime_utc_from_microseconds<>
+                -> chrono::DateTime<chrono::Utc> [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_datetime_utc_from_nanoseconds<>
+                -> chrono::DateTime<chrono::Utc> [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_datetime_utc_flexible<>
+                -> chrono::DateTime<chrono::Utc> [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_option_datetime_none_if_zero<>
+                -> Option<chrono::DateTime<chrono::Utc>> [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_option_datetime_none_if_zero_or_minus_one<>
+                -> Option<chrono::DateTime<chrono::Utc>> [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_option_datetime_utc_from_milliseconds<>
+                -> Option<chrono::DateTime<chrono::Utc>> [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_option_datetime_utc_from_milliseconds_none_if_zero<>
+                -> Option<chrono::DateTime<chrono::Utc>> [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_naive_time_flexible<> -> chrono::NaiveTime [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_datetime_fixed_offset_flexible<>
+                -> chrono::DateTime<chrono::FixedOffset> [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_weekday_flexible<> -> chrono::Weekday [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_month_flexible<> -> chrono::Month [];
+            #[cfg(feature = "chrono")]
+            fn field_attributes::deserialize_naive_date_iso_extended<> -> chrono::NaiveDate [];
+            fn field_attributes::deserialize_number_from_string<T> -> T
+                [T: FromStr + Deserialize<'de>, <T as FromStr>::Err: Display];
+            fn field_attributes::deserialize_option_number_from_string<T> -> Option<T>
+                [T: FromStr + Deserialize<'de>, <T as FromStr>::Err: Display];
+            fn field_attributes::deserialize_number_from_locale_string<T> -> T
+                [T: FromStr + Deserialize<'de>, <T as FromStr>::Err: Display];
+            fn field_attributes::deserialize_number_from_si_string<T> -> T
+                [T: FromStr + Deserialize<'de>, <T as FromStr>::Err: Display];
+            fn field_attributes::deserialize_number_from_underscored_string<T> -> T
+                [T: FromStr + Deserialize<'de>, <T as FromStr>::Err: Display];
+            fn field_attributes::deserialize_option_finite_f64<> -> Option<f64> [];
+            fn field_attributes::deserialize_finite_float<T> -> T
+                [
+                    T: FromStr + Deserialize<'de> + Copy + Into<f64> + Display,
+                    <T as FromStr>::Err: Display,
+                ];
+            fn field_attributes::deserialize_unwrap_envelope<T> -> T [T: Deserialize<'de>];
+            #[cfg(feature = "querystring")]
+            fn field_attributes::deserialize_from_query_string<T> -> T
+                [T: serde::de::DeserializeOwned];
+            fn field_attributes::deserialize_vec_null_elements_as_default<T> -> Vec<T>
+                [T: Deserialize<'de> + Default];
+            #[cfg(feature = "std")]
+            fn field_attributes::deserialize_vec_dedup<T> -> Vec<T>
+                [T: Deserialize<'de> + Eq + core::hash::Hash];
+            fn field_attributes::deserialize_sorted_vec<T> -> Vec<T> [T: Deserialize<'de> + Ord];
+            fn field_attributes::deserialize_string_matching<> -> String [];
+            fn field_attributes::deserialize_byte_vec_flexible<> -> Vec<u8> [];
+            fn field_attributes::deserialize_lines<> -> Vec<String> [];
+            fn field_attributes::deserialize_non_blank_lines<> -> Vec<String> [];
+            fn field_attributes::deserialize_bool_from_anything<> -> bool [];
+            fn field_attributes::deserialize_bool_from_yes_no<> -> bool [];
+            fn field_attributes::deserialize_bool_from_on_off<> -> bool [];
+            fn field_attributes::deserialize_string_from_number<> -> String [];
+            fn field_attributes::deserialize_option_string_from_number<> -> Option<String> [];
+            fn field_attributes::deserialize_option_string_from_literal_null<> -> Option<String> [];
+            fn field_attributes::deserialize_string_lossy<> -> String [];
+            #[cfg(feature = "unicode")]
+            fn field_attributes::deserialize_nfc_string<> -> String [];
+            #[cfg(feature = "unicode")]
+            fn field_attributes::deserialize_nfkc_string<> -> String [];
+            #[cfg(feature = "std")]
+            fn field_attributes::deserialize_os_string_flexible<> -> std::ffi::OsString [];
+            #[cfg(feature = "std")]
+            fn field_attributes::deserialize_path_buf_flexible<> -> std::path::PathBuf [];
+            fn field_attributes::deserialize_bytes_size_from_string<> -> u64 [];
+            fn field_attributes::deserialize_duration_composite<> -> core::time::Duration [];
+            #[cfg(feature = "std")]
+            fn field_attributes::deserialize_system_time_from_seconds<>
+                -> std::time::SystemTime [];
+            #[cfg(feature = "std")]
+            fn field_attributes::deserialize_system_time_from_milliseconds<>
+                -> std::time::SystemTime [];
+            fn field_attributes::deserialize_default_from_null<T> -> T
+                [T: Deserialize<'de> + Default];
+            fn field_attributes::deserialize_default_from_empty_object<T> -> T
+                [T: Deserialize<'de> + Default];
+            fn field_attributes::deserialize_option_none_if_empty_object<T> -> Option<T>
+                [T: Deserialize<'de>];
+            fn field_attributes::deserialize_ignore_any<T> -> T [T: Default];
+            fn field_attributes::deserialize_some<T> -> Option<T> [T: Deserialize<'de>];
+            fn field_attributes::deserialize_enum_from_repr_or_name<T> -> T
+                [T: crate::field_attributes::EnumFromRepr + Deserialize<'de>];
+            fn field_attributes::deserialize_enum_normalized<T> -> T [T: Deserialize<'de>];
+            fn field_attributes::deserialize_enum_with_fallback<T> -> T
+                [T: crate::field_attributes::EnumWithFallback + Deserialize<'de>];
+            fn field_attributes::deserialize_atomic_from_string_or_number<A> -> A
+                [
+                    A: crate::field_attributes::AtomicInteger,
+                    A::Value: FromStr + Deserialize<'de>,
+                    <A::Value as FromStr>::Err: Display,
+                ];
+            fn field_attributes::deserialize_non_empty_vec<T> -> Vec<T> [T: Deserialize<'de>];
+            fn field_attributes::deserialize_ratio_from_string<> -> f64 [];
+            fn field_attributes::deserialize_fraction_from_string<> -> (i64, i64) [];
+            fn field_attributes::deserialize_lat_lng_flexible<> -> (f64, f64) [];
+            fn field_attributes::deserialize_number_from_prefix_of_string<T> -> T
+                [T: FromStr + Deserialize<'de>, <T as FromStr>::Err: Display];
+            fn field_attributes::deserialize_integer_strict<T> -> T
+                [T: FromStr + Deserialize<'de>, <T as FromStr>::Err: Display];
+            fn field_attributes::deserialize_vec_from_string_or_vec<T> -> Vec<T>
+                [T: FromStr + Deserialize<'de>, <T as FromStr>::Err: Display];
+            #[cfg(feature = "ranges")]
+            fn field_attributes::deserialize_expand_ranges<T> -> Vec<T>
+                [T: core::convert::TryFrom<u64> + Deserialize<'de>];
+            fn field_attributes::deserialize_array_from_string_or_seq<T; const N> -> [T; N]
+                [T: FromStr + Deserialize<'de>, <T as FromStr>::Err: Display];
+            fn field_attributes::deserialize_vec_from_json_string_or_seq<T> -> Vec<T>
+                [T: serde::de::DeserializeOwned];
+            fn field_attributes::deserialize_vec_elements_from_strings<T> -> Vec<T>
+                [T: FromStr + Deserialize<'de>, <T as FromStr>::Err: Display];
+            fn container_attributes::deserialize_struct_case_insensitive<T> -> T
+                [T: serde::de::DeserializeOwned];
+            fn container_attributes::deserialize_struct_from_map_or_seq<T> -> T
+                [T: serde::de::DeserializeOwned];
+            fn container_attributes::deserialize_nested_from_dotted_keys<T> -> T
+                [T: serde::de::DeserializeOwned];
+            fn container_attributes::deserialize_permissive_numbers<T> -> T
+                [T: serde::de::DeserializeOwned];
+        }
+    };
+    (@helpers $pointer:ident; $(
+        $(#[$attr:meta])*
+        fn $module:ident::$name:ident<$($generic:ident),* $(; const $const:ident)?> -> $ty:ty
+            [$($bounds:tt)*];
+    )*) => {
+        #[allow(unused_imports)]
+        use serde::Deserialize;
+        #[allow(unused_imports)]
+        use alloc::string::String;
+        #[allow(unused_imports)]
+        use alloc::vec::Vec;
+        #[allow(unused_imports)]
+        use core::fmt::Display;
+        #[allow(unused_imports)]
+        use core::str::FromStr;
+
+        $(
+            $(#[$attr])*
+            /// Wraps the value given by the helper of the same name into the pointer.
+            pub fn $name<'de, D, $($generic,)* $(const $const: usize)?>(
+                deserializer: D,
+            ) -> Result<$pointer<$ty>, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+                $($bounds)*
+            {
+                crate::$module::$name(deserializer).map($pointer::new)
+            }
+        )*
+    };
+}
+
+/// Defines the serializers of this crate reading the value through `$cell`, either a `Cell`
+/// (which needs the value to be `Copy`) or a `RefCell`. Add the new serializers to the list below.
+macro_rules! cell_serializer_helpers {
+    ($cell:ident) => {
+        cell_serializer_helpers! {
+            @helpers $cell;
+            fn field_attributes::serialize_number_as_string<T> -> T [T: core::fmt::Display];
+            fn field_attributes::serialize_option_number_as_string<T> -> Option<T> [T: core::fmt::Display];
+            fn field_attributes::serialize_bool_as_int<> -> bool [];
+            fn field_attributes::serialize_bool_as_yes_no<> -> bool [];
+            fn field_attributes::serialize_bool_as_on_off<> -> bool [];
+            fn field_attributes::serialize_float_nan_as_null<> -> f64 [];
+            fn field_attributes::serialize_option_float_nan_as_null<> -> Option<f64> [];
+            fn field_attributes::serialize_enum_as_repr<T> -> T
+                [T: crate::field_attributes::EnumToRepr];
+        }
+    };
+    (@helpers Cell; $(
+        fn $module:ident::$name:ident<$($generic:ident),*> -> $ty:ty [$($bounds:tt)*];
+    )*) => {
+        $(
+            /// Serializes the value of the cell with the helper of the same name.
+            pub fn $name<S, $($generic),*>(value: &Cell<$ty>, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                $ty: Copy,
+                $($bounds)*
+            {
+                crate::$module::$name(&value.get(), serializer)
+            }
+        )*
+    };
+    (@helpers RefCell; $(
+        fn $module:ident::$name:ident<$($generic:ident),*> -> $ty:ty [$($bounds:tt)*];
+    )*) => {
+        $(
+            /// Serializes the value of the cell with the helper of the same name.
+            pub fn $name<S, $($generic),*>(value: &RefCell<$ty>, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+                $($bounds)*
+            {
+                crate::$module::$name(&*value.borrow(), serializer)
+            }
+        )*
+    };
+}
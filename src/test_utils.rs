@@ -0,0 +1,158 @@
+use alloc::string::ToString;
+use core::fmt::Debug;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Serializes the value to JSON, checks that it gives `expected_json` and that deserializing the
+/// result gives the value back. The JSON documents are compared as values, so the whitespace in
+/// `expected_json` does not matter (the order of the keys does, as `serde_json` keeps it only
+/// with its `preserve_order` feature).
+///
+/// # Panics
+///
+/// Panics with the details if any of the steps fails or gives something unexpected.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::test_utils::assert_roundtrip;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::number_as_string")]
+///     id: u64,
+/// }
+///
+/// assert_roundtrip(&MyStruct { id: 42 }, r#" { "id": "42" } "#);
+/// ```
+pub fn assert_roundtrip<T>(value: &T, expected_json: &str)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json = serde_json::to_string(value)
+        .unwrap_or_else(|e| panic!("failed to serialize {:?}: {}", value, e));
+    let actual: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let expected: serde_json::Value = serde_json::from_str(expected_json)
+        .unwrap_or_else(|e| panic!("invalid expected JSON {:?}: {}", expected_json, e));
+    assert_eq!(actual, expected, "unexpected serialization of {:?}", value);
+    assert_de(&json, value);
+}
+
+/// Deserializes `json` and checks that it gives the `expected` value.
+///
+/// # Panics
+///
+/// Panics with the details if the deserialization fails or gives another value.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use serde_aux::test_utils::assert_de;
+///
+/// #[derive(serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_number_from_string")]
+///     id: u64,
+/// }
+///
+/// for json in &[r#"{"id":"1"}"#, r#"{"id":1}"#] {
+///     assert_de(json, &MyStruct { id: 1 });
+/// }
+/// ```
+pub fn assert_de<T>(json: &str, expected: &T)
+where
+    T: DeserializeOwned + PartialEq + Debug,
+{
+    let actual = serde_json::from_str::<T>(json)
+        .unwrap_or_else(|e| panic!("failed to deserialize {:?}: {}", json, e));
+    assert_eq!(
+        &actual, expected,
+        "unexpected deserialization of {:?}",
+        json
+    );
+}
+
+/// Deserializes `json` and checks that it fails with an error containing `expected_substring`.
+///
+/// # Panics
+///
+/// Panics with the details if the deserialization succeeds or fails with another error.
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+/// use serde_aux::test_utils::assert_de_error;
+///
+/// #[derive(serde::Deserialize, Debug)]
+/// struct MyStruct {
+///     #[serde(deserialize_with = "deserialize_number_from_string")]
+///     id: u8,
+/// }
+///
+/// assert_de_error::<MyStruct>(r#"{"id":"300"}"#, "out of range for u8");
+/// assert_de_error::<MyStruct>(r#"{"id":"a"}"#, "invalid digit");
+/// ```
+pub fn assert_de_error<T>(json: &str, expected_substring: &str)
+where
+    T: DeserializeOwned + Debug,
+{
+    match serde_json::from_str::<T>(json) {
+        Ok(value) => panic!(
+            "expected an error containing {:?} for {:?}, got {:?}",
+            expected_substring, json, value
+        ),
+        Err(e) => assert!(
+            e.to_string().contains(expected_substring),
+            "expected an error containing {:?} for {:?}, got {:?}",
+            expected_substring,
+            json,
+            e.to_string()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct MyStruct {
+        #[serde(with = "crate::with::bool_as_int")]
+        enabled: bool,
+    }
+
+    #[test]
+    fn test_assert_roundtrip() {
+        assert_roundtrip(&MyStruct { enabled: true }, r#"{ "enabled": 1 }"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected serialization")]
+    fn test_assert_roundtrip_fails_on_other_json() {
+        assert_roundtrip(&MyStruct { enabled: true }, r#"{ "enabled": true }"#);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected an error containing")]
+    fn test_assert_de_error_fails_on_success() {
+        assert_de_error::<MyStruct>(r#"{ "enabled": "1" }"#, "invalid");
+    }
+
+    #[test]
+    fn test_assert_de_and_de_error() {
+        assert_de(r#"{ "enabled": "0" }"#, &MyStruct { enabled: false });
+        assert_de_error::<MyStruct>(r#"{ "enabled": 2 }"#, "neither 1 nor 0");
+
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Numbers {
+            #[serde(deserialize_with = "deserialize_number_from_underscored_string")]
+            value: u32,
+        }
+        assert_de(r#"{ "value": "1_000" }"#, &Numbers { value: 1000 });
+        assert_de_error::<Numbers>(r#"{ "value": "1__000" }"#, "underscores");
+    }
+}
@@ -0,0 +1,597 @@
+/// Serializes a number as a string and deserializes it from either a string or a number. See
+/// [`serialize_number_as_string`](crate::field_attributes::serialize_number_as_string) and
+/// [`deserialize_number_from_string`](crate::field_attributes::deserialize_number_from_string).
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::number_as_string")]
+///     id: u64,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { id: 9007199254740993 };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"id":"9007199254740993"}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+///
+///     let a: MyStruct = serde_json::from_str(r#"{"id":42}"#).unwrap();
+///     assert_eq!(a.id, 42);
+/// }
+/// ```
+pub mod number_as_string {
+    pub use crate::field_attributes::deserialize_number_from_string as deserialize;
+    pub use crate::field_attributes::serialize_number_as_string as serialize;
+}
+
+/// Serializes an optional number as a string or `null` and deserializes it from a string, a
+/// number or `null`. See
+/// [`serialize_option_number_as_string`](crate::field_attributes::serialize_option_number_as_string)
+/// and
+/// [`deserialize_option_number_from_string`](crate::field_attributes::deserialize_option_number_from_string).
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(
+///         default,
+///         with = "serde_aux::with::option_number_as_string",
+///         skip_serializing_if = "Option::is_none"
+///     )]
+///     id: Option<u64>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { id: Some(9007199254740993) };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"id":"9007199254740993"}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+///
+///     let a = MyStruct { id: None };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, "{}");
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+pub mod option_number_as_string {
+    pub use crate::field_attributes::deserialize_option_number_from_string as deserialize;
+    pub use crate::field_attributes::serialize_option_number_as_string as serialize;
+}
+
+/// Serializes a boolean as `1` or `0` and deserializes it from anything. See
+/// [`serialize_bool_as_int`](crate::field_attributes::serialize_bool_as_int) and
+/// [`deserialize_bool_from_anything`](crate::field_attributes::deserialize_bool_from_anything).
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::bool_as_int")]
+///     enabled: bool,
+/// }
+///
+/// fn main() {
+///     for &enabled in &[true, false] {
+///         let a = MyStruct { enabled };
+///         let s = serde_json::to_string(&a).unwrap();
+///         assert_eq!(s, format!(r#"{{"enabled":{}}}"#, enabled as u8));
+///         assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+///     }
+///
+///     let a: MyStruct = serde_json::from_str(r#"{"enabled":"true"}"#).unwrap();
+///     assert!(a.enabled);
+/// }
+/// ```
+pub mod bool_as_int {
+    pub use crate::field_attributes::deserialize_bool_from_anything as deserialize;
+    pub use crate::field_attributes::serialize_bool_as_int as serialize;
+}
+
+/// Writes a boolean as **"yes"**/**"no"** and reads it back. See
+/// [`serialize_bool_as_yes_no`](crate::field_attributes::serialize_bool_as_yes_no) and
+/// [`deserialize_bool_from_yes_no`](crate::field_attributes::deserialize_bool_from_yes_no).
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::bool_yes_no")]
+///     subscribed: bool,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { subscribed: true };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"subscribed":"yes"}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+pub mod bool_yes_no {
+    pub use crate::field_attributes::deserialize_bool_from_yes_no as deserialize;
+    pub use crate::field_attributes::serialize_bool_as_yes_no as serialize;
+}
+
+/// Writes a boolean as **"on"**/**"off"** and reads it back. See
+/// [`serialize_bool_as_on_off`](crate::field_attributes::serialize_bool_as_on_off) and
+/// [`deserialize_bool_from_on_off`](crate::field_attributes::deserialize_bool_from_on_off).
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::bool_on_off")]
+///     light: bool,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { light: false };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"light":"off"}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+pub mod bool_on_off {
+    pub use crate::field_attributes::deserialize_bool_from_on_off as deserialize;
+    pub use crate::field_attributes::serialize_bool_as_on_off as serialize;
+}
+
+/// Serializes a vector as a string with the comma separated values and deserializes it from
+/// either such a string or a sequence. See
+/// [`serialize_vec_as_comma_string`](crate::field_attributes::serialize_vec_as_comma_string) and
+/// [`deserialize_vec_from_string_or_vec`](crate::field_attributes::deserialize_vec_from_string_or_vec).
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::vec_as_comma_string")]
+///     list: Vec<String>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { list: vec!["a".to_owned(), "b".to_owned()] };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"list":"a,b"}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+///
+///     let a = MyStruct { list: vec![] };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+///
+///     let a: MyStruct = serde_json::from_str(r#"{"list":["c"]}"#).unwrap();
+///     assert_eq!(a.list, vec!["c"]);
+/// }
+/// ```
+pub mod vec_as_comma_string {
+    pub use crate::field_attributes::deserialize_vec_from_string_or_vec as deserialize;
+    pub use crate::field_attributes::serialize_vec_as_comma_string as serialize;
+}
+
+/// Tells a missing field from an explicit `null` using an `Option<Option<T>>`. See
+/// [`serialize_double_option`](crate::field_attributes::serialize_double_option) and
+/// [`deserialize_some`](crate::field_attributes::deserialize_some). The field needs
+/// `#[serde(default)]` for reading a missing field and
+/// `#[serde(skip_serializing_if = "Option::is_none")]` for omitting it when writing.
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(
+///         default,
+///         with = "serde_aux::with::double_option",
+///         skip_serializing_if = "Option::is_none"
+///     )]
+///     name: Option<Option<String>>,
+/// }
+///
+/// fn main() {
+///     for (value, json) in vec![
+///         (None, r#"{}"#),
+///         (Some(None), r#"{"name":null}"#),
+///         (Some(Some("foo".to_owned())), r#"{"name":"foo"}"#),
+///     ] {
+///         let a = MyStruct { name: value };
+///         assert_eq!(serde_json::to_string(&a).unwrap(), json);
+///         assert_eq!(serde_json::from_str::<MyStruct>(json).unwrap(), a);
+///     }
+/// }
+/// ```
+pub mod double_option {
+    pub use crate::field_attributes::deserialize_some as deserialize;
+    pub use crate::field_attributes::serialize_double_option as serialize;
+}
+
+/// Serializes a `std::time::SystemTime` as a seconds time stamp and deserializes it from a number
+/// or a numeric string. See
+/// [`serialize_system_time_as_seconds`](crate::field_attributes::serialize_system_time_as_seconds)
+/// and
+/// [`deserialize_system_time_from_seconds`](crate::field_attributes::deserialize_system_time_from_seconds).
+///
+/// # Example:
+///
+/// ```rust
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::system_time_seconds")]
+///     time: SystemTime,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { time: UNIX_EPOCH - Duration::from_secs(60) };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"time":-60}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub mod system_time_seconds {
+    pub use crate::field_attributes::deserialize_system_time_from_seconds as deserialize;
+    pub use crate::field_attributes::serialize_system_time_as_seconds as serialize;
+}
+
+/// Reads the unknown variant names of an enum into its catch-all variant and writes them back as
+/// they were. See
+/// [`serialize_enum_with_fallback`](crate::field_attributes::serialize_enum_with_fallback) and
+/// [`deserialize_enum_with_fallback`](crate::field_attributes::deserialize_enum_with_fallback).
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// enum Color {
+///     Red,
+///     Other(String),
+/// }
+///
+/// impl EnumWithFallback for Color {
+///     fn from_unknown(name: String) -> Self {
+///         Color::Other(name)
+///     }
+///
+///     fn as_unknown(&self) -> Option<&str> {
+///         match self {
+///             Color::Other(name) => Some(name),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::enum_with_fallback")]
+///     color: Color,
+/// }
+///
+/// fn main() {
+///     for s in &[r#"{"color":"Red"}"#, r#"{"color":"Teal"}"#] {
+///         let a: MyStruct = serde_json::from_str(s).unwrap();
+///         assert_eq!(&serde_json::to_string(&a).unwrap(), s);
+///     }
+/// }
+/// ```
+pub mod enum_with_fallback {
+    pub use crate::field_attributes::deserialize_enum_with_fallback as deserialize;
+    pub use crate::field_attributes::serialize_enum_with_fallback as serialize;
+}
+
+/// Writes an enum as its discriminant and reads it from either the discriminant or a variant
+/// name. See
+/// [`serialize_enum_as_repr`](crate::field_attributes::serialize_enum_as_repr) and
+/// [`deserialize_enum_from_repr_or_name`](crate::field_attributes::deserialize_enum_from_repr_or_name).
+///
+/// # Example:
+///
+/// ```rust
+/// use serde_aux::prelude::*;
+///
+/// #[derive(serde::Deserialize, Debug, PartialEq, Clone, Copy)]
+/// #[serde(rename_all = "lowercase")]
+/// enum Status {
+///     Inactive = 0,
+///     Active = 2,
+/// }
+///
+/// impl EnumFromRepr for Status {
+///     const DISCRIMINANTS: &'static [i64] = &[0, 2];
+///
+///     fn from_repr(repr: i64) -> Option<Self> {
+///         match repr {
+///             0 => Some(Status::Inactive),
+///             2 => Some(Status::Active),
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// impl EnumToRepr for Status {
+///     fn to_repr(&self) -> i64 {
+///         *self as i64
+///     }
+/// }
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::enum_repr")]
+///     status: Status,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#"{"status":"active"}"#).unwrap();
+///     assert_eq!(a.status, Status::Active);
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"status":2}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+pub mod enum_repr {
+    pub use crate::field_attributes::deserialize_enum_from_repr_or_name as deserialize;
+    pub use crate::field_attributes::serialize_enum_as_repr as serialize;
+}
+
+/// Unwraps a value from a single-key envelope like `{"data": ...}` and wraps it back. See
+/// [`serialize_wrap_envelope`](crate::field_attributes::serialize_wrap_envelope) and
+/// [`deserialize_unwrap_envelope`](crate::field_attributes::deserialize_unwrap_envelope).
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::envelope")]
+///     ids: Vec<u32>,
+/// }
+///
+/// fn main() {
+///     let a: MyStruct = serde_json::from_str(r#"{"ids":{"result":[1,2]}}"#).unwrap();
+///     assert_eq!(a.ids, vec![1, 2]);
+///     assert_eq!(serde_json::to_string(&a).unwrap(), r#"{"ids":{"data":[1,2]}}"#);
+/// }
+/// ```
+pub mod envelope {
+    pub use crate::field_attributes::deserialize_unwrap_envelope as deserialize;
+    pub use crate::field_attributes::serialize_wrap_envelope as serialize;
+}
+
+/// Writes every element of a sequence as a string and reads the elements back from the strings
+/// or the numbers. See
+/// [`serialize_vec_elements_as_strings`](crate::field_attributes::serialize_vec_elements_as_strings)
+/// and
+/// [`deserialize_vec_elements_from_strings`](crate::field_attributes::deserialize_vec_elements_from_strings).
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::vec_elements_as_strings")]
+///     ids: Vec<u64>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { ids: vec![u64::MAX, 2] };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"ids":["18446744073709551615","2"]}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+pub mod vec_elements_as_strings {
+    pub use crate::field_attributes::deserialize_vec_elements_from_strings as deserialize;
+    pub use crate::field_attributes::serialize_vec_elements_as_strings as serialize;
+}
+
+/// Serializes a `chrono::DateTime<Utc>` as a seconds time stamp and deserializes it from a number
+/// or a numeric string. See
+/// [`serialize_datetime_utc_as_seconds`](crate::field_attributes::serialize_datetime_utc_as_seconds)
+/// and
+/// [`deserialize_datetime_utc_from_seconds`](crate::field_attributes::deserialize_datetime_utc_from_seconds).
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::datetime_utc_seconds")]
+///     time: DateTime<Utc>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { time: Utc.timestamp_opt(1580887135, 0).unwrap() };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"time":1580887135}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub mod datetime_utc_seconds {
+    pub use crate::field_attributes::deserialize_datetime_utc_from_seconds as deserialize;
+    pub use crate::field_attributes::serialize_datetime_utc_as_seconds as serialize;
+}
+
+/// The same as [`datetime_utc_seconds`] but writes the time stamp as a string. See
+/// [`serialize_datetime_utc_as_seconds_string`](crate::field_attributes::serialize_datetime_utc_as_seconds_string).
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::datetime_utc_seconds_string")]
+///     time: DateTime<Utc>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { time: Utc.timestamp_opt(1580887135, 0).unwrap() };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"time":"1580887135"}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub mod datetime_utc_seconds_string {
+    pub use crate::field_attributes::deserialize_datetime_utc_from_seconds as deserialize;
+    pub use crate::field_attributes::serialize_datetime_utc_as_seconds_string as serialize;
+}
+
+/// Serializes a `chrono::DateTime<Utc>` as a milliseconds time stamp and deserializes it from a
+/// number or a numeric string. See
+/// [`serialize_datetime_utc_as_milliseconds`](crate::field_attributes::serialize_datetime_utc_as_milliseconds)
+/// and
+/// [`deserialize_datetime_utc_from_milliseconds`](crate::field_attributes::deserialize_datetime_utc_from_milliseconds).
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::datetime_utc_milliseconds")]
+///     time: DateTime<Utc>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { time: Utc.timestamp_opt(1519927261, 900_000_000).unwrap() };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"time":1519927261900}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub mod datetime_utc_milliseconds {
+    pub use crate::field_attributes::deserialize_datetime_utc_from_milliseconds as deserialize;
+    pub use crate::field_attributes::serialize_datetime_utc_as_milliseconds as serialize;
+}
+
+/// The same as [`datetime_utc_milliseconds`] but writes the time stamp as a string. See
+/// [`serialize_datetime_utc_as_milliseconds_string`](crate::field_attributes::serialize_datetime_utc_as_milliseconds_string).
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::datetime_utc_milliseconds_string")]
+///     time: DateTime<Utc>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { time: Utc.timestamp_opt(-1, 0).unwrap() };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"time":"-1000"}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub mod datetime_utc_milliseconds_string {
+    pub use crate::field_attributes::deserialize_datetime_utc_from_milliseconds as deserialize;
+    pub use crate::field_attributes::serialize_datetime_utc_as_milliseconds_string as serialize;
+}
+
+/// Serializes an `Option<chrono::DateTime<Utc>>` as a milliseconds time stamp or `null`, and
+/// deserializes it back. See
+/// [`serialize_option_datetime_as_milliseconds_null`](crate::field_attributes::serialize_option_datetime_as_milliseconds_null)
+/// and
+/// [`deserialize_option_datetime_utc_from_milliseconds`](crate::field_attributes::deserialize_option_datetime_utc_from_milliseconds).
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(default, with = "serde_aux::with::option_datetime_milliseconds_null")]
+///     time: Option<DateTime<Utc>>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { time: None };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"time":null}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub mod option_datetime_milliseconds_null {
+    pub use crate::field_attributes::deserialize_option_datetime_utc_from_milliseconds as deserialize;
+    pub use crate::field_attributes::serialize_option_datetime_as_milliseconds_null as serialize;
+}
+
+/// Serializes an `Option<chrono::DateTime<Utc>>` as a milliseconds time stamp or `0`, and
+/// deserializes it back taking `0` as `None`. See
+/// [`serialize_option_datetime_as_milliseconds_zero`](crate::field_attributes::serialize_option_datetime_as_milliseconds_zero)
+/// and
+/// [`deserialize_option_datetime_utc_from_milliseconds_none_if_zero`](crate::field_attributes::deserialize_option_datetime_utc_from_milliseconds_none_if_zero).
+///
+/// # Example:
+///
+/// ```rust
+/// use chrono::prelude::*;
+///
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::option_datetime_milliseconds_zero")]
+///     time: Option<DateTime<Utc>>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { time: None };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"time":0}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+#[cfg(feature = "chrono")]
+pub mod option_datetime_milliseconds_zero {
+    pub use crate::field_attributes::deserialize_option_datetime_utc_from_milliseconds_none_if_zero as deserialize;
+    pub use crate::field_attributes::serialize_option_datetime_as_milliseconds_zero as serialize;
+}
+
+/// Serializes an `Option<String>` writing `None` as the string `"null"`, and deserializes the
+/// string `"null"` (or a real `null`) back as `None`, for the consumers which require the
+/// literal string. An interoperability workaround only: a `Some("null")` is read back as
+/// `None`. See
+/// [`serialize_option_string_none_as_literal_null`](crate::field_attributes::serialize_option_string_none_as_literal_null)
+/// and
+/// [`deserialize_option_string_from_literal_null`](crate::field_attributes::deserialize_option_string_from_literal_null).
+///
+/// # Example:
+///
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+/// struct MyStruct {
+///     #[serde(with = "serde_aux::with::option_string_literal_null")]
+///     comment: Option<String>,
+/// }
+///
+/// fn main() {
+///     let a = MyStruct { comment: None };
+///     let s = serde_json::to_string(&a).unwrap();
+///     assert_eq!(s, r#"{"comment":"null"}"#);
+///     assert_eq!(serde_json::from_str::<MyStruct>(&s).unwrap(), a);
+/// }
+/// ```
+pub mod option_string_literal_null {
+    pub use crate::field_attributes::deserialize_option_string_from_literal_null as deserialize;
+    pub use crate::field_attributes::serialize_option_string_none_as_literal_null as serialize;
+}